@@ -0,0 +1,100 @@
+//! Time spent turning matches into occurrences and tags on a large generated Go file,
+//! shaped like the mocks of go-mockgen, where every type has dozens of methods and every
+//! method a handful of locals.
+//!
+//! Run with `cargo bench --bench occurrences`.
+
+use std::fmt::Write;
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use scip_semantic::{
+    document::parse_document,
+    languages::go_configuration,
+    locals::get_locals,
+    matches::{get_tag_entries, get_tags},
+};
+
+/// About 12KB per store, so 250 stores are as large as the 3MB file this was tuned on.
+const STORES: usize = 250;
+const METHODS: usize = 12;
+
+fn mocks(stores: usize) -> String {
+    let mut source = String::from("package database\n\nimport \"context\"\n\n");
+    for store in 0..stores {
+        writeln!(source, "type MockStore{store} struct {{").unwrap();
+        for method in 0..METHODS {
+            writeln!(
+                source,
+                "\tMethod{method}Func *Store{store}Method{method}Func"
+            )
+            .unwrap();
+        }
+        writeln!(source, "}}").unwrap();
+
+        for method in 0..METHODS {
+            let func = format!("Store{store}Method{method}Func");
+            write!(
+                source,
+                "type {func} struct {{
+\tdefaultHook func(context.Context, int) (int, error)
+\thooks []func(context.Context, int) (int, error)
+\thistory []{func}Call
+}}
+func (m *MockStore{store}) Method{method}(v0 context.Context, v1 int) (int, error) {{
+\tr0, r1 := m.Method{method}Func.nextHook()(v0, v1)
+\tm.Method{method}Func.appendCall({func}Call{{v0, v1, r0, r1}})
+\treturn r0, r1
+}}
+func (f *{func}) nextHook() func(context.Context, int) (int, error) {{
+\tif len(f.hooks) == 0 {{
+\t\treturn f.defaultHook
+\t}}
+\thook := f.hooks[0]
+\tf.hooks = f.hooks[1:]
+\treturn hook
+}}
+type {func}Call struct {{
+\tArg0 context.Context
+\tArg1 int
+\tResult0 int
+\tResult1 error
+}}
+func (c {func}Call) Args() []interface{{}} {{
+\treturn []interface{{}}{{c.Arg0, c.Arg1}}
+}}
+"
+            )
+            .unwrap();
+        }
+    }
+
+    source
+}
+
+fn occurrences(c: &mut Criterion) {
+    let source = mocks(STORES);
+    let mut config = go_configuration();
+    let tree = config.parser.parse(source.as_bytes(), None).unwrap();
+    let tags = config.tags.as_ref().unwrap();
+    let locals = config.locals.as_ref().unwrap();
+
+    let mut group = c.benchmark_group("mocks");
+    group.sample_size(10);
+    group.throughput(Throughput::Bytes(source.len() as u64));
+    group.bench_function("tags", |b| {
+        b.iter(|| get_tags(tags, &tree, source.as_bytes()).unwrap())
+    });
+    group.bench_function("tag_entries", |b| {
+        b.iter(|| get_tag_entries(tags, &tree, source.as_bytes()).unwrap())
+    });
+    group.bench_function("locals", |b| {
+        b.iter(|| get_locals(locals, &tree, source.as_bytes()).unwrap())
+    });
+    group.bench_function("document", |b| {
+        b.iter(|| parse_document(&mut config, &source).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, occurrences);
+criterion_main!(benches);
@@ -55,12 +55,59 @@
 //  export const TypeParameter = 26;
 // }
 
-use scip::types::{Descriptor, Document};
+use std::io::Write;
 
-#[derive(Debug)]
+use scip::types::{descriptor::Suffix, Descriptor};
+
+/// What a tag defines, from the `@definition.<kind>` capture of its `scip-tags` pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TagKind {
+    Namespace,
+    Package,
+    Module,
     Function,
-    Class,
+    Method,
+    Type,
+    Struct,
+    Interface,
+    Trait,
+    Implementation,
+    Enum,
+}
+
+impl TagKind {
+    /// The name universal-ctags gives this kind in `language`, which is what
+    /// consumers of ctags output, like zoekt, expect.
+    pub fn ctags_name(self, language: &str) -> &'static str {
+        match (language, self) {
+            ("go", TagKind::Function | TagKind::Method) => "func",
+            ("rust", TagKind::Trait) => "interface",
+            ("rust", TagKind::Type) => "typedef",
+            (_, TagKind::Namespace) => "namespace",
+            (_, TagKind::Package) => "package",
+            (_, TagKind::Module) => "module",
+            (_, TagKind::Function) => "function",
+            (_, TagKind::Method) => "method",
+            (_, TagKind::Type) => "type",
+            (_, TagKind::Struct) => "struct",
+            (_, TagKind::Interface) => "interface",
+            (_, TagKind::Trait) => "trait",
+            (_, TagKind::Implementation) => "implementation",
+            (_, TagKind::Enum) => "enum",
+        }
+    }
+
+    /// The kind of a descriptor without a tag of its own, like the receiver of a
+    /// Go method defined in another file.
+    pub fn implied(suffix: Suffix, language: &str) -> Self {
+        match (suffix, language) {
+            (Suffix::Namespace, "go") => TagKind::Package,
+            (Suffix::Namespace, "rust") => TagKind::Module,
+            (Suffix::Namespace, _) => TagKind::Namespace,
+            (Suffix::Method, _) => TagKind::Function,
+            _ => TagKind::Type,
+        }
+    }
 }
 
 impl std::str::FromStr for TagKind {
@@ -68,25 +115,141 @@ impl std::str::FromStr for TagKind {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
+            "definition.namespace" => Ok(Self::Namespace),
+            "definition.package" => Ok(Self::Package),
+            "definition.module" => Ok(Self::Module),
             "definition.function" => Ok(Self::Function),
-            "definition.type" => Ok(Self::Function),
+            "definition.method" => Ok(Self::Method),
+            "definition.type" => Ok(Self::Type),
+            "definition.struct" => Ok(Self::Struct),
+            "definition.interface" => Ok(Self::Interface),
+            "definition.trait" => Ok(Self::Trait),
+            "definition.implementation" => Ok(Self::Implementation),
+            "definition.enum" => Ok(Self::Enum),
             _ => anyhow::bail!("unknown tag kind: {}", s),
         }
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct TagEntry {
+    /// Every descriptor of the symbol, the last one names this tag
     pub descriptors: Vec<Descriptor>,
     pub kind: TagKind,
+    /// The tag defining every descriptor but the last, when it's in the same file
     pub parent: Option<Box<TagEntry>>,
 
+    /// 1-based, like ctags
     pub line: usize,
     // pub column: usize,
+    /// The parameters of a function, on a single line
+    pub signature: Option<String>,
 }
 
 impl TagEntry {
-    pub fn from_document(document: Document) -> Vec<TagEntry> {
-        todo!("{:?}", document)
+    pub fn name(&self) -> &str {
+        self.descriptors
+            .last()
+            .map(|descriptor| descriptor.name.as_str())
+            .unwrap_or_default()
+    }
+
+    /// The name and kind of the parent, implied by the descriptors when the parent
+    /// isn't in the same file.
+    pub fn parent_name_and_kind(&self, language: &str) -> Option<(&str, TagKind)> {
+        match &self.parent {
+            Some(parent) => Some((parent.name(), parent.kind)),
+            None => self.descriptors.iter().rev().nth(1).map(|descriptor| {
+                (
+                    descriptor.name.as_str(),
+                    TagKind::implied(descriptor.suffix.enum_value_or_default(), language),
+                )
+            }),
+        }
+    }
+
+    /// The kind of the parent and the names of every tag it's nested in, outermost first,
+    /// like universal-ctags writes the scope of a tag: `implementation:Point` for a
+    /// method in `impl Point`, `module:foo::bar` for an item of `mod bar` in `mod foo`.
+    pub fn scope(&self, language: &str) -> Option<(TagKind, String)> {
+        let separator = if language == "rust" { "::" } else { "." };
+        let Some(parent) = &self.parent else {
+            let (_, kind) = self.parent_name_and_kind(language)?;
+            let scope = self.descriptors[..self.descriptors.len() - 1]
+                .iter()
+                .map(|descriptor| descriptor.name.as_str())
+                .collect::<Vec<_>>()
+                .join(separator);
+            return Some((kind, scope));
+        };
+
+        let mut names = vec![];
+        let mut ancestor = Some(parent);
+        while let Some(tag) = ancestor {
+            names.push(tag.name());
+            ancestor = tag.parent.as_ref();
+        }
+        names.reverse();
+
+        Some((parent.kind, names.join(separator)))
+    }
+
+    /// This tag as a line of a `tags` file in the extended format, where `source_line`
+    /// is the line it's defined on, used as the search pattern.
+    pub fn tags_line(&self, path: &str, language: &str, source_line: &str) -> String {
+        let mut line = format!(
+            "{}\t{}\t/^{}$/;\"\tkind:{}\tline:{}",
+            self.name(),
+            path,
+            escape_pattern(source_line),
+            self.kind.ctags_name(language),
+            self.line
+        );
+
+        if let Some((kind, scope)) = self.scope(language) {
+            line.push_str(&format!("\t{}:{}", kind.ctags_name(language), scope));
+        }
+        if let Some(signature) = &self.signature {
+            line.push_str(&format!("\tsignature:{}", signature));
+        }
+
+        line
     }
 }
+
+/// Escapes a line for a `/^...$/` search pattern.
+fn escape_pattern(line: &str) -> String {
+    line.trim_end_matches('\r')
+        .replace('\\', "\\\\")
+        .replace('/', "\\/")
+}
+
+/// Writes a `tags` file: the `!_TAG_` pseudo-tags, then `lines` sorted by byte value,
+/// which is what `!_TAG_FILE_SORTED 1` promises editors doing a binary search.
+pub fn write_tags_file(
+    writer: &mut impl Write,
+    program: &str,
+    version: &str,
+    mut lines: Vec<String>,
+) -> std::io::Result<()> {
+    for (name, value, comment) in [
+        (
+            "!_TAG_FILE_FORMAT",
+            "2",
+            "extended format; --format=1 will not append ;\" to lines",
+        ),
+        ("!_TAG_FILE_SORTED", "1", "0=unsorted, 1=sorted, 2=foldcase"),
+        ("!_TAG_FILE_ENCODING", "utf-8", ""),
+        ("!_TAG_PROGRAM_NAME", program, ""),
+        ("!_TAG_PROGRAM_VERSION", version, ""),
+    ] {
+        writeln!(writer, "{}\t{}\t/{}/", name, value, comment)?;
+    }
+
+    lines.sort_unstable();
+    for line in lines {
+        writeln!(writer, "{}", line)?;
+    }
+
+    Ok(())
+}
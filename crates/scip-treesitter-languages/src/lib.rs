@@ -1,13 +1,20 @@
-use tree_sitter::Language;
+//! Every grammar is behind a cargo feature of the same name, see `Cargo.toml`.
 
 pub fn add(left: usize, right: usize) -> usize {
     left + right
 }
 
-pub fn rust() -> Language {
+#[cfg(feature = "rust")]
+pub fn rust() -> tree_sitter::Language {
     tree_sitter_rust::language()
 }
 
-pub fn go() -> Language {
+#[cfg(feature = "go")]
+pub fn go() -> tree_sitter::Language {
     tree_sitter_go::language()
 }
+
+#[cfg(feature = "c")]
+pub fn c() -> tree_sitter::Language {
+    tree_sitter_c::language()
+}
@@ -0,0 +1,63 @@
+;; Captures are named after SCIP SyntaxKinds, see `ts_scip::capture_name_to_syntax_kind`.
+;; When several patterns capture the same node, the first one in this file wins.
+
+(comment) @comment
+
+(interpreted_string_literal) @string
+(raw_string_literal) @string
+(escape_sequence) @string.escape
+(rune_literal) @character
+
+(int_literal) @number
+(float_literal) @number
+(imaginary_literal) @number
+
+(true) @boolean
+(false) @boolean
+(nil) @constant.null
+
+(package_identifier) @namespace
+
+(function_declaration name: (identifier) @function.definition)
+(method_declaration name: (field_identifier) @function.definition)
+
+(call_expression function: (identifier) @function)
+(call_expression
+  function: (selector_expression
+              field: (field_identifier) @function))
+
+(parameter_declaration name: (identifier) @variable.parameter)
+(variadic_parameter_declaration (identifier) @variable.parameter)
+
+((type_identifier) @type.builtin
+  (#match? @type.builtin "^(any|bool|byte|comparable|complex64|complex128|error|float32|float64|int|int8|int16|int32|int64|rune|string|uint|uint8|uint16|uint32|uint64|uintptr)$"))
+
+(type_identifier) @type
+
+[
+  "break"
+  "case"
+  "chan"
+  "const"
+  "continue"
+  "default"
+  "defer"
+  "else"
+  "fallthrough"
+  "for"
+  "func"
+  "go"
+  "goto"
+  "if"
+  "import"
+  "interface"
+  "map"
+  "package"
+  "range"
+  "return"
+  "select"
+  "struct"
+  "switch"
+  "type"
+  "var"
+] @keyword
@@ -0,0 +1,29 @@
+;; Raw strings tagged with a comment naming their language:
+;;
+;;   // language=sql
+;;   query := `SELECT name FROM users`
+;;
+;; The backticks are left out of the injected region.
+
+((comment) @injection.language
+ .
+ (short_var_declaration
+   right: (expression_list . (raw_string_literal) @injection.content))
+ (#match? @injection.language "language=")
+ (#offset! @injection.content 0 1 0 -1))
+
+((comment) @injection.language
+ .
+ (const_declaration
+   (const_spec
+     value: (expression_list . (raw_string_literal) @injection.content)))
+ (#match? @injection.language "language=")
+ (#offset! @injection.content 0 1 0 -1))
+
+((comment) @injection.language
+ .
+ (var_declaration
+   (var_spec
+     value: (expression_list . (raw_string_literal) @injection.content)))
+ (#match? @injection.language "language=")
+ (#offset! @injection.content 0 1 0 -1))
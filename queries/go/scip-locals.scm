@@ -12,7 +12,7 @@
 (block) @scope
 
 (short_var_declaration
-  left: (expression_list (identifier) @definition.term))
+  left: (expression_list (identifier) @definition.term)) @enclosing
 
 ;; TODO: We should talk about these: they could be params instead
 (parameter_declaration name: (identifier) @definition.term)
@@ -39,7 +39,7 @@
 (for_statement
  (range_clause
    left: (expression_list
-           (identifier) @definition.var)))
+           (identifier) @definition.var)) @enclosing)
 
 (const_declaration
  (const_spec
@@ -49,6 +49,28 @@
   (type_spec
     name: (type_identifier) @definition.type))
 
+;; Writes: assignment targets are written, and compound assignments and
+;; `++`/`--` read the previous value too. The plain `@reference` below matches
+;; the same identifiers, only the access roles are taken from these.
+(assignment_statement
+  left: (expression_list (identifier) @reference.write)
+  operator: "=")
+
+(assignment_statement
+  left: (expression_list (identifier) @reference.readwrite)
+  operator: ["+=" "-=" "*=" "/=" "%=" "&=" "|=" "^=" "<<=" ">>=" "&^="])
+
+(inc_statement (identifier) @reference.readwrite)
+(dec_statement (identifier) @reference.readwrite)
+
+;; Reads: values that are assigned, passed, returned or operated on
+(assignment_statement right: (expression_list (identifier) @reference.read))
+(short_var_declaration right: (expression_list (identifier) @reference.read))
+(argument_list (identifier) @reference.read)
+(binary_expression left: (identifier) @reference.read)
+(binary_expression right: (identifier) @reference.read)
+(return_statement (expression_list (identifier) @reference.read))
+
 ;; reference
 (identifier) @reference
 (type_identifier) @reference
@@ -78,8 +100,7 @@
 ;
 
 ;; TODO: These may not make much sense to have for locals... {{{
-((package_identifier) @reference
-  (set! reference.kind "namespace"))
+(package_identifier) @reference
 
 (package_clause
    (package_identifier) @definition.namespace)
@@ -1,18 +1,41 @@
-(source_file (package_clause (package_identifier) @descriptor.namespace) @scope)
+(source_file (package_clause (package_identifier) @descriptor.namespace) @scope @definition.package)
 
 (function_declaration
- name: (identifier) @descriptor.method)
+ name: (identifier) @descriptor.method) @definition.function
 
 (method_declaration
  receiver: (parameter_list
             (parameter_declaration
              type: (pointer_type
                      (type_identifier) @descriptor.type)))
- name: (field_identifier) @descriptor.method)
+ name: (field_identifier) @descriptor.method) @definition.method
 
 (method_declaration
   receiver: (parameter_list
                (parameter_declaration type: (type_identifier) @descriptor.type))
-  name: (field_identifier) @descriptor.method)
+  name: (field_identifier) @descriptor.method) @definition.method
 
-(type_declaration (type_spec name: (type_identifier) @descriptor.type))
+(type_declaration
+ (type_spec
+  name: (type_identifier) @descriptor.type
+  type: (struct_type))) @definition.struct
+
+(type_declaration
+ (type_spec
+  name: (type_identifier) @descriptor.type
+  type: (interface_type))) @definition.interface
+
+(type_declaration
+ (type_spec
+  name: (type_identifier) @descriptor.type
+  type: [
+    (array_type)
+    (channel_type)
+    (function_type)
+    (map_type)
+    (parenthesized_type)
+    (pointer_type)
+    (qualified_type)
+    (slice_type)
+    (type_identifier)
+  ])) @definition.type
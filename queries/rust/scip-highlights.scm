@@ -0,0 +1,79 @@
+;; Captures are named after SCIP SyntaxKinds, see `ts_scip::capture_name_to_syntax_kind`.
+;; When several patterns capture the same node, the first one in this file wins.
+
+(line_comment) @comment
+(block_comment) @comment
+
+(string_literal) @string
+(raw_string_literal) @string
+(escape_sequence) @string.escape
+(char_literal) @character
+
+(integer_literal) @number
+(float_literal) @number
+(boolean_literal) @boolean
+
+(attribute_item) @attribute
+(inner_attribute_item) @attribute
+
+(self) @variable.builtin
+
+(mod_item name: (identifier) @namespace)
+
+(function_item name: (identifier) @function.definition)
+(function_signature_item name: (identifier) @function.definition)
+(macro_definition name: (identifier) @macro.definition)
+
+(call_expression function: (identifier) @function)
+(call_expression
+  function: (field_expression
+              field: (field_identifier) @function))
+(call_expression
+  function: (scoped_identifier
+              name: (identifier) @function))
+(macro_invocation macro: (identifier) @macro)
+
+(parameter pattern: (identifier) @variable.parameter)
+
+(primitive_type) @type.builtin
+(type_identifier) @type
+
+(mutable_specifier) @keyword
+(crate) @keyword
+(super) @keyword
+
+[
+  "as"
+  "async"
+  "await"
+  "break"
+  "const"
+  "continue"
+  "default"
+  "dyn"
+  "else"
+  "enum"
+  "extern"
+  "fn"
+  "for"
+  "if"
+  "impl"
+  "in"
+  "let"
+  "loop"
+  "match"
+  "mod"
+  "move"
+  "pub"
+  "ref"
+  "return"
+  "static"
+  "struct"
+  "trait"
+  "type"
+  "union"
+  "unsafe"
+  "use"
+  "where"
+  "while"
+] @keyword
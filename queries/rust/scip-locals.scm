@@ -0,0 +1,69 @@
+(block) @scope
+(function_item) @scope
+(closure_expression) @scope
+(for_expression) @scope
+(match_arm) @scope
+
+;; Bindings introduced directly by a pattern
+(let_declaration
+  pattern: (identifier) @definition.var) @enclosing
+
+(parameter pattern: (identifier) @definition.term)
+(closure_parameters (identifier) @definition.term)
+
+(for_expression pattern: (identifier) @definition.var)
+
+;; A bare identifier in a match can also be a constant or a variant like `None`
+((match_pattern . (identifier) @definition.var)
+ (#match? @definition.var "^[a-z_]"))
+
+;; `if let` and `while let` bindings are only visible in the block they guard,
+;; not in the expression they destructure
+(if_expression
+  condition: (let_condition pattern: (_) @bindings)
+  consequence: (block) @scope)
+(if_expression
+  condition: (let_chain (let_condition pattern: (_) @bindings))
+  consequence: (block) @scope)
+(while_expression
+  condition: (let_condition pattern: (_) @bindings)
+  body: (block) @scope)
+
+(let_condition pattern: (identifier) @definition.var)
+
+;; Bindings nested in other patterns, which only appear where bindings are
+;; introduced. The constructor of `Some(x)` is the `type` of the
+;; `tuple_struct_pattern`, so only the identifiers after it are bindings.
+((tuple_struct_pattern type: (_) (identifier) @definition.var)
+ (#match? @definition.var "^[a-z_]"))
+((tuple_pattern (identifier) @definition.var)
+ (#match? @definition.var "^[a-z_]"))
+((slice_pattern (identifier) @definition.var)
+ (#match? @definition.var "^[a-z_]"))
+((or_pattern (identifier) @definition.var)
+ (#match? @definition.var "^[a-z_]"))
+(captured_pattern . (identifier) @definition.var)
+(ref_pattern (identifier) @definition.var)
+(mut_pattern (identifier) @definition.var)
+(reference_pattern (identifier) @definition.var)
+(field_pattern name: (shorthand_field_identifier) @definition.var)
+(field_pattern pattern: (identifier) @definition.var)
+
+;; Writes: assignment targets are written, compound assignments read the
+;; previous value too. The plain `@reference` below matches the same
+;; identifiers, only the access roles are taken from these.
+(assignment_expression left: (identifier) @reference.write)
+(compound_assignment_expr left: (identifier) @reference.readwrite)
+
+;; Reads: values that are assigned, passed, returned or operated on
+(let_declaration value: (identifier) @reference.read)
+(assignment_expression right: (identifier) @reference.read)
+(compound_assignment_expr right: (identifier) @reference.read)
+(arguments (identifier) @reference.read)
+(binary_expression left: (identifier) @reference.read)
+(binary_expression right: (identifier) @reference.read)
+(return_expression (identifier) @reference.read)
+(block (identifier) @reference.read .)
+
+;; reference
+(identifier) @reference
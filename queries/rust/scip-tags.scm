@@ -1,22 +1,26 @@
-;; TODO: Could do @scope.ignore to ignore this as a definition
-
 (mod_item
- name: (_) @descriptor.namespace) @scope
+ name: (_) @descriptor.namespace) @scope @definition.module
 
 (trait_item
- name: (_) @descriptor.type) @scope
+ name: (_) @descriptor.type) @scope @definition.trait
 
+;; Both inherent and trait impls are named after the implementing type, like
+;; universal-ctags does, so their methods are `Type#method().` Type arguments are
+;; left out, `impl<T> Trait<T> for Thing<T>` is `Thing#`.
 (impl_item
- trait: (_) @descriptor.type
- type: (_) @descriptor.type) @scope
+ type: [
+   (type_identifier) @descriptor.type
+   (scoped_type_identifier) @descriptor.type
+   (generic_type type: (_) @descriptor.type)
+ ]) @scope @definition.implementation
 
 ;; TODO: @local to stop traversal
 (function_signature_item
- name: (identifier) @descriptor.method)
+ name: (identifier) @descriptor.method) @definition.function
 
 ;; TODO: @local to stop traversal
 (function_item
- name: (identifier) @descriptor.method)
+ name: (identifier) @descriptor.method) @definition.function
 
 (struct_item
- name: (type_identifier) @descriptor.type) @scope
+ name: (type_identifier) @descriptor.type) @scope @definition.struct
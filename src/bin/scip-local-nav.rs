@@ -1,15 +1,24 @@
-use std::{fs, path::Path};
-
-use scip::{types::Document, write_message_to_file};
-use scip_semantic::{languages::LocalConfiguration, locals::parse_tree};
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use scip::types::Document;
+use scip_semantic::{languages::LocalConfiguration, locals::parse_tree, writer::IndexWriter};
 use walkdir::WalkDir;
 
-fn parse_files(config: &mut LocalConfiguration, root: &Path, dir: &Path) -> Vec<Document> {
+/// Writes each document as soon as it is parsed, rather than holding all of them
+fn parse_files<W: Write>(
+    config: &mut LocalConfiguration,
+    root: &Path,
+    dir: &Path,
+    writer: &mut IndexWriter<W>,
+) {
     // TODO: Filtr
 
     let extension = "go";
 
-    let mut documents = vec![];
     for entry in WalkDir::new(dir) {
         let entry = entry.unwrap();
         let entry = entry.path();
@@ -51,10 +60,8 @@ fn parse_files(config: &mut LocalConfiguration, root: &Path, dir: &Path) -> Vec<
         //     })
         //     .collect();
 
-        documents.push(doc);
+        writer.write_document(&doc).expect("to write the document");
     }
-
-    documents
 }
 
 fn main() {
@@ -63,27 +70,25 @@ fn main() {
     let directory = Path::new("/home/tjdevries/sourcegraph/sourcegraph.git/main/");
     // let extension = "go";
 
-    let mut index = scip::types::Index {
-        metadata: Some(scip::types::Metadata {
-            tool_info: Some(scip::types::ToolInfo {
-                name: "scip-local-nav".to_string(),
-                version: "0.0.1".to_string(),
-                arguments: vec![],
-                ..Default::default()
-            })
-            .into(),
-            project_root: "file://".to_string() + directory.to_str().unwrap(),
+    let metadata = scip::types::Metadata {
+        tool_info: Some(scip::types::ToolInfo {
+            name: "scip-local-nav".to_string(),
+            version: "0.0.1".to_string(),
+            arguments: vec![],
             ..Default::default()
         })
         .into(),
+        project_root: "file://".to_string() + directory.to_str().unwrap(),
         ..Default::default()
     };
 
+    let file = File::create(directory.join("index.scip")).expect("to create the file");
+    let mut writer =
+        IndexWriter::new(BufWriter::new(file), Some(&metadata)).expect("to write the metadata");
+
     let mut config = scip_semantic::languages::go_locals();
-    index
-        .documents
-        .extend(parse_files(&mut config, directory, directory));
+    parse_files(&mut config, directory, directory, &mut writer);
 
-    println!("{:?}", index.documents.len());
-    write_message_to_file(directory.join("index.scip"), index).expect("to write the file");
+    println!("{:?}", writer.documents());
+    writer.finish().expect("to write the file");
 }
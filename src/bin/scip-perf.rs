@@ -1,75 +1,224 @@
-use std::{path::Path, time::Instant};
-
-use scip_semantic::locals::parse_tree;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use protobuf::Message;
+use rustc_hash::FxHashMap as HashMap;
+use scip_semantic::{
+    detect::Detector,
+    document::{parse_document_timed, DocumentOptions},
+    languages::{load_language_configuration, LanguageConfiguration, QUERIES_ENV},
+    timings::{timed, Timings},
+};
+use serde_json::json;
 use walkdir::WalkDir;
 
-struct ParseTiming {
-    pub filepath: String,
-    pub duration: std::time::Duration,
+/// Measures how long each phase of indexing takes over a corpus
+#[derive(Parser)]
+#[command(name = "scip-perf")]
+struct Cli {
+    /// Files or directories to index
+    #[arg(required = true)]
+    corpus: Vec<PathBuf>,
+
+    /// See `scip-semantic --queries`
+    #[arg(long, env = QUERIES_ENV)]
+    queries: Option<PathBuf>,
+
+    /// Only index files detected as this language
+    #[arg(long)]
+    language: Option<String>,
+
+    /// How many of the slowest files to print
+    #[arg(long, default_value_t = 10)]
+    top: usize,
+
+    /// Write the report, and the timings of every file, as JSON
+    #[arg(long)]
+    json: Option<PathBuf>,
 }
 
-fn parse_files(dir: &Path) -> Vec<ParseTiming> {
-    let mut config = scip_semantic::languages::go_locals();
-    let extension = "go";
+struct FileTiming {
+    path: PathBuf,
+    language: String,
+    bytes: usize,
+    timings: Timings,
+}
 
-    let mut timings = vec![];
+fn corpus_files(corpus: &[PathBuf]) -> impl Iterator<Item = Result<PathBuf>> + '_ {
+    corpus.iter().flat_map(|root| {
+        WalkDir::new(root)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(|entry| match entry {
+                Ok(entry) if entry.file_type().is_file() => Some(Ok(entry.into_path())),
+                Ok(_) => None,
+                Err(err) => Some(Err(err.into())),
+            })
+    })
+}
 
-    for entry in WalkDir::new(dir) {
-        let entry = entry.unwrap();
-        let entry = entry.path();
+fn measure_file(
+    path: &Path,
+    cli: &Cli,
+    detector: &Detector,
+    configs: &mut HashMap<String, Option<LanguageConfiguration>>,
+) -> Result<Option<FileTiming>> {
+    let Ok(source) = fs::read_to_string(path) else {
+        return Ok(None);
+    };
+    let Some(detection) = detector.detect(path, &source) else {
+        return Ok(None);
+    };
+    if cli
+        .language
+        .as_ref()
+        .filter(|language| **language != detection.language)
+        .is_some()
+    {
+        return Ok(None);
+    }
 
-        match entry.extension() {
-            Some(ext) if extension == ext => {}
-            _ => continue,
-        }
+    if !configs.contains_key(&detection.language) {
+        let config = load_language_configuration(&detection.language, cli.queries.as_deref())?;
+        configs.insert(detection.language.clone(), config);
+    }
+    let Some(config) = configs
+        .get_mut(&detection.language)
+        .and_then(Option::as_mut)
+    else {
+        return Ok(None);
+    };
+
+    let mut timings = Timings::default();
+    let doc = parse_document_timed(config, &source, &DocumentOptions::default(), &mut timings)
+        .with_context(|| format!("indexing {}", path.display()))?
+        .document;
+    timed(&mut timings.serialize, || doc.write_to_bytes())?;
+
+    Ok(Some(FileTiming {
+        path: path.to_path_buf(),
+        language: detection.language,
+        bytes: source.len(),
+        timings,
+    }))
+}
 
-        let start = Instant::now();
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
 
-        let source = std::fs::read_to_string(entry).unwrap();
-        let source_bytes = source.as_bytes();
-        let tree = config.parser.parse(source_bytes, None).unwrap();
-        parse_tree(&mut config, &tree, source_bytes).unwrap();
+/// Nearest-rank percentile of sorted durations
+fn percentile(sorted: &[Duration], percent: usize) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
 
-        let finish = Instant::now();
+    let rank = (percent * sorted.len()).div_ceil(100);
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
 
-        timings.push(ParseTiming {
-            filepath: entry.file_stem().unwrap().to_string_lossy().to_string(),
-            duration: finish - start,
-        });
+fn phase_report(durations: &mut [Duration]) -> serde_json::Value {
+    durations.sort();
+    json!({
+        "total_ms": millis(durations.iter().sum()),
+        "p50_ms": millis(percentile(durations, 50)),
+        "p90_ms": millis(percentile(durations, 90)),
+        "p99_ms": millis(percentile(durations, 99)),
+        "max_ms": millis(durations.last().copied().unwrap_or_default()),
+    })
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let detector = Detector::new();
+    let mut configs = HashMap::default();
+
+    let mut files = vec![];
+    for path in corpus_files(&cli.corpus) {
+        if let Some(timing) = measure_file(&path?, &cli, &detector, &mut configs)? {
+            files.push(timing);
+        }
     }
 
-    timings
-}
+    let mut total = Timings::default();
+    for file in &files {
+        total += file.timings;
+    }
 
-fn measure_parsing() {
-    println!("Measuring parsing");
-    let start = Instant::now();
+    let names = Timings::PHASES.iter().chain(["total"].iter());
+    let mut phases = serde_json::Map::new();
+    for (idx, name) in names.clone().enumerate() {
+        let mut durations = files
+            .iter()
+            .map(|file| match file.timings.phases().get(idx) {
+                Some(duration) => *duration,
+                None => file.timings.total(),
+            })
+            .collect::<Vec<_>>();
+        phases.insert(name.to_string(), phase_report(&mut durations));
+    }
 
-    let root = Path::new(
-        // "/home/tjdevries/sourcegraph/sourcegraph.git/main/",
-        "/home/tjdevries/sourcegraph/sourcegraph.git/main/internal/database/mocks_temp.go",
-        // "/home/tjdevries/sourcegraph/scip-semantic/testdata/locals-nested.go",
-        // "/home/tjdevries/sourcegraph/scip-semantic/testdata/funcs.go",
-        // "/home/tjdevries/sourcegraph/scip-semantic/testdata/multi-scopes.go",
+    println!(
+        "{} files, {} bytes, {:.2}ms",
+        files.len(),
+        files.iter().map(|file| file.bytes).sum::<usize>(),
+        millis(total.total())
     );
-    let mut timings = parse_files(root);
-    timings.sort_by(|a, b| a.duration.cmp(&b.duration));
-    println!("Slowest files:");
-    for timing in timings.iter().rev().take(10) {
-        println!("{}: {:?}", timing.filepath, timing.duration);
+    println!(
+        "{:<10} {:>12} {:>10} {:>10} {:>10} {:>10}",
+        "phase", "total", "p50", "p90", "p99", "max"
+    );
+    for name in names {
+        let report = &phases[*name];
+        println!(
+            "{:<10} {:>10.2}ms {:>8.3}ms {:>8.3}ms {:>8.3}ms {:>8.3}ms",
+            name,
+            report["total_ms"].as_f64().unwrap_or_default(),
+            report["p50_ms"].as_f64().unwrap_or_default(),
+            report["p90_ms"].as_f64().unwrap_or_default(),
+            report["p99_ms"].as_f64().unwrap_or_default(),
+            report["max_ms"].as_f64().unwrap_or_default(),
+        );
     }
 
-    let finish = Instant::now();
-
-    println!("Done {:?}", finish - start);
-}
+    files.sort_by_key(|file| std::cmp::Reverse(file.timings.total()));
+    println!("\nSlowest files:");
+    for file in files.iter().take(cli.top) {
+        println!(
+            "{:>10.2}ms {}",
+            millis(file.timings.total()),
+            file.path.display()
+        );
+    }
 
-fn main() {
-    // TODO: parameterize
-    let measure = "parsing";
+    if let Some(output) = &cli.json {
+        let report = json!({
+            "files": files.len(),
+            "bytes": files.iter().map(|file| file.bytes).sum::<usize>(),
+            "phases": phases,
+            "timings": files
+                .iter()
+                .map(|file| {
+                    let mut timing = serde_json::Map::new();
+                    timing.insert("path".to_string(), json!(file.path.display().to_string()));
+                    timing.insert("language".to_string(), json!(file.language));
+                    timing.insert("bytes".to_string(), json!(file.bytes));
+                    for (name, duration) in Timings::PHASES.iter().zip(file.timings.phases()) {
+                        timing.insert(format!("{}_ms", name), json!(millis(duration)));
+                    }
+                    timing
+                })
+                .collect::<Vec<_>>(),
+        });
 
-    match measure {
-        "parsing" => measure_parsing(),
-        _ => panic!("Unknown measure: {}", measure),
+        fs::write(output, serde_json::to_string_pretty(&report)?)
+            .with_context(|| format!("writing {}", output.display()))?;
     }
+
+    Ok(())
 }
@@ -0,0 +1,522 @@
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::PathBuf,
+    str::FromStr,
+    time::Duration,
+};
+
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand};
+use protobuf::{Message, MessageField};
+use scip::{
+    types::{Document, Index, Metadata},
+    write_message_to_file,
+};
+use scip_semantic::{
+    cache::DocumentCache,
+    detect::Detector,
+    document::ParsedDocument,
+    expectations::run_expectations,
+    git::index_revision,
+    grammars::load_dynamic_language,
+    index::{index_documents, index_metadata, IndexOptions},
+    inspect::{annotate_document, source_root, write_summary},
+    json::write_index_json,
+    languages::QUERIES_ENV,
+    limits::{reasons_json, GeneratedPolicy, Limits},
+    lint::lint_queries,
+    lsif::write_lsif,
+    merge::merge_index,
+    snapshot::{run_snapshots, SnapshotOutcome},
+    tags_file::write_directory_tags,
+    writer::IndexWriter,
+    zoekt::directory_symbols,
+};
+
+#[derive(Parser)]
+#[command(name = "scip-semantic", version, about)]
+struct Cli {
+    /// Load queries from `<queries>/<lang>/*.scm` instead of the embedded ones,
+    /// for the queries present in the directory
+    #[arg(long, global = true, env = QUERIES_ENV)]
+    queries: Option<PathBuf>,
+
+    /// Load a grammar compiled as a shared library, as `<name>=<path>`.
+    /// Its queries are read from `<queries>/<name>/`.
+    #[arg(long, global = true, value_parser = parse_grammar)]
+    grammar: Vec<(String, PathBuf)>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Index every supported file under a directory
+    Index {
+        #[arg(default_value = ".")]
+        root: PathBuf,
+
+        #[arg(long, short, default_value = "index.scip")]
+        output: PathBuf,
+
+        /// Either scip (protobuf) or json. JSON is easier to read, but it is only
+        /// written once every file is indexed, so the whole index is held in memory.
+        #[arg(long, default_value = "scip")]
+        format: IndexFormat,
+
+        /// Where to write why files were skipped or only partly indexed, as JSON. Defaults
+        /// to the output with a `.report.json` extension.
+        #[arg(long)]
+        report: Option<PathBuf>,
+
+        /// Files over this many bytes are skipped, 0 for no limit
+        #[arg(long, default_value_t = 2 * 1024 * 1024)]
+        max_file_size: u64,
+
+        /// Files taking longer to parse are skipped, 0 for no limit
+        #[arg(long, default_value_t = 2000)]
+        parse_timeout_ms: u64,
+
+        /// Stop matching queries over a file after this many matches, keeping the occurrences
+        /// found until then, 0 for no limit
+        #[arg(long, default_value_t = 200_000)]
+        max_matches: usize,
+
+        /// What to do with generated files: skip, tags-only or full
+        #[arg(long, default_value = "tags-only")]
+        generated: GeneratedPolicy,
+
+        /// Force the language of files matching a glob, as `<glob>=<language>`
+        #[arg(long, value_parser = parse_override)]
+        language_override: Vec<(String, String)>,
+
+        /// Files indexed in parallel, 0 for one per CPU
+        #[arg(long, short, default_value_t = 0)]
+        jobs: usize,
+
+        /// Reuse the documents of unchanged files from this directory, and store new ones
+        #[arg(long)]
+        cache: Option<PathBuf>,
+
+        /// Remove cached documents that weren't used for this many days, 0 to keep them
+        #[arg(long, default_value_t = 30)]
+        cache_max_age_days: u64,
+
+        /// Index `root` as of this commit of its repository, read from the object database
+        /// rather than the working tree
+        #[arg(long)]
+        revision: Option<String>,
+
+        /// Only index the files added or modified since this revision, as a partial index
+        #[arg(long)]
+        since: Option<String>,
+    },
+
+    /// Add search-based occurrences to a precise index, wherever it has no coverage
+    Merge {
+        /// The index produced by a precise indexer. It is never overridden.
+        #[arg(long)]
+        precise: PathBuf,
+
+        /// The index produced by scip-semantic
+        #[arg(long)]
+        heuristic: PathBuf,
+
+        #[arg(long, short, default_value = "index.scip")]
+        output: PathBuf,
+
+        /// Where to write which occurrences are search-based, as JSON. Defaults to the
+        /// output with a `.provenance.json` extension.
+        #[arg(long)]
+        provenance: Option<PathBuf>,
+    },
+
+    /// Convert an index to LSIF, as JSON lines
+    ExportLsif {
+        #[arg(default_value = "index.scip")]
+        index: PathBuf,
+
+        #[arg(long, short, default_value = "dump.lsif")]
+        output: PathBuf,
+
+        /// Where the sources of the documents are, to convert their positions to UTF-16.
+        /// The project root of the index by default.
+        #[arg(long)]
+        source_root: Option<PathBuf>,
+    },
+
+    /// Print what an index holds per document, or documents annotated over their source
+    Inspect {
+        #[arg(default_value = "index.scip")]
+        index: PathBuf,
+
+        /// Annotate the document at this relative path, as in snapshots
+        #[arg(long)]
+        document: Vec<String>,
+
+        /// Where the sources of the documents are, the project root of the index by default
+        #[arg(long)]
+        source_root: Option<PathBuf>,
+
+        /// Print the whole index as JSON instead
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Compile every `<directory>/<lang>/*.scm` and report mistakes that would
+    /// only show up while indexing
+    LintQueries {
+        #[arg(default_value = "queries")]
+        directory: PathBuf,
+    },
+
+    /// Check every file in `<testdata>/<lang>/` against its golden snapshot
+    /// and its inline expectation comments
+    Snapshot {
+        /// Write missing or outdated snapshots instead of failing
+        #[arg(long)]
+        update: bool,
+
+        #[arg(long, default_value = "testdata")]
+        testdata: PathBuf,
+    },
+
+    /// Write a ctags `tags` file for every file under `root`, for editors
+    Tags {
+        #[arg(default_value = ".")]
+        root: PathBuf,
+
+        /// Paths in the file are relative to `root`, so it goes there by default
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+
+    /// Print the symbols of every file under `root` as zoekt's `Symbol` records,
+    /// one JSON line per file
+    ZoektSymbols {
+        #[arg(default_value = ".")]
+        root: PathBuf,
+    },
+}
+
+#[derive(Clone, Copy)]
+enum IndexFormat {
+    Scip,
+    Json,
+}
+
+impl FromStr for IndexFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "scip" => Self::Scip,
+            "json" => Self::Json,
+            _ => bail!("unknown format {:?}, expected scip or json", s),
+        })
+    }
+}
+
+/// Where `index` writes documents: streamed as protobuf, or gathered to be written as JSON.
+enum IndexOutput {
+    Scip(IndexWriter<BufWriter<File>>),
+    Json(Index, BufWriter<File>),
+}
+
+impl IndexOutput {
+    fn new(format: IndexFormat, file: File, metadata: Metadata) -> Result<Self> {
+        let writer = BufWriter::new(file);
+        Ok(match format {
+            IndexFormat::Scip => Self::Scip(IndexWriter::new(writer, Some(&metadata))?),
+            IndexFormat::Json => {
+                let mut index = Index::new();
+                index.metadata = MessageField::some(metadata);
+                Self::Json(index, writer)
+            }
+        })
+    }
+
+    fn write_document(&mut self, doc: Document) -> Result<()> {
+        match self {
+            Self::Scip(writer) => writer.write_document(&doc),
+            Self::Json(index, _) => {
+                index.documents.push(doc);
+                Ok(())
+            }
+        }
+    }
+
+    fn documents(&self) -> usize {
+        match self {
+            Self::Scip(writer) => writer.documents(),
+            Self::Json(index, _) => index.documents.len(),
+        }
+    }
+
+    fn finish(self) -> Result<()> {
+        match self {
+            Self::Scip(writer) => writer.finish().map(drop),
+            Self::Json(index, writer) => write_index_json(&index, writer),
+        }
+    }
+}
+
+fn parse_grammar(value: &str) -> Result<(String, PathBuf), String> {
+    match value.split_once('=') {
+        Some((name, path)) => Ok((name.to_string(), PathBuf::from(path))),
+        None => Err("expected <name>=<path>".to_string()),
+    }
+}
+
+fn parse_override(value: &str) -> Result<(String, String), String> {
+    match value.rsplit_once('=') {
+        Some((glob, language)) => Ok((glob.to_string(), language.to_string())),
+        None => Err("expected <glob>=<language>".to_string()),
+    }
+}
+
+fn read_index(path: &PathBuf) -> Result<Index> {
+    let bytes = fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+    Index::parse_from_bytes(&bytes).with_context(|| format!("decoding {}", path.display()))
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let queries = cli.queries.as_deref();
+
+    for (name, library) in &cli.grammar {
+        let queries = queries.context("--grammar needs --queries to find the grammar's queries")?;
+        load_dynamic_language(name, library, queries)?;
+    }
+
+    match cli.command {
+        Command::Index {
+            root,
+            output,
+            format,
+            report,
+            max_file_size,
+            parse_timeout_ms,
+            max_matches,
+            generated,
+            language_override,
+            jobs,
+            cache,
+            cache_max_age_days,
+            revision,
+            since,
+        } => {
+            let mut detector = Detector::new();
+            for (glob, language) in &language_override {
+                detector = detector.with_override(glob, language)?;
+            }
+
+            let limits = Limits {
+                max_file_size: (max_file_size > 0).then_some(max_file_size),
+                parse_timeout: (parse_timeout_ms > 0)
+                    .then(|| Duration::from_millis(parse_timeout_ms)),
+                max_matches: (max_matches > 0).then_some(max_matches),
+                generated,
+            };
+            let options = IndexOptions {
+                queries: cli.queries.clone(),
+                detector,
+                cache: cache
+                    .map(|directory| {
+                        DocumentCache::open(&directory, &limits, cli.queries.as_deref())
+                    })
+                    .transpose()?,
+                limits,
+                jobs: match jobs {
+                    0 => std::thread::available_parallelism().map_or(1, usize::from),
+                    jobs => jobs,
+                },
+            };
+
+            let revision = match (revision, since) {
+                (None, None) => None,
+                (revision, since) => Some((revision.unwrap_or_else(|| "HEAD".to_string()), since)),
+            };
+
+            // As protobuf, documents are written as they are indexed, so the index is never
+            // all in memory. JSON has to gather them first, see `IndexOutput`.
+            let file =
+                File::create(&output).with_context(|| format!("writing {}", output.display()))?;
+            let mut writer = IndexOutput::new(format, file, index_metadata(&root)?)?;
+            let mut reasons = vec![];
+            let emit = |parsed: ParsedDocument| {
+                if let Some(reason) = parsed.reason {
+                    eprintln!("{}: {}", parsed.document.relative_path, reason);
+                    reasons.push((parsed.document.relative_path.clone(), reason));
+                }
+                writer.write_document(parsed.document)
+            };
+            match &revision {
+                Some((revision, since)) => {
+                    index_revision(&root, revision, since.as_deref(), &options, emit)?
+                }
+                None => index_documents(&root, &options, emit)?,
+            }
+            println!("{} documents indexed", writer.documents());
+
+            writer
+                .finish()
+                .with_context(|| format!("writing {}", output.display()))?;
+
+            let report = report.unwrap_or_else(|| output.with_extension("report.json"));
+            fs::write(&report, reasons_json(&reasons).to_string())
+                .with_context(|| format!("writing {}", report.display()))?;
+
+            if let Some(cache) = &options.cache {
+                let (hits, misses) = cache.stats();
+                let pruned = match cache_max_age_days {
+                    0 => 0,
+                    days => cache.prune(Duration::from_secs(days * 24 * 60 * 60))?,
+                };
+                println!("cache: {hits} reused, {misses} indexed, {pruned} pruned");
+            }
+        }
+        Command::Merge {
+            precise,
+            heuristic,
+            output,
+            provenance: provenance_path,
+        } => {
+            let mut index = read_index(&precise)?;
+            let (summary, provenance) = merge_index(&mut index, read_index(&heuristic)?);
+            println!("{:#?}", summary);
+
+            write_message_to_file(&output, index)
+                .map_err(|err| anyhow::anyhow!("writing {}: {}", output.display(), err))?;
+
+            let provenance_path =
+                provenance_path.unwrap_or_else(|| output.with_extension("provenance.json"));
+            fs::write(&provenance_path, provenance.to_json().to_string())
+                .with_context(|| format!("writing {}", provenance_path.display()))?;
+        }
+        Command::ExportLsif {
+            index,
+            output,
+            source_root: root,
+        } => {
+            let index = read_index(&index)?;
+            let root = root
+                .or_else(|| source_root(&index))
+                .context("the index has no local project root, pass --source-root")?;
+            let file =
+                File::create(&output).with_context(|| format!("writing {}", output.display()))?;
+            let elements = write_lsif(&index, &root, BufWriter::new(file))
+                .with_context(|| format!("writing {}", output.display()))?;
+            println!("{} vertices and edges written", elements);
+        }
+        Command::Inspect {
+            index,
+            document,
+            source_root: root,
+            json,
+        } => {
+            let path = index;
+            let index = read_index(&path)?;
+            let mut stdout = std::io::stdout().lock();
+            if json {
+                return write_index_json(&index, stdout);
+            }
+            if document.is_empty() {
+                return write_summary(&index, stdout);
+            }
+
+            let root = root
+                .or_else(|| source_root(&index))
+                .context("the index has no local project root, pass --source-root")?;
+            for relative_path in &document {
+                let doc = index
+                    .documents
+                    .iter()
+                    .find(|doc| &doc.relative_path == relative_path)
+                    .with_context(|| {
+                        format!("{} has no document {}", path.display(), relative_path)
+                    })?;
+                match annotate_document(doc, &root)? {
+                    Some(annotated) => writeln!(stdout, "{}:\n{}", relative_path, annotated)?,
+                    None => bail!(
+                        "cannot find the source of {} under {}",
+                        relative_path,
+                        root.display()
+                    ),
+                }
+            }
+        }
+        Command::LintQueries { directory } => {
+            let issues = lint_queries(&directory)?;
+            for issue in &issues {
+                println!("{}", issue);
+            }
+
+            if !issues.is_empty() {
+                anyhow::bail!("{} issues found", issues.len());
+            }
+        }
+        Command::Snapshot { update, testdata } => {
+            let results = run_snapshots(&testdata, queries, update)?;
+
+            let mut failed = 0;
+            for result in &results {
+                match result.outcome {
+                    SnapshotOutcome::Matched => {}
+                    SnapshotOutcome::Updated => println!("{}", result),
+                    SnapshotOutcome::Missing | SnapshotOutcome::Mismatched { .. } => {
+                        failed += 1;
+                        println!("{}", result);
+                    }
+                }
+            }
+
+            let expectations = run_expectations(&testdata, queries)?;
+            for failure in &expectations {
+                println!("{}", failure);
+            }
+
+            if failed > 0 {
+                anyhow::bail!(
+                    "{} of {} snapshots failed, rerun with --update to accept them",
+                    failed,
+                    results.len()
+                );
+            }
+            if !expectations.is_empty() {
+                anyhow::bail!("{} files have failed expectations", expectations.len());
+            }
+
+            println!("{} snapshots checked", results.len());
+        }
+        Command::Tags { root, output } => {
+            let options = IndexOptions {
+                queries: cli.queries.clone(),
+                ..Default::default()
+            };
+
+            let output = output.unwrap_or_else(|| root.join("tags"));
+            let file =
+                File::create(&output).with_context(|| format!("writing {}", output.display()))?;
+            let mut writer = BufWriter::new(file);
+            let count = write_directory_tags(&root, &options, &mut writer)?;
+            writer
+                .flush()
+                .with_context(|| format!("writing {}", output.display()))?;
+            println!("{} tags written to {}", count, output.display());
+        }
+        Command::ZoektSymbols { root } => {
+            let options = IndexOptions {
+                queries: cli.queries.clone(),
+                ..Default::default()
+            };
+
+            let mut stdout = std::io::stdout().lock();
+            directory_symbols(&root, &options, |line| Ok(writeln!(stdout, "{}", line)?))?;
+        }
+    }
+
+    Ok(())
+}
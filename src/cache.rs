@@ -0,0 +1,235 @@
+//! On-disk cache of indexed documents, so that re-indexing a repository only parses
+//! the files that changed.
+//!
+//! A document is stored under a key hashed from the file contents, its language, the
+//! fingerprint of that language's grammar and queries, the tool version and the limits.
+//! Anything that can change the document changes the key, so a cached document is
+//! always the one a fresh run would produce. Documents that were skipped or limited aren't
+//! stored, since a parse timeout depends on how busy the machine was.
+
+use std::{
+    fs::{self, File},
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{Context, Result};
+use protobuf::Message;
+use scip::types::Document;
+use walkdir::WalkDir;
+
+use crate::{
+    digest::digest,
+    languages::{language_names, load_language_configuration, LanguageConfiguration},
+    limits::Limits,
+};
+
+const EXTENSION: &str = "scip";
+
+pub struct DocumentCache {
+    directory: PathBuf,
+    /// Part of every key: the tool version and the limits
+    salt: String,
+    /// Documents can contain injected regions in any registered language
+    registry: String,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+    temporary: AtomicUsize,
+}
+
+impl DocumentCache {
+    /// Opens the cache in `directory`, creating it if needed. `queries` is the
+    /// directory the indexed languages load their queries from, if any.
+    pub fn open(directory: &Path, limits: &Limits, queries: Option<&Path>) -> Result<Self> {
+        fs::create_dir_all(directory)
+            .with_context(|| format!("creating {}", directory.display()))?;
+
+        let mut fingerprints = vec![];
+        for name in language_names() {
+            if let Some(config) = load_language_configuration(name, queries)? {
+                fingerprints.push(config.fingerprint);
+            }
+        }
+
+        Ok(Self {
+            directory: directory.to_path_buf(),
+            salt: digest(&[
+                env!("CARGO_PKG_VERSION").as_bytes(),
+                format!("{:?}", limits).as_bytes(),
+            ]),
+            registry: fingerprints.concat(),
+            hits: AtomicUsize::new(0),
+            misses: AtomicUsize::new(0),
+            temporary: AtomicUsize::new(0),
+        })
+    }
+
+    pub fn key(&self, config: &LanguageConfiguration, source: &str) -> String {
+        let registry = match config.injections {
+            Some(_) => self.registry.as_str(),
+            None => "",
+        };
+
+        digest(&[
+            self.salt.as_bytes(),
+            config.name.as_bytes(),
+            config.fingerprint.as_bytes(),
+            registry.as_bytes(),
+            source.as_bytes(),
+        ])
+    }
+
+    /// The cached document for `key`. Unreadable entries are treated as missing, and
+    /// will be replaced by the next [`DocumentCache::insert`].
+    pub fn get(&self, key: &str) -> Option<Document> {
+        let path = self.path(key);
+        let document = fs::read(&path)
+            .ok()
+            .and_then(|bytes| Document::parse_from_bytes(&bytes).ok());
+
+        match document {
+            Some(document) => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                // Pruning goes by the last time an entry was used
+                if let Ok(file) = File::options().append(true).open(&path) {
+                    let _ = file.set_modified(SystemTime::now());
+                }
+
+                Some(document)
+            }
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
+    /// Stores `document`. Its `relative_path` isn't part of the entry, so files with
+    /// the same contents share it.
+    pub fn insert(&self, key: &str, document: &Document) -> Result<()> {
+        let mut document = document.clone();
+        document.relative_path.clear();
+
+        let path = self.path(key);
+        let directory = path.parent().unwrap();
+        fs::create_dir_all(directory)
+            .with_context(|| format!("creating {}", directory.display()))?;
+
+        // Written next to the entry then renamed, so a concurrent run never reads half of it
+        let temporary = directory.join(format!(
+            "{}.{}.{}.tmp",
+            key,
+            std::process::id(),
+            self.temporary.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&temporary, document.write_to_bytes()?)
+            .with_context(|| format!("writing {}", temporary.display()))?;
+        fs::rename(&temporary, &path).with_context(|| format!("writing {}", path.display()))?;
+
+        Ok(())
+    }
+
+    /// How many lookups found a document, and how many didn't.
+    pub fn stats(&self) -> (usize, usize) {
+        (
+            self.hits.load(Ordering::Relaxed),
+            self.misses.load(Ordering::Relaxed),
+        )
+    }
+
+    /// Removes the entries that weren't used for `max_age`, and returns how many.
+    pub fn prune(&self, max_age: Duration) -> Result<usize> {
+        let cutoff = SystemTime::now()
+            .checked_sub(max_age)
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+
+        let mut removed = 0;
+        for entry in WalkDir::new(&self.directory).min_depth(2).max_depth(2) {
+            let entry = entry?;
+            let is_entry = entry
+                .path()
+                .extension()
+                .filter(|extension| *extension == EXTENSION || *extension == "tmp")
+                .is_some();
+            if !entry.file_type().is_file() || !is_entry {
+                continue;
+            }
+
+            if entry.metadata()?.modified()? <= cutoff {
+                match fs::remove_file(entry.path()) {
+                    Ok(()) => removed += 1,
+                    // Another run pruned it first
+                    Err(err) if err.kind() == ErrorKind::NotFound => {}
+                    Err(err) => {
+                        return Err(err)
+                            .with_context(|| format!("removing {}", entry.path().display()))
+                    }
+                }
+            }
+        }
+
+        Ok(removed)
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.directory
+            .join(&key[..2])
+            .join(format!("{}.{}", key, EXTENSION))
+    }
+}
+
+#[cfg(all(test, feature = "go"))]
+mod test {
+    use super::*;
+    use crate::index::{index_directory, IndexOptions};
+
+    #[test]
+    fn test_cached_runs_match_fresh_runs() -> Result<()> {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata");
+        let directory = tempfile::tempdir()?;
+
+        let fresh = index_directory(&root, &IndexOptions::default())?.write_to_bytes()?;
+
+        let options = IndexOptions {
+            cache: Some(DocumentCache::open(
+                directory.path(),
+                &Limits::default(),
+                None,
+            )?),
+            ..Default::default()
+        };
+        let cold = index_directory(&root, &options)?.write_to_bytes()?;
+        let cache = options.cache.as_ref().unwrap();
+        let (hits, misses) = cache.stats();
+        assert_eq!(hits, 0);
+        assert!(misses > 0);
+
+        let warm = index_directory(&root, &options)?.write_to_bytes()?;
+        assert_eq!(cache.stats(), (misses, misses));
+
+        assert_eq!(cold, fresh);
+        assert_eq!(warm, fresh);
+
+        assert_eq!(cache.prune(Duration::from_secs(3600))?, 0);
+        assert_eq!(cache.prune(Duration::ZERO)?, misses);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_keys_depend_on_the_queries() -> Result<()> {
+        let directory = tempfile::tempdir()?;
+        let cache = DocumentCache::open(directory.path(), &Limits::default(), None)?;
+        let mut config = crate::languages::go_configuration();
+        let key = cache.key(&config, "package main\n");
+        assert_eq!(key, cache.key(&config, "package main\n"));
+        assert_ne!(key, cache.key(&config, "package other\n"));
+
+        config.fingerprint = digest(&[config.fingerprint.as_bytes(), b"changed"]);
+        assert_ne!(key, cache.key(&config, "package main\n"));
+
+        Ok(())
+    }
+}
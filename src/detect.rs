@@ -0,0 +1,340 @@
+//! Detects the language of a file before looking it up in the registry.
+//!
+//! Evidence is checked from the most to the least reliable: per-path overrides,
+//! editor modelines, shebangs, well-known file names, and extensions. Extensions
+//! shared by several languages (like `.h`) are settled with content heuristics.
+//! Grammars loaded at runtime bring their own extensions, see [`crate::grammars`].
+//!
+//! Languages are reported by name, whether or not they are in the registry,
+//! so callers can tell "not supported" apart from "unknown".
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use globset::{Glob, GlobMatcher};
+
+use crate::languages::dynamic_language_for_extension;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Evidence {
+    Override,
+    Modeline,
+    Shebang,
+    Filename,
+    Extension,
+    /// An ambiguous extension, settled by looking at the contents
+    Heuristic,
+    /// An ambiguous extension with nothing in the contents to go by
+    Fallback,
+}
+
+impl Evidence {
+    pub fn confidence(&self) -> f32 {
+        match self {
+            Evidence::Override => 1.0,
+            Evidence::Modeline => 0.95,
+            Evidence::Shebang => 0.9,
+            Evidence::Filename => 0.9,
+            Evidence::Extension => 0.8,
+            Evidence::Heuristic => 0.6,
+            Evidence::Fallback => 0.3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Detection {
+    pub language: String,
+    pub evidence: Evidence,
+    /// Between 0 and 1, see [`Evidence::confidence`]
+    pub confidence: f32,
+}
+
+impl Detection {
+    fn new(language: &str, evidence: Evidence) -> Self {
+        Self {
+            language: language.to_string(),
+            evidence,
+            confidence: evidence.confidence(),
+        }
+    }
+}
+
+const FILENAMES: &[(&str, &str)] = &[
+    ("BUILD", "starlark"),
+    ("BUILD.bazel", "starlark"),
+    ("Dockerfile", "dockerfile"),
+    ("Gemfile", "ruby"),
+    ("GNUmakefile", "make"),
+    ("Makefile", "make"),
+    ("Rakefile", "ruby"),
+    ("WORKSPACE", "starlark"),
+];
+
+const EXTENSIONS: &[(&str, &str)] = &[
+    ("bash", "shell"),
+    ("c", "c"),
+    ("cc", "cpp"),
+    ("cpp", "cpp"),
+    ("cxx", "cpp"),
+    ("go", "go"),
+    ("hh", "cpp"),
+    ("hpp", "cpp"),
+    ("java", "java"),
+    ("js", "javascript"),
+    ("m", "objc"),
+    ("mm", "objcpp"),
+    ("py", "python"),
+    ("rb", "ruby"),
+    ("rs", "rust"),
+    ("sh", "shell"),
+    ("ts", "typescript"),
+];
+
+/// Extensions shared by several languages, the first one is the fallback.
+const AMBIGUOUS_EXTENSIONS: &[(&str, &[&str])] = &[("h", &["c", "cpp", "objc"])];
+
+const INTERPRETERS: &[(&str, &str)] = &[
+    ("bash", "shell"),
+    ("node", "javascript"),
+    ("perl", "perl"),
+    ("python", "python"),
+    ("ruby", "ruby"),
+    ("rust-script", "rust"),
+    ("sh", "shell"),
+    ("zsh", "shell"),
+];
+
+/// Modelines only count near the start or the end of a file, like in vim.
+const MODELINE_LINES: usize = 5;
+
+#[derive(Default)]
+pub struct Detector {
+    overrides: Vec<(GlobMatcher, String)>,
+}
+
+impl Detector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Files matching `glob` are always `language`. Earlier overrides win.
+    pub fn with_override(mut self, glob: &str, language: &str) -> Result<Self> {
+        let matcher = Glob::new(glob)
+            .with_context(|| format!("invalid glob: {}", glob))?
+            .compile_matcher();
+        self.overrides.push((matcher, language.to_string()));
+
+        Ok(self)
+    }
+
+    pub fn detect(&self, path: &Path, contents: &str) -> Option<Detection> {
+        if let Some((_, language)) = self.overrides.iter().find(|(glob, _)| glob.is_match(path)) {
+            return Some(Detection::new(language, Evidence::Override));
+        }
+
+        if let Some(language) = detect_modeline(contents) {
+            return Some(Detection::new(&language, Evidence::Modeline));
+        }
+
+        if let Some(language) = detect_shebang(contents) {
+            return Some(Detection::new(language, Evidence::Shebang));
+        }
+
+        let filename = path.file_name()?.to_str()?;
+        if let Some((_, language)) = FILENAMES.iter().find(|(name, _)| *name == filename) {
+            return Some(Detection::new(language, Evidence::Filename));
+        }
+
+        // Grammars loaded at runtime were asked for, so they win over the table
+        let extension = path.extension()?.to_str()?;
+        if let Some(language) = dynamic_language_for_extension(extension) {
+            return Some(Detection::new(language, Evidence::Extension));
+        }
+        if let Some((_, language)) = EXTENSIONS.iter().find(|(ext, _)| *ext == extension) {
+            return Some(Detection::new(language, Evidence::Extension));
+        }
+
+        let (_, candidates) = AMBIGUOUS_EXTENSIONS
+            .iter()
+            .find(|(ext, _)| *ext == extension)?;
+        Some(match guess_from_contents(candidates, contents) {
+            Some(language) => Detection::new(language, Evidence::Heuristic),
+            None => Detection::new(candidates[0], Evidence::Fallback),
+        })
+    }
+}
+
+fn detect_shebang(contents: &str) -> Option<&'static str> {
+    let line = contents.lines().next()?.strip_prefix("#!")?;
+    let mut words = line.split_whitespace();
+
+    let mut interpreter = words.next()?.rsplit('/').next()?;
+    if interpreter == "env" {
+        interpreter = words.find(|word| !word.starts_with('-'))?;
+    }
+
+    // python3, python3.11, ...
+    let interpreter = interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    INTERPRETERS
+        .iter()
+        .find(|(name, _)| *name == interpreter)
+        .map(|(_, language)| *language)
+}
+
+fn detect_modeline(contents: &str) -> Option<String> {
+    let lines = contents.lines().collect::<Vec<_>>();
+    let tail = lines
+        .len()
+        .saturating_sub(MODELINE_LINES)
+        .max(MODELINE_LINES);
+
+    lines
+        .iter()
+        .take(MODELINE_LINES)
+        .chain(lines.iter().skip(tail))
+        .find_map(|line| emacs_modeline(line).or_else(|| vim_modeline(line)))
+        .map(|language| modeline_language(&language))
+}
+
+/// `-*- mode: go -*-` or `-*- go -*-`
+fn emacs_modeline(line: &str) -> Option<String> {
+    let (_, rest) = line.split_once("-*-")?;
+    let (settings, _) = rest.split_once("-*-")?;
+
+    if !settings.contains(':') {
+        return Some(settings.trim().to_lowercase());
+    }
+
+    settings.split(';').find_map(|setting| {
+        let (key, value) = setting.split_once(':')?;
+        (key.trim() == "mode").then(|| value.trim().to_lowercase())
+    })
+}
+
+/// `vim: set ft=go:`, `vim: filetype=go` and the `vi:` and `ex:` variants
+fn vim_modeline(line: &str) -> Option<String> {
+    // The marker has to be a word of its own, so `regex:` isn't an `ex:` modeline
+    let options = ["vim:", "vi:", "ex:"].iter().find_map(|marker| {
+        let (before, after) = line.split_once(marker)?;
+        let own_word = !before.ends_with(|c: char| !c.is_whitespace());
+        own_word.then_some(after)
+    })?;
+
+    options
+        .split(|c: char| c == ':' || c.is_whitespace())
+        .find_map(|option| {
+            let (key, value) = option.split_once('=')?;
+            matches!(key, "ft" | "filetype" | "syntax").then(|| value.to_lowercase())
+        })
+}
+
+fn modeline_language(name: &str) -> String {
+    match name {
+        "sh" | "bash" | "zsh" | "shell-script" => "shell",
+        "c++" => "cpp",
+        "objective-c" => "objc",
+        "js" => "javascript",
+        other => other,
+    }
+    .to_string()
+}
+
+/// Constructs only some of the candidates for an ambiguous extension have.
+const CONTENT_MARKERS: &[(&str, &[&str])] = &[
+    (
+        "objc",
+        &["@interface", "@implementation", "@protocol", "#import "],
+    ),
+    (
+        "cpp",
+        &[
+            "namespace ",
+            "template <",
+            "template<",
+            "std::",
+            "public:",
+            "private:",
+            "class ",
+        ],
+    ),
+];
+
+fn guess_from_contents(candidates: &[&'static str], contents: &str) -> Option<&'static str> {
+    CONTENT_MARKERS
+        .iter()
+        .filter(|(language, _)| candidates.contains(language))
+        .find(|(_, markers)| markers.iter().any(|marker| contents.contains(marker)))
+        .map(|(language, _)| *language)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn detect(path: &str, contents: &str) -> Option<(String, Evidence)> {
+        Detector::new()
+            .detect(Path::new(path), contents)
+            .map(|detection| (detection.language, detection.evidence))
+    }
+
+    #[test]
+    fn test_detects_from_paths() {
+        assert_eq!(
+            detect("src/main.rs", ""),
+            Some(("rust".to_string(), Evidence::Extension))
+        );
+        assert_eq!(
+            detect("tools/Makefile", ""),
+            Some(("make".to_string(), Evidence::Filename))
+        );
+        assert_eq!(detect("README", "hello"), None);
+    }
+
+    #[test]
+    fn test_detects_from_contents() {
+        assert_eq!(
+            detect("bin/release", "#!/usr/bin/env -S python3.11 -u\nprint()"),
+            Some(("python".to_string(), Evidence::Shebang))
+        );
+        assert_eq!(
+            detect("gen.txt", "// -*- mode: go; tab-width: 4 -*-\npackage main"),
+            Some(("go".to_string(), Evidence::Modeline))
+        );
+        assert_eq!(
+            detect("script", "echo hi\n\n# vim: set ft=sh ts=2:"),
+            Some(("shell".to_string(), Evidence::Modeline))
+        );
+    }
+
+    #[test]
+    fn test_settles_ambiguous_extensions() {
+        assert_eq!(
+            detect("point.h", "@interface Point : NSObject\n@end"),
+            Some(("objc".to_string(), Evidence::Heuristic))
+        );
+        assert_eq!(
+            detect("point.h", "namespace geo {\nclass Point {};\n}"),
+            Some(("cpp".to_string(), Evidence::Heuristic))
+        );
+        assert_eq!(
+            detect("point.h", "struct point { int x; };"),
+            Some(("c".to_string(), Evidence::Fallback))
+        );
+    }
+
+    #[test]
+    fn test_overrides_win() -> Result<()> {
+        let detector = Detector::new()
+            .with_override("vendor/**/*.h", "cpp")?
+            .with_override("**/*.h", "c")?;
+
+        let detection = detector
+            .detect(Path::new("vendor/lib/point.h"), "@interface Point")
+            .unwrap();
+        assert_eq!(detection.language, "cpp");
+        assert_eq!(detection.confidence, 1.0);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,19 @@
+//! Hashes identifying cached documents and the queries and grammars they were made with.
+
+use sha2::{Digest, Sha256};
+
+/// Hex SHA-256 of `parts`, each prefixed with its length so that they can't run into
+/// each other.
+pub fn digest(parts: &[&[u8]]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update((part.len() as u64).to_le_bytes());
+        hasher.update(part);
+    }
+
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
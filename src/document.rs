@@ -0,0 +1,490 @@
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context, Result};
+use protobuf::Enum;
+use rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet};
+use scip::types::{Document, Occurrence, SymbolInformation, SymbolRole};
+use tree_sitter::Query;
+
+use crate::{
+    highlights::get_highlights_within,
+    injections::get_injections,
+    languages::{load_language_configuration, LanguageConfiguration},
+    limits::{skipped_document, MatchBudget, Reason, LIMITED, SKIPPED},
+    locals::get_locals_timed,
+    matches::get_tags_within,
+    snapshot::PackedRange,
+    timings::{timed, Timings},
+};
+
+/// Safeguards applied while parsing a single document, see [`crate::limits`].
+#[derive(Debug, Default, Clone)]
+pub struct DocumentOptions {
+    /// Only run the tags query, for files that aren't worth more
+    pub tags_only: bool,
+    /// Passed to [`tree_sitter::Parser::set_timeout_micros`]
+    pub parse_timeout: Option<Duration>,
+    /// Query matches over all passes. The pass that runs out stops matching and keeps
+    /// what it found so far, and the remaining passes don't run.
+    pub max_matches: Option<usize>,
+}
+
+/// A parsed document, and why it was skipped or only partly indexed when a limit was
+/// hit. The reason stays out of the document, where it would look like an occurrence.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParsedDocument {
+    pub document: Document,
+    pub reason: Option<Reason>,
+}
+
+/// Parses `source` once and runs the tags, locals and highlights queries over the tree,
+/// producing a single document with global definitions, local definitions and references.
+///
+/// Regions found by the injections query are parsed with their own grammar, and what
+/// they define and reference is added to the same document.
+pub fn parse_document(config: &mut LanguageConfiguration, source: &str) -> Result<Document> {
+    Ok(parse_document_with(config, source, &DocumentOptions::default())?.document)
+}
+
+/// Same as [`parse_document`], within the limits of `options`, with the reason when
+/// a limit is hit.
+pub fn parse_document_with(
+    config: &mut LanguageConfiguration,
+    source: &str,
+    options: &DocumentOptions,
+) -> Result<ParsedDocument> {
+    parse_document_timed(config, source, options, &mut Timings::default())
+}
+
+/// Same as [`parse_document_with`], adding the time spent in each phase to `timings`.
+pub fn parse_document_timed(
+    config: &mut LanguageConfiguration,
+    source: &str,
+    options: &DocumentOptions,
+    timings: &mut Timings,
+) -> Result<ParsedDocument> {
+    let source_bytes = source.as_bytes();
+
+    let timeout = options
+        .parse_timeout
+        .map_or(0, |timeout| timeout.as_micros());
+    config.parser.set_timeout_micros(timeout as u64);
+    let tree = timed(&mut timings.parse, || {
+        config.parser.parse(source_bytes, None)
+    });
+    config.parser.set_timeout_micros(0);
+    if tree.is_none() {
+        // Otherwise the next parse would try to resume this one
+        config.parser.reset();
+    }
+
+    let tree = match (tree, options.parse_timeout) {
+        (Some(tree), _) => tree,
+        (None, Some(timeout)) => {
+            let reason = format!("parsing took longer than {}ms", timeout.as_millis());
+            return Ok(skipped_document(config.name, reason));
+        }
+        (None, None) => bail!("tree-sitter failed to parse the document"),
+    };
+
+    let mut passes = Passes::new(options, timings);
+    let (mut globals, mut locals, mut highlights) =
+        run_queries(config, &tree, source_bytes, &mut passes)?;
+
+    let injections = match &config.injections {
+        Some(query) if passes.limited.is_none() && !options.tags_only => {
+            timed(&mut passes.timings.matching, || {
+                get_injections(query, &tree, source_bytes)
+            })?
+        }
+        _ => vec![],
+    };
+
+    let mut injected_configs = HashMap::<String, Option<LanguageConfiguration>>::default();
+    for injection in injections {
+        if !injected_configs.contains_key(&injection.language) {
+            let injected =
+                load_language_configuration(&injection.language, config.queries.as_deref())?;
+            injected_configs.insert(injection.language.clone(), injected);
+        }
+        let Some(injected) = injected_configs
+            .get_mut(&injection.language)
+            .and_then(Option::as_mut)
+        else {
+            continue;
+        };
+
+        // Parsing only the injected range of the whole file keeps every position
+        // relative to the host document.
+        injected
+            .parser
+            .set_included_ranges(&[injection.range])
+            .map_err(|_| anyhow!("invalid injection range {:?}", injection.range))?;
+        let tree = timed(&mut passes.timings.parse, || {
+            injected.parser.parse(source_bytes, None)
+        })
+        .context("tree-sitter failed to parse an injected region")?;
+        let (injected_globals, injected_locals, injected_highlights) =
+            run_queries(injected, &tree, source_bytes, &mut passes)?;
+
+        globals.extend(injected_globals);
+        let first_id = last_local_id(&locals);
+        locals.extend(offset_locals(injected_locals, first_id));
+        highlights.extend(injected_highlights);
+    }
+
+    let mut doc = Document::new();
+    doc.language = config.name.to_string();
+    doc.symbols = globals
+        .iter()
+        .filter(|o| o.symbol_roles & SymbolRole::Definition.value() != 0)
+        .map(|o| SymbolInformation {
+            symbol: o.symbol.clone(),
+            ..Default::default()
+        })
+        .collect();
+    timed(&mut passes.timings.resolve, || {
+        doc.occurrences = merge_occurrences(globals, locals);
+        apply_highlights(&mut doc.occurrences, highlights);
+    });
+
+    let reason = passes.limited.map(|message| Reason {
+        code: if doc.occurrences.is_empty() {
+            SKIPPED
+        } else {
+            LIMITED
+        },
+        message,
+    });
+
+    Ok(ParsedDocument {
+        document: doc,
+        reason,
+    })
+}
+
+/// Shares the match budget of a document between its query passes.
+struct Passes<'a> {
+    options: &'a DocumentOptions,
+    timings: &'a mut Timings,
+    budget: MatchBudget,
+    limited: Option<String>,
+}
+
+impl<'a> Passes<'a> {
+    fn new(options: &'a DocumentOptions, timings: &'a mut Timings) -> Self {
+        Self {
+            options,
+            timings,
+            budget: MatchBudget::new(options.max_matches),
+            limited: None,
+        }
+    }
+
+    fn run(
+        &mut self,
+        name: &str,
+        query: Option<&Query>,
+        pass: impl FnOnce(&Query, &mut MatchBudget, &mut Timings) -> Result<Vec<Occurrence>>,
+    ) -> Result<Vec<Occurrence>> {
+        let Some(query) = query else {
+            return Ok(vec![]);
+        };
+        if self.limited.is_some() {
+            return Ok(vec![]);
+        }
+
+        let occurrences = pass(query, &mut self.budget, self.timings)?;
+        if self.budget.is_exhausted() {
+            self.limited = Some(format!(
+                "the {} query went over the limit of {} matches",
+                name,
+                self.options.max_matches.unwrap_or_default()
+            ));
+        }
+
+        Ok(occurrences)
+    }
+}
+
+/// The global, local and highlight occurrences of a tree.
+fn run_queries(
+    config: &LanguageConfiguration,
+    tree: &tree_sitter::Tree,
+    source_bytes: &[u8],
+    passes: &mut Passes,
+) -> Result<(Vec<Occurrence>, Vec<Occurrence>, Vec<Occurrence>)> {
+    let globals = passes.run("tags", config.tags.as_ref(), |query, budget, timings| {
+        timed(&mut timings.matching, || {
+            get_tags_within(query, tree, source_bytes, budget)
+        })
+    })?;
+
+    if passes.options.tags_only {
+        return Ok((globals, vec![], vec![]));
+    }
+
+    let locals = passes.run(
+        "locals",
+        config.locals.as_ref(),
+        |query, budget, timings| get_locals_timed(query, tree, source_bytes, budget, timings),
+    )?;
+    let highlights = passes.run(
+        "highlights",
+        config.highlights.as_ref(),
+        |query, budget, timings| {
+            timed(&mut timings.matching, || {
+                get_highlights_within(query, tree, source_bytes, budget)
+            })
+        },
+    )?;
+
+    Ok((globals, locals, highlights))
+}
+
+fn local_id(symbol: &str) -> Option<usize> {
+    symbol.strip_prefix("local ")?.parse().ok()
+}
+
+fn last_local_id(locals: &[Occurrence]) -> usize {
+    locals
+        .iter()
+        .filter_map(|o| local_id(&o.symbol))
+        .max()
+        .unwrap_or_default()
+}
+
+/// Every injected region numbers its locals from 1, so they are moved after the
+/// ones already in the document.
+fn offset_locals(mut locals: Vec<Occurrence>, offset: usize) -> Vec<Occurrence> {
+    for occ in &mut locals {
+        if let Some(id) = local_id(&occ.symbol) {
+            occ.symbol = format!("local {}", id + offset);
+        }
+    }
+
+    locals
+}
+
+/// Combines the output of the two passes.
+///
+/// Both queries can define the same identifier (for example a Go function is both
+/// a tag and a `scope global` local). In that case the global definition wins, and
+/// every reference the locals pass resolved to it is rewritten to the global symbol.
+/// Any other local occurrence sitting on a global definition is dropped.
+fn merge_occurrences(globals: Vec<Occurrence>, locals: Vec<Occurrence>) -> Vec<Occurrence> {
+    let definition = SymbolRole::Definition.value();
+
+    // Decided on borrowed ranges and symbols first, so that only the symbols which
+    // actually change are copied.
+    let (keep_globals, locals_plan) = {
+        let global_definitions = globals
+            .iter()
+            .filter(|o| o.symbol_roles & definition != 0)
+            .map(|o| (o.range.as_slice(), o.symbol.as_str()))
+            .collect::<HashMap<_, _>>();
+
+        let renamed = locals
+            .iter()
+            .filter(|o| o.symbol_roles & definition != 0)
+            .filter_map(|o| {
+                global_definitions
+                    .get(o.range.as_slice())
+                    .map(|global| (o.symbol.as_str(), *global))
+            })
+            .collect::<HashMap<_, _>>();
+
+        let mut seen = HashSet::default();
+        let keep_globals = globals
+            .iter()
+            .map(|occ| seen.insert((occ.range.as_slice(), occ.symbol.as_str())))
+            .collect::<Vec<_>>();
+
+        // For every local: whether it's kept, and the global symbol it's renamed to
+        let locals_plan = locals
+            .iter()
+            .map(|occ| {
+                if global_definitions.contains_key(occ.range.as_slice()) {
+                    return (false, None);
+                }
+
+                let global = renamed.get(occ.symbol.as_str()).copied();
+                let symbol = global.unwrap_or(occ.symbol.as_str());
+                let keep = seen.insert((occ.range.as_slice(), symbol));
+                (keep, global.filter(|_| keep).map(str::to_string))
+            })
+            .collect::<Vec<_>>();
+
+        (keep_globals, locals_plan)
+    };
+
+    let mut occurrences = Vec::with_capacity(globals.len() + locals.len());
+    occurrences.extend(
+        globals
+            .into_iter()
+            .zip(keep_globals)
+            .filter_map(|(occ, keep)| keep.then_some(occ)),
+    );
+    for (mut occ, (keep, global)) in locals.into_iter().zip(locals_plan) {
+        if !keep {
+            continue;
+        }
+
+        if let Some(global) = global {
+            occ.symbol = global;
+        }
+        occurrences.push(occ);
+    }
+
+    occurrences.sort_by_key(|o| PackedRange::from_vec(&o.range));
+    occurrences
+}
+
+/// Sets the syntax kind on navigation occurrences covering exactly the highlighted range,
+/// and keeps the remaining highlights as their own symbol-less occurrences.
+fn apply_highlights(occurrences: &mut Vec<Occurrence>, highlights: Vec<Occurrence>) {
+    if highlights.is_empty() {
+        return;
+    }
+
+    let mut by_range = HashMap::<&[i32], Vec<usize>>::default();
+    for (idx, occ) in occurrences.iter().enumerate() {
+        by_range.entry(occ.range.as_slice()).or_default().push(idx);
+    }
+
+    let mut syntax_kinds = vec![];
+    let mut remaining = vec![];
+    for highlight in highlights {
+        match by_range.get(highlight.range.as_slice()) {
+            Some(indexes) => {
+                for &idx in indexes {
+                    syntax_kinds.push((idx, highlight.syntax_kind));
+                }
+            }
+            None => remaining.push(highlight),
+        }
+    }
+
+    for (idx, syntax_kind) in syntax_kinds {
+        occurrences[idx].syntax_kind = syntax_kind;
+    }
+    occurrences.extend(remaining);
+
+    occurrences.sort_by_key(|o| PackedRange::from_vec(&o.range));
+}
+
+#[cfg(all(test, feature = "go"))]
+mod test {
+    use std::fmt::Write;
+
+    use super::*;
+    use crate::snapshot::dump_document;
+
+    fn dump_enclosing_ranges(doc: &Document) -> String {
+        let mut result = String::new();
+        for occ in &doc.occurrences {
+            if occ.symbol_roles & SymbolRole::Definition.value() == 0 {
+                continue;
+            }
+
+            let range = PackedRange::from_vec(&occ.range);
+            let _ = write!(
+                result,
+                "{}:{} {}",
+                range.start_line, range.start_col, occ.symbol
+            );
+            if occ.enclosing_range.is_empty() {
+                let _ = writeln!(result, " (no enclosing range)");
+            } else {
+                let enclosing = PackedRange::from_vec(&occ.enclosing_range);
+                let _ = writeln!(
+                    result,
+                    " => {}:{}..{}:{}",
+                    enclosing.start_line,
+                    enclosing.start_col,
+                    enclosing.end_line,
+                    enclosing.end_col
+                );
+            }
+        }
+
+        result
+    }
+
+    #[test]
+    fn test_can_parse_go_document() -> Result<()> {
+        let mut config = crate::languages::go_configuration();
+        let source_code = include_str!("../testdata/go/funcs.go");
+        let doc = parse_document(&mut config, source_code)?;
+
+        let dumped = dump_document(&doc, source_code);
+        insta::assert_snapshot!(dumped);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_does_not_duplicate_definitions() -> Result<()> {
+        let mut config = crate::languages::go_configuration();
+        let source_code = include_str!("../testdata/go/funcs.go");
+        let doc = parse_document(&mut config, source_code)?;
+
+        let mut ranges = doc
+            .occurrences
+            .iter()
+            .map(|o| o.range.clone())
+            .collect::<Vec<_>>();
+        let total = ranges.len();
+        ranges.sort();
+        ranges.dedup();
+
+        assert_eq!(
+            total,
+            ranges.len(),
+            "every range should only be emitted once"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_times_each_phase() -> Result<()> {
+        let mut config = crate::languages::go_configuration();
+        let source_code = include_str!("../testdata/go/funcs.go");
+
+        let mut timings = Timings::default();
+        let options = DocumentOptions::default();
+        let timed =
+            parse_document_timed(&mut config, source_code, &options, &mut timings)?.document;
+
+        assert_eq!(timed, parse_document(&mut config, source_code)?);
+        assert!(timings.parse > Duration::ZERO);
+        assert!(timings.matching > Duration::ZERO);
+        assert!(timings.resolve > Duration::ZERO);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_go_enclosing_ranges() -> Result<()> {
+        let mut config = crate::languages::go_configuration();
+        let source_code = include_str!("../testdata/go/enclosing.go");
+        let doc = parse_document(&mut config, source_code)?;
+
+        insta::assert_snapshot!(dump_enclosing_ranges(&doc));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "rust")]
+    fn test_rust_enclosing_ranges() -> Result<()> {
+        let mut config = crate::languages::rust_configuration();
+        let source_code = include_str!("../testdata/rust/enclosing.rs");
+        let doc = parse_document(&mut config, source_code)?;
+
+        insta::assert_snapshot!(dump_enclosing_ranges(&doc));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,498 @@
+//! Inline expectations, written as comments right below the code they describe:
+//!
+//! ```go
+//! func Something(count int) int {
+//! //             ^^^^^ definition
+//!     count = count + 1
+//!     // <- reference -> 1:16
+//!     //      ^^^^^ reference local 2
+//! }
+//! ```
+//!
+//! Carets point at the same columns on the closest line above that isn't an assertion,
+//! and the occurrence must span exactly those columns. `<-` points at the column of the
+//! comment itself, where the occurrence must start. Columns are bytes, like
+//! tree-sitter's, so a tab counts as one column.
+//!
+//! An assertion is `definition` or `reference`, optionally followed by extra roles
+//! (`write`, `read`, `import`, ...), and then either a symbol (`local 1`,
+//! `scip-ctags Something().`) or `-> line:column`, the 1-based position of the
+//! definition the occurrence should resolve to.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use pretty_assertions::StrComparison;
+use protobuf::Enum;
+use rustc_hash::FxHashMap as HashMap;
+use scip::types::{Occurrence, SymbolRole};
+
+use crate::{
+    languages::{language_names, load_language_configuration, LanguageConfiguration},
+    locals::get_locals,
+    matches::get_tags,
+    snapshot::{format_roles, testdata_files, PackedRange, ROLE_NAMES},
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Target {
+    Any,
+    Symbol(String),
+    /// The symbol defined at this 0-based line and column
+    DefinitionAt {
+        line: usize,
+        column: usize,
+    },
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Assertion {
+    /// 0-based line of the comment holding the assertion
+    pub assertion_line: usize,
+    /// 0-based position the assertion points at
+    pub line: usize,
+    pub column: usize,
+    /// Number of carets, or `None` for `<-`
+    pub width: Option<usize>,
+    pub definition: bool,
+    /// Role bits, other than `Definition`, the occurrence must have
+    pub roles: i32,
+    pub target: Target,
+}
+
+pub fn parse_assertions(source: &str, line_comment: &str) -> Result<Vec<Assertion>> {
+    let mut assertions = vec![];
+    let mut target_line = None;
+
+    for (idx, line) in source.lines().enumerate() {
+        let Some((column, width, text)) = split_assertion(line, line_comment) else {
+            target_line = Some(idx);
+            continue;
+        };
+
+        let Some(target_line) = target_line else {
+            bail!("line {}: assertion without any code above it", idx + 1);
+        };
+
+        let (definition, roles, target) =
+            parse_expectation(text).with_context(|| format!("line {}", idx + 1))?;
+
+        assertions.push(Assertion {
+            assertion_line: idx,
+            line: target_line,
+            column,
+            width,
+            definition,
+            roles,
+            target,
+        });
+    }
+
+    Ok(assertions)
+}
+
+/// Returns the column an assertion points at, its number of carets, and the text of
+/// the assertion.
+fn split_assertion<'a>(
+    line: &'a str,
+    line_comment: &str,
+) -> Option<(usize, Option<usize>, &'a str)> {
+    let comment_start = line.len() - line.trim_start().len();
+    let after_comment = line[comment_start..].strip_prefix(line_comment)?;
+
+    let text = after_comment.trim_start();
+    if let Some(text) = text.strip_prefix("<-") {
+        return Some((comment_start, None, text));
+    }
+
+    let caret_start = line.len() - text.len();
+    let after_carets = text.trim_start_matches('^');
+    let width = text.len() - after_carets.len();
+    if width == 0 {
+        return None;
+    }
+
+    Some((caret_start, Some(width), after_carets))
+}
+
+fn parse_expectation(text: &str) -> Result<(bool, i32, Target)> {
+    let mut words = text.split_whitespace().peekable();
+
+    let definition = match words.next() {
+        Some("definition") => true,
+        Some("reference") => false,
+        Some(other) => bail!("expected `definition` or `reference`, found `{}`", other),
+        None => bail!("empty assertion"),
+    };
+
+    let mut roles = 0;
+    while let Some(role) = words
+        .peek()
+        .and_then(|word| ROLE_NAMES.iter().find(|(_, name)| name == word))
+    {
+        roles |= role.0.value();
+        words.next();
+    }
+
+    let target = match words.peek() {
+        None => Target::Any,
+        Some(&"->") => {
+            words.next();
+            let position = words.next().context("expected `line:column` after `->`")?;
+            let (line, column) = position
+                .split_once(':')
+                .with_context(|| format!("expected `line:column`, found `{}`", position))?;
+
+            let line = line.parse::<usize>()?;
+            let column = column.parse::<usize>()?;
+            if line == 0 || column == 0 {
+                bail!("positions after `->` start at 1:1");
+            }
+
+            Target::DefinitionAt {
+                line: line - 1,
+                column: column - 1,
+            }
+        }
+        Some(_) => Target::Symbol(words.collect::<Vec<_>>().join(" ")),
+    };
+
+    Ok((definition, roles & !SymbolRole::Definition.value(), target))
+}
+
+fn occurrences_at(occurrences: &[Occurrence], line: usize, column: usize) -> Vec<&Occurrence> {
+    let (line, column) = (line as i32, column as i32);
+    occurrences
+        .iter()
+        .filter(|occ| !occ.symbol.is_empty())
+        .filter(|occ| {
+            let range = PackedRange::from_vec(&occ.range);
+            let after_start =
+                line > range.start_line || (line == range.start_line && column >= range.start_col);
+            let before_end =
+                line < range.end_line || (line == range.end_line && column < range.end_col);
+
+            after_start && before_end
+        })
+        .collect()
+}
+
+/// The occurrences spanning exactly the carets of `assertion`, or starting at its `<-`.
+fn asserted_occurrences<'o>(
+    assertion: &Assertion,
+    occurrences: &'o [Occurrence],
+) -> Vec<&'o Occurrence> {
+    let (line, column) = (assertion.line as i32, assertion.column as i32);
+    occurrences_at(occurrences, assertion.line, assertion.column)
+        .into_iter()
+        .filter(|occ| {
+            let range = PackedRange::from_vec(&occ.range);
+            let starts_here = range.start_line == line && range.start_col == column;
+            match assertion.width {
+                Some(width) => {
+                    starts_here && range.end_line == line && range.end_col == column + width as i32
+                }
+                None => starts_here,
+            }
+        })
+        .collect()
+}
+
+fn is_satisfied(assertion: &Assertion, occurrences: &[Occurrence]) -> bool {
+    let definition = SymbolRole::Definition.value();
+
+    asserted_occurrences(assertion, occurrences)
+        .into_iter()
+        .filter(|occ| (occ.symbol_roles & definition != 0) == assertion.definition)
+        .filter(|occ| occ.symbol_roles & assertion.roles == assertion.roles)
+        .any(|occ| match &assertion.target {
+            Target::Any => true,
+            Target::Symbol(symbol) => &occ.symbol == symbol,
+            Target::DefinitionAt { line, column } => occurrences_at(occurrences, *line, *column)
+                .into_iter()
+                .any(|def| def.symbol_roles & definition != 0 && def.symbol == occ.symbol),
+        })
+}
+
+/// What is actually at the position an assertion points at, written as an assertion.
+fn describe_actual(assertion: &Assertion, occurrences: &[Occurrence]) -> String {
+    let found = occurrences_at(occurrences, assertion.line, assertion.column)
+        .into_iter()
+        .map(|occ| {
+            let mut parts = format_roles(occ.symbol_roles, false);
+            parts.push(occ.symbol.clone());
+            parts.join(" ")
+        })
+        .collect::<Vec<_>>();
+
+    if found.is_empty() {
+        "nothing".to_string()
+    } else {
+        found.join(" | ")
+    }
+}
+
+/// The assertion `line` rewritten to what was found. Carets spanning other columns
+/// than the occurrence are moved over it, or when they can't be, because it starts
+/// before the comment, its 1-based column is given.
+fn rewrite_assertion(
+    line: &str,
+    line_comment: &str,
+    assertion: &Assertion,
+    occurrences: &[Occurrence],
+) -> String {
+    let (_, _, text) = split_assertion(line, line_comment).expect("rewritten lines are assertions");
+    let prefix = &line[..line.len() - text.len()];
+    let found = describe_actual(assertion, occurrences);
+
+    let span = occurrences_at(occurrences, assertion.line, assertion.column)
+        .first()
+        .map(|occ| PackedRange::from_vec(&occ.range))
+        .filter(|range| range.start_line == range.end_line)
+        .map(|range| {
+            (
+                range.start_col as usize,
+                (range.end_col - range.start_col) as usize,
+            )
+        });
+    let Some((start, width)) = span.filter(|_| assertion.width.is_some()) else {
+        return format!("{} {}", prefix, found);
+    };
+    if (start, Some(width)) == (assertion.column, assertion.width) {
+        return format!("{} {}", prefix, found);
+    }
+
+    let comment_end = line.len() - line.trim_start().len() + line_comment.len();
+    if start < comment_end {
+        return format!("{} {} (at column {})", prefix, found, start + 1);
+    }
+
+    format!(
+        "{}{}{} {}",
+        &line[..comment_end],
+        " ".repeat(start - comment_end),
+        "^".repeat(width),
+        found
+    )
+}
+
+/// Checks every assertion in `source` against the occurrences from the tags and locals
+/// queries. On failure, returns `source` with the failing assertions rewritten to
+/// describe what was actually found, so it can be diffed against the original.
+pub fn check_expectations(
+    config: &mut LanguageConfiguration,
+    source: &str,
+) -> Result<Option<String>> {
+    let assertions = parse_assertions(source, config.line_comment)?;
+    if assertions.is_empty() {
+        return Ok(None);
+    }
+
+    let source_bytes = source.as_bytes();
+    let tree = config
+        .parser
+        .parse(source_bytes, None)
+        .context("tree-sitter failed to parse the document")?;
+
+    let mut occurrences = vec![];
+    if let Some(query) = &config.tags {
+        occurrences.extend(get_tags(query, &tree, source_bytes)?);
+    }
+    if let Some(query) = &config.locals {
+        occurrences.extend(get_locals(query, &tree, source_bytes)?);
+    }
+
+    let mut rewritten = HashMap::default();
+    for assertion in &assertions {
+        if !is_satisfied(assertion, &occurrences) {
+            rewritten.insert(assertion.assertion_line, assertion);
+        }
+    }
+
+    if rewritten.is_empty() {
+        return Ok(None);
+    }
+
+    let actual = source
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| match rewritten.get(&idx) {
+            Some(assertion) => format!(
+                "{}\n",
+                rewrite_assertion(line, config.line_comment, assertion, &occurrences)
+            ),
+            None => format!("{}\n", line),
+        })
+        .collect::<String>();
+    Ok(Some(actual))
+}
+
+#[derive(Debug)]
+pub struct ExpectationFailure {
+    pub path: PathBuf,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl std::fmt::Display for ExpectationFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "failed expectations: {}\n{}",
+            self.path.display(),
+            StrComparison::new(&self.expected, &self.actual)
+        )
+    }
+}
+
+/// Checks the inline expectations of every file in `testdata/<lang>/`.
+pub fn run_expectations(
+    testdata: &Path,
+    queries: Option<&Path>,
+) -> Result<Vec<ExpectationFailure>> {
+    let mut failures = vec![];
+
+    for language in language_names() {
+        let mut config = load_language_configuration(language, queries)?
+            .expect("registered languages have a configuration");
+
+        for path in testdata_files(testdata, language)? {
+            let source = fs::read_to_string(&path)?;
+            if let Some(actual) = check_expectations(&mut config, &source)
+                .with_context(|| format!("checking {}", path.display()))?
+            {
+                let expected = source.lines().map(|line| format!("{}\n", line)).collect();
+                failures.push(ExpectationFailure {
+                    path,
+                    expected,
+                    actual,
+                });
+            }
+        }
+    }
+
+    Ok(failures)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_assertions() -> Result<()> {
+        let source = "\
+func Something(count int) int {
+//             ^^^^^ definition write local 1
+\treturn count
+\t// <- reference -> 1:16
+\t//     ^ reference scip-ctags Something().
+}";
+
+        let assertions = parse_assertions(source, "//")?;
+        assert_eq!(
+            assertions,
+            vec![
+                Assertion {
+                    assertion_line: 1,
+                    line: 0,
+                    column: 15,
+                    width: Some(5),
+                    definition: true,
+                    roles: SymbolRole::WriteAccess.value(),
+                    target: Target::Symbol("local 1".to_string()),
+                },
+                Assertion {
+                    assertion_line: 3,
+                    line: 2,
+                    column: 1,
+                    width: None,
+                    definition: false,
+                    roles: 0,
+                    target: Target::DefinitionAt {
+                        line: 0,
+                        column: 15
+                    },
+                },
+                Assertion {
+                    assertion_line: 4,
+                    line: 2,
+                    column: 8,
+                    width: Some(1),
+                    definition: false,
+                    roles: 0,
+                    target: Target::Symbol("scip-ctags Something().".to_string()),
+                },
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "go")]
+    fn test_reports_failed_expectations() -> Result<()> {
+        let mut config = crate::languages::go_configuration();
+        let source = "\
+package main
+
+func main() {
+\tx := 1
+\t// <- definition
+\tprintln(x)
+\t//      ^ definition
+}";
+
+        let actual = check_expectations(&mut config, source)?.expect("the second assertion fails");
+        assert!(actual.contains("\t// <- definition\n"), "{}", actual);
+        assert!(
+            actual.contains("\t//      ^ reference read local "),
+            "{}",
+            actual
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "go")]
+    fn test_checks_the_whole_caret_span() -> Result<()> {
+        let mut config = crate::languages::go_configuration();
+        let source = "\
+package main
+
+func main() {
+\tvalue := 1
+\tprintln(value)
+\t//      ^^^^^ reference
+\t//       ^^^ reference
+}";
+
+        let actual = check_expectations(&mut config, source)?.expect("the second assertion fails");
+        let rewritten = actual.lines().filter(|line| line.contains('^'));
+        assert_eq!(
+            rewritten.collect::<Vec<_>>(),
+            [
+                "\t//      ^^^^^ reference",
+                "\t//      ^^^^^ reference read local 2"
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_testdata_expectations() -> Result<()> {
+        let testdata = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata");
+        let failures = run_expectations(&testdata, None)?
+            .into_iter()
+            .map(|failure| failure.to_string())
+            .collect::<Vec<_>>();
+
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,327 @@
+//! Indexes a git revision straight from the object database, without checking it out,
+//! either entirely or only the files changed since another revision.
+//!
+//! Objects are read with the `git` command: `ls-tree` lists the files of a revision,
+//! `diff` the files that changed, and one `cat-file --batch` per worker reads the blobs.
+
+use std::{
+    ffi::OsStr,
+    io::{BufRead, BufReader, Read, Write},
+    path::Path,
+    process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use rustc_hash::FxHashSet as HashSet;
+
+use crate::{
+    document::ParsedDocument,
+    index::{index_contents, index_in_order, is_hidden, Configurations, IndexOptions},
+};
+
+/// A file in the tree of a revision.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeFile {
+    pub path: String,
+    pub object: String,
+    pub size: u64,
+}
+
+/// Calls `emit` with the document of every supported file in `revision`, in path order.
+/// With `since`, only the files added or modified between `since` and `revision` are
+/// indexed, which gives a partial index.
+///
+/// `root` can be any directory of the repository: like when indexing a directory, only
+/// the files under it are indexed, with paths relative to it.
+pub fn index_revision(
+    root: &Path,
+    revision: &str,
+    since: Option<&str>,
+    options: &IndexOptions,
+    emit: impl FnMut(ParsedDocument) -> Result<()>,
+) -> Result<()> {
+    let mut files = tree_files(root, revision)?;
+    if let Some(since) = since {
+        let changed = changed_files(root, since, revision)?;
+        files.retain(|file| changed.contains(&file.path));
+    }
+
+    index_in_order(
+        files.len(),
+        options.jobs,
+        || Ok((Configurations::default(), BlobReader::new(root)?)),
+        |(configs, blobs), idx| {
+            let file = &files[idx];
+            index_contents(Path::new(&file.path), file.size, options, configs, || {
+                let contents = blobs.read(&file.object)?;
+                Ok(String::from_utf8(contents).ok())
+            })
+            .with_context(|| format!("indexing {}:{}", revision, file.path))
+        },
+        emit,
+    )
+}
+
+/// The regular files of `revision` under `root`, relative to it, sorted by path and
+/// without hidden ones, like the files indexed from a directory.
+pub fn tree_files(root: &Path, revision: &str) -> Result<Vec<TreeFile>> {
+    // Run from a subdirectory, ls-tree only lists the files under it
+    let output = git(root, &["ls-tree", "-r", "-z", "--long", revision])?;
+
+    let mut files = vec![];
+    for entry in output
+        .split(|byte| *byte == 0)
+        .filter(|entry| !entry.is_empty())
+    {
+        let entry = String::from_utf8_lossy(entry);
+        let (info, path) = entry
+            .split_once('\t')
+            .ok_or_else(|| anyhow!("unexpected ls-tree entry {:?}", entry))?;
+
+        // <mode> <type> <object> <size>, where submodules and symlinks aren't files
+        let info = info.split_whitespace().collect::<Vec<_>>();
+        let [mode, "blob", object, size] = info[..] else {
+            continue;
+        };
+        if mode == "120000" || path.split('/').any(|name| is_hidden(OsStr::new(name))) {
+            continue;
+        }
+
+        files.push(TreeFile {
+            path: path.to_string(),
+            object: object.to_string(),
+            size: size.parse().context("parsing ls-tree")?,
+        });
+    }
+
+    // git sorts trees as if directory names ended with a slash, unlike walking a directory
+    files.sort_by(|a, b| a.path.split('/').cmp(b.path.split('/')));
+
+    Ok(files)
+}
+
+/// The paths under `root` added or modified between `from` and `to`, relative to it.
+/// Deletions have nothing to index.
+pub fn changed_files(root: &Path, from: &str, to: &str) -> Result<HashSet<String>> {
+    let output = git(
+        root,
+        &[
+            "diff",
+            "--name-only",
+            "--relative",
+            "-z",
+            "--no-renames",
+            "--diff-filter=d",
+            from,
+            to,
+            "--",
+        ],
+    )?;
+
+    Ok(output
+        .split(|byte| *byte == 0)
+        .filter(|path| !path.is_empty())
+        .map(|path| String::from_utf8_lossy(path).into_owned())
+        .collect())
+}
+
+fn git(repository: &Path, args: &[&str]) -> Result<Vec<u8>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repository)
+        .args(args)
+        .output()
+        .context("running git")?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(output.stdout)
+}
+
+/// Reads blobs through a single `git cat-file --batch`.
+struct BlobReader {
+    child: Child,
+    input: ChildStdin,
+    output: BufReader<ChildStdout>,
+}
+
+impl BlobReader {
+    fn new(repository: &Path) -> Result<Self> {
+        let mut child = Command::new("git")
+            .arg("-C")
+            .arg(repository)
+            .args(["cat-file", "--batch"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .context("running git cat-file")?;
+
+        Ok(Self {
+            input: child.stdin.take().unwrap(),
+            output: BufReader::new(child.stdout.take().unwrap()),
+            child,
+        })
+    }
+
+    fn read(&mut self, object: &str) -> Result<Vec<u8>> {
+        writeln!(self.input, "{}", object)?;
+
+        // <object> <type> <size>, or <object> missing
+        let mut header = String::new();
+        self.output.read_line(&mut header)?;
+        let size = match header.split_whitespace().collect::<Vec<_>>()[..] {
+            [_, "blob", size] => size.parse::<usize>().context("parsing cat-file")?,
+            _ => bail!("cannot read blob {}: {}", object, header.trim()),
+        };
+
+        // The contents are followed by a newline
+        let mut contents = vec![0; size + 1];
+        self.output.read_exact(&mut contents)?;
+        contents.pop();
+
+        Ok(contents)
+    }
+}
+
+impl Drop for BlobReader {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(all(test, feature = "go"))]
+mod test {
+    use std::fs;
+
+    use scip::types::Document;
+
+    use super::*;
+    use crate::index::index_directory;
+
+    fn commit(repository: &Path, files: &[(&str, &str)]) -> Result<()> {
+        for (path, contents) in files {
+            let path = repository.join(path);
+            fs::create_dir_all(path.parent().unwrap())?;
+            fs::write(path, contents)?;
+        }
+
+        git(repository, &["add", "-A"])?;
+        git(
+            repository,
+            &[
+                "-c",
+                "user.name=test",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "-q",
+                "-m",
+                "test",
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    fn revision_documents(
+        repository: &Path,
+        revision: &str,
+        since: Option<&str>,
+    ) -> Result<Vec<Document>> {
+        let mut documents = vec![];
+        index_revision(
+            repository,
+            revision,
+            since,
+            &IndexOptions::default(),
+            |parsed| {
+                documents.push(parsed.document);
+                Ok(())
+            },
+        )?;
+
+        Ok(documents)
+    }
+
+    #[test]
+    fn test_indexes_revisions_without_checking_them_out() -> Result<()> {
+        let directory = tempfile::tempdir()?;
+        let repository = directory.path().to_path_buf();
+        git(&repository, &["init", "-q"])?;
+
+        commit(
+            &repository,
+            &[
+                ("main.go", "package main\n\nfunc main() {}\n"),
+                ("pkg/util.go", "package pkg\n\nfunc Util() {}\n"),
+                ("pkg-a.go", "package main\n\nfunc A() {}\n"),
+                ("README.md", "nothing to index\n"),
+            ],
+        )?;
+        let first = revision_documents(&repository, "HEAD", None)?;
+        let checkout = index_directory(&repository, &IndexOptions::default())?;
+        assert_eq!(first, checkout.documents.to_vec());
+
+        commit(
+            &repository,
+            &[
+                ("main.go", "package main\n\nfunc main() { Added() }\n"),
+                ("added.go", "package main\n\nfunc Added() {}\n"),
+            ],
+        )?;
+        let changed = revision_documents(&repository, "HEAD", Some("HEAD~1"))?;
+        let paths = changed
+            .iter()
+            .map(|doc| doc.relative_path.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(paths, ["added.go", "main.go"]);
+
+        // The working tree isn't read, so it can be anywhere
+        fs::remove_file(repository.join("main.go"))?;
+        assert_eq!(revision_documents(&repository, "HEAD~1", None)?, first);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_indexes_the_subdirectory_of_a_revision() -> Result<()> {
+        let directory = tempfile::tempdir()?;
+        let repository = directory.path().to_path_buf();
+        git(&repository, &["init", "-q"])?;
+
+        commit(
+            &repository,
+            &[
+                ("main.go", "package main\n\nfunc main() {}\n"),
+                ("pkg/util.go", "package pkg\n\nfunc Util() {}\n"),
+            ],
+        )?;
+        commit(
+            &repository,
+            &[
+                ("main.go", "package main\n\nfunc main() { pkg.Util() }\n"),
+                ("pkg/more.go", "package pkg\n\nfunc More() {}\n"),
+            ],
+        )?;
+
+        let subdirectory = repository.join("pkg");
+        let all = revision_documents(&subdirectory, "HEAD", None)?;
+        let checkout = index_directory(&subdirectory, &IndexOptions::default())?;
+        assert_eq!(all, checkout.documents.to_vec());
+
+        let changed = revision_documents(&subdirectory, "HEAD", Some("HEAD~1"))?;
+        let paths = changed
+            .iter()
+            .map(|doc| doc.relative_path.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(paths, ["more.go"]);
+
+        Ok(())
+    }
+}
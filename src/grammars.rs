@@ -0,0 +1,197 @@
+//! Grammars compiled as shared libraries and loaded at runtime, so that a language
+//! can be added without a new crate dependency and a rebuild.
+
+use std::{fs, path::Path};
+
+use anyhow::{bail, Context, Result};
+use libloading::{Library, Symbol};
+use serde_json::Value;
+use tree_sitter::{Language, LANGUAGE_VERSION, MIN_COMPATIBLE_LANGUAGE_VERSION};
+
+use crate::{
+    digest::digest,
+    languages::{compile_query, register_language, DynamicLanguage},
+};
+
+/// Loads the `tree_sitter_<name>` grammar from a shared library built from a
+/// tree-sitter `parser.c` (and `scanner.c`).
+pub fn load_grammar(path: &Path, name: &str) -> Result<Language> {
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        bail!("invalid grammar name \"{}\"", name);
+    }
+
+    let symbol = format!("tree_sitter_{name}");
+    let language = unsafe {
+        let library = Library::new(path)
+            .with_context(|| format!("loading grammar from {}", path.display()))?;
+
+        let language = {
+            let constructor: Symbol<unsafe extern "C" fn() -> Language> = library
+                .get(symbol.as_bytes())
+                .with_context(|| format!("{} has no {}", path.display(), symbol))?;
+            constructor()
+        };
+
+        // The language points into the library, which must outlive every parser.
+        std::mem::forget(library);
+        language
+    };
+
+    check_abi_version(name, language.version())?;
+    Ok(language)
+}
+
+pub fn check_abi_version(name: &str, version: usize) -> Result<()> {
+    if !(MIN_COMPATIBLE_LANGUAGE_VERSION..=LANGUAGE_VERSION).contains(&version) {
+        bail!(
+            "grammar {} was generated with ABI version {}, expected {} to {}. Regenerate it with a compatible tree-sitter CLI.",
+            name,
+            version,
+            MIN_COMPATIBLE_LANGUAGE_VERSION,
+            LANGUAGE_VERSION
+        );
+    }
+
+    Ok(())
+}
+
+/// What the registry needs to know about a language besides its grammar and queries,
+/// read from `<queries>/<name>/language.json`:
+///
+/// ```json
+/// { "line_comment": "#", "extensions": ["ml", "mli"] }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LanguageInfo {
+    pub line_comment: String,
+    /// Without the leading dot
+    pub extensions: Vec<String>,
+}
+
+impl LanguageInfo {
+    pub fn parse(path: &Path, source: &str) -> Result<Self> {
+        let value: Value =
+            serde_json::from_str(source).with_context(|| format!("parsing {}", path.display()))?;
+
+        let Some(line_comment) = value["line_comment"].as_str() else {
+            bail!("{}: expected a \"line_comment\" string", path.display());
+        };
+        let Some(extensions) = value["extensions"].as_array() else {
+            bail!("{}: expected an \"extensions\" array", path.display());
+        };
+        let extensions = extensions
+            .iter()
+            .map(|extension| match extension.as_str() {
+                Some(extension) => Ok(extension.trim_start_matches('.').to_string()),
+                None => bail!("{}: extensions must be strings", path.display()),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            line_comment: line_comment.to_string(),
+            extensions,
+        })
+    }
+}
+
+/// Loads a grammar with its queries and [`LanguageInfo`] from `<queries>/<name>/`,
+/// and registers it so that it can be used like the built-in languages.
+pub fn load_dynamic_language(name: &str, library: &Path, queries: &Path) -> Result<()> {
+    let language = load_grammar(library, name)?;
+
+    let directory = queries.join(name);
+    let info_path = directory.join("language.json");
+    let info = fs::read_to_string(&info_path)
+        .with_context(|| format!("reading {}", info_path.display()))?;
+    let info = LanguageInfo::parse(&info_path, &info)?;
+
+    let load = |file: &str| -> Result<Option<&'static str>> {
+        let path = directory.join(format!("{file}.scm"));
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        let source =
+            fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+        compile_query(language, &path, &source)?;
+
+        Ok(Some(Box::leak(source.into_boxed_str())))
+    };
+
+    let tags = load("scip-tags")?;
+    let locals = load("scip-locals")?;
+    let highlights = load("scip-highlights")?;
+    let injections = load("scip-injections")?;
+    if tags.is_none() && locals.is_none() && highlights.is_none() && injections.is_none() {
+        bail!("no queries for {} in {}", name, directory.display());
+    }
+
+    let grammar = fs::read(library).with_context(|| format!("reading {}", library.display()))?;
+    register_language(DynamicLanguage {
+        name: Box::leak(name.to_string().into_boxed_str()),
+        line_comment: Box::leak(info.line_comment.into_boxed_str()),
+        extensions: Box::leak(
+            info.extensions
+                .into_iter()
+                .map(|extension| &*Box::leak(extension.into_boxed_str()))
+                .collect::<Vec<_>>()
+                .into_boxed_slice(),
+        ),
+        language,
+        tags,
+        locals,
+        highlights,
+        injections,
+        grammar_digest: Box::leak(digest(&[&grammar]).into_boxed_str()),
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_checks_abi_version() {
+        assert!(check_abi_version("go", LANGUAGE_VERSION).is_ok());
+        assert!(check_abi_version("go", MIN_COMPATIBLE_LANGUAGE_VERSION).is_ok());
+
+        let err = check_abi_version("go", LANGUAGE_VERSION + 1).unwrap_err();
+        assert!(err.to_string().contains("ABI version"), "{}", err);
+    }
+
+    #[test]
+    fn test_rejects_invalid_grammars() {
+        let err = load_grammar(Path::new("/does/not/exist.so"), "go").unwrap_err();
+        assert!(
+            err.to_string().starts_with("loading grammar from"),
+            "{}",
+            err
+        );
+
+        let err = load_grammar(Path::new("grammar.so"), "../go").unwrap_err();
+        assert!(err.to_string().contains("invalid grammar name"), "{}", err);
+    }
+
+    #[test]
+    fn test_parses_language_info() -> Result<()> {
+        let path = Path::new("mylang/language.json");
+        let info = LanguageInfo::parse(
+            path,
+            r##"{ "line_comment": "#", "extensions": ["ml", ".mli"] }"##,
+        )?;
+        assert_eq!(
+            info,
+            LanguageInfo {
+                line_comment: "#".to_string(),
+                extensions: vec!["ml".to_string(), "mli".to_string()],
+            }
+        );
+
+        let err = LanguageInfo::parse(path, r#"{ "extensions": [] }"#).unwrap_err();
+        assert!(err.to_string().contains("line_comment"), "{}", err);
+
+        Ok(())
+    }
+}
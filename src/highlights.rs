@@ -0,0 +1,94 @@
+use anyhow::Result;
+use rustc_hash::FxHashMap as HashMap;
+use scip::types::Occurrence;
+use scip_treesitter::prelude::*;
+use tree_sitter::Query;
+
+use crate::{limits::MatchBudget, snapshot::PackedRange, ts_scip::capture_name_to_syntax_kind};
+
+/// Runs a `scip-highlights` query over the tree, returning one symbol-less
+/// occurrence per highlighted range with its `syntax_kind` set.
+///
+/// If several patterns capture the same range, the one declared first in the
+/// query wins, the same way tree-sitter's own highlighter resolves them.
+pub fn get_highlights<'a>(
+    query: &Query,
+    tree: &'a tree_sitter::Tree,
+    source_bytes: &'a [u8],
+) -> Result<Vec<Occurrence>> {
+    get_highlights_within(query, tree, source_bytes, &mut MatchBudget::default())
+}
+
+/// Same as [`get_highlights`], stopping once `budget` is spent.
+pub fn get_highlights_within<'a>(
+    query: &Query,
+    tree: &'a tree_sitter::Tree,
+    source_bytes: &'a [u8],
+    budget: &mut MatchBudget,
+) -> Result<Vec<Occurrence>> {
+    let mut cursor = tree_sitter::QueryCursor::new();
+
+    let root_node = tree.root_node();
+    let capture_names = query.capture_names();
+
+    let mut highlighted = HashMap::default();
+    for m in budget.limit(cursor.matches(query, root_node, source_bytes)) {
+        for capture in m.captures {
+            let capture_name = capture_names
+                .get(capture.index as usize)
+                .expect("capture indexes should always work");
+
+            let Some(kind) = capture_name_to_syntax_kind(capture_name) else {
+                continue;
+            };
+
+            highlighted
+                .entry(capture.node.to_scip_range())
+                .and_modify(|existing: &mut (usize, _)| {
+                    if m.pattern_index < existing.0 {
+                        *existing = (m.pattern_index, kind);
+                    }
+                })
+                .or_insert((m.pattern_index, kind));
+        }
+    }
+
+    let mut occurrences = highlighted
+        .into_iter()
+        .map(|(range, (_, kind))| Occurrence {
+            range,
+            syntax_kind: kind.into(),
+            ..Default::default()
+        })
+        .collect::<Vec<_>>();
+
+    occurrences.sort_by_key(|o| PackedRange::from_vec(&o.range));
+    Ok(occurrences)
+}
+
+#[cfg(all(test, feature = "rust"))]
+mod test {
+    use scip::types::Document;
+
+    use super::*;
+    use crate::snapshot::dump_document;
+
+    #[test]
+    fn test_can_highlight_rust() -> Result<()> {
+        let config = crate::languages::rust_configuration();
+        let source_code = include_str!("../testdata/rust/scopes.rs");
+
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(config.language)?;
+        let tree = parser.parse(source_code.as_bytes(), None).unwrap();
+
+        let query = config.highlights.as_ref().expect("rust has highlights");
+        let mut doc = Document::new();
+        doc.occurrences = get_highlights(query, &tree, source_code.as_bytes())?;
+
+        let dumped = dump_document(&doc, source_code);
+        insta::assert_snapshot!(dumped);
+
+        Ok(())
+    }
+}
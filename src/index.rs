@@ -0,0 +1,514 @@
+//! Indexes every supported file under a directory into a single SCIP index.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, Condvar, Mutex, PoisonError,
+    },
+};
+
+use anyhow::{Context, Result};
+use protobuf::{EnumOrUnknown, MessageField};
+use rustc_hash::FxHashMap as HashMap;
+use scip::types::{Index, Metadata, TextEncoding, ToolInfo};
+use walkdir::WalkDir;
+
+use crate::{
+    cache::DocumentCache,
+    detect::Detector,
+    document::ParsedDocument,
+    languages::{load_language_configuration, LanguageConfiguration},
+    limits::{parse_document_within, skipped_document, tags_only_reason, Limits, Reason},
+};
+
+#[derive(Default)]
+pub struct IndexOptions {
+    /// See [`load_language_configuration`]
+    pub queries: Option<PathBuf>,
+    pub detector: Detector,
+    pub limits: Limits,
+    /// Worker threads. With 0 or 1, files are indexed on the calling thread.
+    pub jobs: usize,
+    /// Reuses the documents of files that didn't change since an earlier run
+    pub cache: Option<DocumentCache>,
+}
+
+/// Indexes `root` into an `Index` held in memory, see [`index_documents`] to stream it.
+pub fn index_directory(root: &Path, options: &IndexOptions) -> Result<Index> {
+    let mut index = Index::new();
+    index.metadata = MessageField::some(index_metadata(root)?);
+    index_documents(root, options, |parsed| {
+        index.documents.push(parsed.document);
+        Ok(())
+    })?;
+
+    Ok(index)
+}
+
+/// Calls `emit` with the document of every supported file under `root`, in the same
+/// order whatever the number of jobs, as soon as it and every document before it are ready.
+pub fn index_documents(
+    root: &Path,
+    options: &IndexOptions,
+    emit: impl FnMut(ParsedDocument) -> Result<()>,
+) -> Result<()> {
+    let root = canonical_root(root)?;
+    let files = walk_files(&root)?;
+
+    index_in_order(
+        files.len(),
+        options.jobs,
+        || Ok(HashMap::default()),
+        |configs, idx| {
+            let path = &files[idx];
+            index_file(path, &relative_path(&root, path), options, configs)
+        },
+        emit,
+    )
+}
+
+/// The configurations loaded so far by one worker, by language name.
+pub(crate) type Configurations = HashMap<String, Option<LanguageConfiguration>>;
+
+/// Calls `index` with every index below `count`, on `jobs` threads each with its own
+/// state from `init`, and `emit` with the documents in order of their index.
+pub(crate) fn index_in_order<S, D: Send>(
+    count: usize,
+    jobs: usize,
+    init: impl Fn() -> Result<S> + Sync,
+    index: impl Fn(&mut S, usize) -> Result<Option<D>> + Sync,
+    mut emit: impl FnMut(D) -> Result<()>,
+) -> Result<()> {
+    if jobs <= 1 {
+        let mut state = init()?;
+        for idx in 0..count {
+            if let Some(doc) = index(&mut state, idx)? {
+                emit(doc)?;
+            }
+        }
+
+        return Ok(());
+    }
+
+    // Files are only handed out this far ahead of the next one to emit, so that workers
+    // running ahead of a slow file don't pile up documents waiting for it
+    let window = jobs * 4;
+    let next = AtomicUsize::new(0);
+    let emitted = Emitted::default();
+    std::thread::scope(|scope| {
+        let (sender, receiver) = mpsc::sync_channel(window);
+        for _ in 0..jobs {
+            let sender = sender.clone();
+            let (next, emitted, init, index) = (&next, &emitted, &init, &index);
+            scope.spawn(move || {
+                // A panicking worker would otherwise leave the others waiting for its file
+                let _stop = StopOnPanic(emitted);
+                let mut state = match init() {
+                    Ok(state) => state,
+                    Err(err) => {
+                        let _ = sender.send((next.load(Ordering::Relaxed), Err(err)));
+                        return;
+                    }
+                };
+
+                loop {
+                    let idx = next.fetch_add(1, Ordering::Relaxed);
+                    if idx >= count || !emitted.wait_for(idx, window) {
+                        break;
+                    }
+
+                    let doc = index(&mut state, idx);
+                    let failed = doc.is_err();
+                    // The receiver is only gone once indexing failed elsewhere
+                    if sender.send((idx, doc)).is_err() || failed {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(sender);
+
+        let mut pending = BTreeMap::new();
+        let mut expected = 0;
+        let result = receiver.into_iter().try_for_each(|(idx, doc)| {
+            pending.insert(idx, doc?);
+            while let Some(doc) = pending.remove(&expected) {
+                expected += 1;
+                if let Some(doc) = doc {
+                    emit(doc)?;
+                }
+            }
+            emitted.advance(expected);
+
+            Ok(())
+        });
+        if result.is_err() {
+            emitted.stop();
+        }
+
+        result
+    })
+}
+
+/// How many documents were emitted, for workers to wait on.
+#[derive(Default)]
+struct Emitted {
+    count: Mutex<usize>,
+    advanced: Condvar,
+}
+
+impl Emitted {
+    fn advance(&self, count: usize) {
+        let mut current = self.count.lock().unwrap_or_else(PoisonError::into_inner);
+        // Once stopped, it stays stopped
+        *current = count.max(*current);
+        self.advanced.notify_all();
+    }
+
+    /// Lets every waiting worker go, to find out that indexing stopped.
+    fn stop(&self) {
+        self.advance(usize::MAX);
+    }
+
+    /// Waits until `idx` is within `window` of the next document to emit, and returns
+    /// whether indexing goes on.
+    fn wait_for(&self, idx: usize, window: usize) -> bool {
+        let count = self.count.lock().unwrap_or_else(PoisonError::into_inner);
+        let count = self
+            .advanced
+            .wait_while(count, |count| idx >= count.saturating_add(window))
+            .unwrap_or_else(PoisonError::into_inner);
+
+        *count != usize::MAX
+    }
+}
+
+struct StopOnPanic<'a>(&'a Emitted);
+
+impl Drop for StopOnPanic<'_> {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            self.0.stop();
+        }
+    }
+}
+
+/// Calls `f` with the relative path, configuration and contents of every supported file
+/// under `root` within the size limit, for outputs that don't need whole documents.
+pub fn for_each_source(
+    root: &Path,
+    options: &IndexOptions,
+    mut f: impl FnMut(&str, &mut LanguageConfiguration, &str) -> Result<()>,
+) -> Result<()> {
+    let root = canonical_root(root)?;
+    let mut configs = Configurations::default();
+    for path in walk_files(&root)? {
+        let size = fs::metadata(&path)?.len();
+        if options.limits.check_size(size).is_some() {
+            continue;
+        }
+
+        let source = match fs::read_to_string(&path) {
+            Ok(source) => source,
+            Err(err) if err.kind() == std::io::ErrorKind::InvalidData => continue,
+            Err(err) => return Err(err).with_context(|| format!("reading {}", path.display())),
+        };
+        let relative_path = relative_path(&root, &path);
+        let Some(detection) = options.detector.detect(&relative_path, &source) else {
+            continue;
+        };
+        let Some(config) = configuration(&mut configs, &detection.language, options)? else {
+            continue;
+        };
+
+        f(&slash_path(&relative_path), config, &source)
+            .with_context(|| format!("indexing {}", path.display()))?;
+    }
+
+    Ok(())
+}
+
+/// Metadata for an index of `root`.
+pub fn index_metadata(root: &Path) -> Result<Metadata> {
+    let root = canonical_root(root)?;
+
+    Ok(Metadata {
+        tool_info: MessageField::some(ToolInfo {
+            name: "scip-semantic".to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            ..Default::default()
+        }),
+        project_root: format!("file://{}", root.display()),
+        text_document_encoding: EnumOrUnknown::new(TextEncoding::UTF8),
+        ..Default::default()
+    })
+}
+
+fn canonical_root(root: &Path) -> Result<PathBuf> {
+    root.canonicalize()
+        .with_context(|| format!("reading {}", root.display()))
+}
+
+/// Every file under `root`, sorted and without hidden entries.
+fn walk_files(root: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    let entries = WalkDir::new(root)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| entry.depth() == 0 || !is_hidden(entry.file_name()));
+    for entry in entries {
+        let entry = entry?;
+        if entry.file_type().is_file() {
+            files.push(entry.into_path());
+        }
+    }
+
+    Ok(files)
+}
+
+/// Indexes one file, or returns `None` when its language isn't supported.
+fn index_file(
+    path: &Path,
+    relative_path: &Path,
+    options: &IndexOptions,
+    configs: &mut Configurations,
+) -> Result<Option<ParsedDocument>> {
+    let size = fs::metadata(path)?.len();
+    index_contents(
+        relative_path,
+        size,
+        options,
+        configs,
+        || match fs::read_to_string(path) {
+            Ok(source) => Ok(Some(source)),
+            Err(err) if err.kind() == std::io::ErrorKind::InvalidData => Ok(None),
+            Err(err) => Err(err).with_context(|| format!("reading {}", path.display())),
+        },
+    )
+}
+
+/// Indexes a file of `size` bytes wherever it is stored. `read` gives its contents, or
+/// `None` when they aren't UTF-8, and isn't called for files over the size limit.
+pub(crate) fn index_contents(
+    relative_path: &Path,
+    size: u64,
+    options: &IndexOptions,
+    configs: &mut Configurations,
+    read: impl FnOnce() -> Result<Option<String>>,
+) -> Result<Option<ParsedDocument>> {
+    let oversized = options.limits.check_size(size);
+
+    // Huge files are never read, so their language comes from their path only
+    let source = match oversized {
+        Some(_) => String::new(),
+        None => match read()? {
+            Some(source) => source,
+            None => return Ok(None),
+        },
+    };
+
+    let Some(detection) = options.detector.detect(relative_path, &source) else {
+        return Ok(None);
+    };
+    let Some(config) = configuration(configs, &detection.language, options)? else {
+        return Ok(None);
+    };
+
+    let mut parsed = match oversized {
+        Some(reason) => skipped_document(config.name, reason),
+        None => index_source(config, &source, options)
+            .with_context(|| format!("indexing {}", relative_path.display()))?,
+    };
+    parsed.document.relative_path = slash_path(relative_path);
+
+    Ok(Some(parsed))
+}
+
+/// The configuration of `language`, loaded the first time it's needed.
+fn configuration<'c>(
+    configs: &'c mut Configurations,
+    language: &str,
+    options: &IndexOptions,
+) -> Result<Option<&'c mut LanguageConfiguration>> {
+    if !configs.contains_key(language) {
+        let config = load_language_configuration(language, options.queries.as_deref())?;
+        configs.insert(language.to_string(), config);
+    }
+
+    Ok(configs.get_mut(language).and_then(Option::as_mut))
+}
+
+fn slash_path(path: &Path) -> String {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Parses `source`, or takes its document from the cache when there is one.
+fn index_source(
+    config: &mut LanguageConfiguration,
+    source: &str,
+    options: &IndexOptions,
+) -> Result<ParsedDocument> {
+    let Some(cache) = &options.cache else {
+        return parse_document_within(config, source, &options.limits);
+    };
+
+    // Only deterministic documents are cached, and the only reason they can have
+    // comes from the contents and the limits, which are part of the key
+    let key = cache.key(config, source);
+    if let Some(document) = cache.get(&key) {
+        return Ok(ParsedDocument {
+            document,
+            reason: tags_only_reason(source, &options.limits),
+        });
+    }
+
+    let parsed = parse_document_within(config, source, &options.limits)?;
+    if parsed.reason.as_ref().is_none_or(Reason::is_deterministic) {
+        cache.insert(&key, &parsed.document)?;
+    }
+
+    Ok(parsed)
+}
+
+pub(crate) fn is_hidden(name: &std::ffi::OsStr) -> bool {
+    name.to_str().filter(|name| name.starts_with('.')).is_some()
+}
+
+fn relative_path(root: &Path, path: &Path) -> PathBuf {
+    path.strip_prefix(root).unwrap_or(path).to_path_buf()
+}
+
+#[cfg(all(test, feature = "go"))]
+mod test {
+    use protobuf::Message;
+
+    use super::*;
+    use crate::{
+        cache::DocumentCache,
+        limits::{GeneratedPolicy, TAGS_ONLY},
+        writer::IndexWriter,
+    };
+
+    #[test]
+    fn test_records_skipped_files() -> Result<()> {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/go");
+        let options = IndexOptions {
+            limits: Limits {
+                max_file_size: Some(300),
+                generated: GeneratedPolicy::Skip,
+                ..Limits::none()
+            },
+            ..Default::default()
+        };
+
+        let mut documents = vec![];
+        index_documents(&root, &options, |parsed| {
+            documents.push(parsed);
+            Ok(())
+        })?;
+        assert!(!documents.is_empty());
+
+        let mut skipped = 0;
+        for ParsedDocument { document, reason } in &documents {
+            let size = fs::metadata(root.join(&document.relative_path))?.len();
+            assert_eq!(size > 300, reason.is_some(), "{}", document.relative_path);
+            if reason.is_some() {
+                // The reason is reported next to the document, not inside it
+                assert!(document.occurrences.is_empty());
+                skipped += 1;
+            }
+        }
+        assert!(skipped > 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_caches_generated_files_indexed_by_policy() -> Result<()> {
+        let root = tempfile::tempdir()?;
+        fs::write(
+            root.path().join("mocks.go"),
+            "// Code generated by mockgen. DO NOT EDIT.\n\npackage mocks\n\nfunc New() {}\n",
+        )?;
+        let cache = tempfile::tempdir()?;
+        let options = IndexOptions {
+            cache: Some(DocumentCache::open(cache.path(), &Limits::default(), None)?),
+            ..Default::default()
+        };
+
+        let mut runs = vec![];
+        for _ in 0..2 {
+            let mut documents = vec![];
+            index_documents(root.path(), &options, |parsed| {
+                documents.push(parsed);
+                Ok(())
+            })?;
+            runs.push(documents);
+        }
+
+        // Reused the second time, with the same reason
+        assert_eq!(options.cache.as_ref().unwrap().stats(), (1, 1));
+        assert_eq!(runs[0], runs[1]);
+        assert_eq!(runs[0][0].reason.as_ref().unwrap().code, TAGS_ONLY);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_workers_stay_within_a_window_of_a_slow_file() -> Result<()> {
+        let slow_done = std::sync::atomic::AtomicBool::new(false);
+        let furthest = AtomicUsize::new(0);
+
+        let mut emitted = vec![];
+        index_in_order(
+            100,
+            2,
+            || Ok(()),
+            |_, idx| {
+                if idx == 0 {
+                    std::thread::sleep(std::time::Duration::from_millis(200));
+                    slow_done.store(true, Ordering::Relaxed);
+                } else if !slow_done.load(Ordering::Relaxed) {
+                    furthest.fetch_max(idx, Ordering::Relaxed);
+                }
+                Ok(Some(idx))
+            },
+            |idx| {
+                emitted.push(idx);
+                Ok(())
+            },
+        )?;
+
+        assert_eq!(emitted, (0..100).collect::<Vec<_>>());
+        assert!(furthest.load(Ordering::Relaxed) < 2 * 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_streams_the_same_index_with_jobs() -> Result<()> {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata");
+        let buffered = index_directory(&root, &IndexOptions::default())?.write_to_bytes()?;
+
+        let options = IndexOptions {
+            jobs: 4,
+            ..Default::default()
+        };
+        let metadata = index_metadata(&root)?;
+        let mut writer = IndexWriter::new(vec![], Some(&metadata))?;
+        index_documents(&root, &options, |parsed| {
+            writer.write_document(&parsed.document)
+        })?;
+        assert!(writer.documents() > 1);
+
+        assert_eq!(writer.finish()?, buffered);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,169 @@
+use anyhow::{bail, Result};
+use tree_sitter::{Point, Query, QueryPredicateArg, Range};
+
+/// A region of the host document written in another language.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Injection {
+    pub language: String,
+    pub range: Range,
+}
+
+/// Runs a `scip-injections` query, following tree-sitter's conventions:
+///
+/// - `@injection.content` is the region to parse with the other grammar
+/// - the language is either set with `(#set! injection.language "sql")`, or read from
+///   the text of `@injection.language`. For a comment like `// language=sql`, the
+///   word after `language=` is used.
+/// - `(#offset! @injection.content start_row start_col end_row end_col)` moves the
+///   edges of the region, for example to leave out the quotes of a string
+pub fn get_injections(
+    query: &Query,
+    tree: &tree_sitter::Tree,
+    source_bytes: &[u8],
+) -> Result<Vec<Injection>> {
+    let mut cursor = tree_sitter::QueryCursor::new();
+    let capture_names = query.capture_names();
+    let line_starts = line_starts(source_bytes);
+
+    let mut injections = vec![];
+    for m in cursor.matches(query, tree.root_node(), source_bytes) {
+        let mut language = query
+            .property_settings(m.pattern_index)
+            .iter()
+            .find(|prop| &(*prop.key) == "injection.language")
+            .and_then(|prop| prop.value.as_deref())
+            .map(|language| language.to_string());
+        let mut content = None;
+
+        for capture in m.captures {
+            match capture_names[capture.index as usize].as_str() {
+                "injection.content" => content = Some(capture.node),
+                "injection.language" if language.is_none() => {
+                    language = language_name(capture.node.utf8_text(source_bytes)?)
+                }
+                _ => {}
+            }
+        }
+
+        let (Some(language), Some(content)) = (language, content) else {
+            continue;
+        };
+
+        let mut range = content.range();
+        for predicate in query.general_predicates(m.pattern_index) {
+            if &(*predicate.operator) == "offset!" {
+                range = offset_range(range, &predicate.args, &line_starts)?;
+            }
+        }
+
+        if range.start_byte < range.end_byte {
+            injections.push(Injection { language, range });
+        }
+    }
+
+    Ok(injections)
+}
+
+fn language_name(text: &str) -> Option<String> {
+    let name = match text.split_once("language=") {
+        Some((_, rest)) => rest.split_whitespace().next()?,
+        None => text.trim(),
+    };
+
+    (!name.is_empty()).then(|| name.to_lowercase())
+}
+
+fn line_starts(source_bytes: &[u8]) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(
+            source_bytes
+                .iter()
+                .enumerate()
+                .filter(|(_, byte)| **byte == b'\n')
+                .map(|(idx, _)| idx + 1),
+        )
+        .collect()
+}
+
+fn offset_range(range: Range, args: &[QueryPredicateArg], line_starts: &[usize]) -> Result<Range> {
+    let offsets = args
+        .iter()
+        .skip(1)
+        .map(|arg| match arg {
+            QueryPredicateArg::String(value) => Ok(value.parse::<i64>()?),
+            QueryPredicateArg::Capture(_) => bail!("#offset! takes a capture and four numbers"),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let [start_row, start_col, end_row, end_col] = offsets[..] else {
+        bail!("#offset! takes a capture and four numbers");
+    };
+
+    let shift = |point: Point, rows: i64, columns: i64| {
+        let row = (point.row as i64 + rows).max(0) as usize;
+        let column = (point.column as i64 + columns).max(0) as usize;
+        let byte = line_starts.get(row).copied().unwrap_or_default() + column;
+
+        (Point::new(row, column), byte)
+    };
+
+    let (start_point, start_byte) = shift(range.start_point, start_row, start_col);
+    let (end_point, end_byte) = shift(range.end_point, end_row, end_col);
+
+    Ok(Range {
+        start_byte,
+        end_byte,
+        start_point,
+        end_point,
+    })
+}
+
+#[cfg(all(test, feature = "go"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_finds_tagged_raw_strings() -> Result<()> {
+        let mut config = crate::languages::go_configuration();
+        let source = "package main\n\n// language=rust\nconst snippet = `fn main() {}`\n";
+        let tree = config.parser.parse(source, None).unwrap();
+
+        let injections = get_injections(
+            config.injections.as_ref().unwrap(),
+            &tree,
+            source.as_bytes(),
+        )?;
+
+        assert_eq!(injections.len(), 1);
+        assert_eq!(injections[0].language, "rust");
+        let range = injections[0].range;
+        assert_eq!(&source[range.start_byte..range.end_byte], "fn main() {}");
+        assert_eq!(range.start_point, Point::new(3, 17));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "rust")]
+    fn test_injected_languages_use_the_overridden_queries() -> Result<()> {
+        let queries = tempfile::tempdir()?;
+        std::fs::create_dir_all(queries.path().join("rust"))?;
+        std::fs::write(
+            queries.path().join("rust/scip-tags.scm"),
+            "(function_item name: (identifier) @descriptor.type)",
+        )?;
+
+        let source = "package main\n\n// language=rust\nconst snippet = `fn main() {}`\n";
+        let mut config =
+            crate::languages::load_language_configuration("go", Some(queries.path()))?.unwrap();
+        let doc = crate::document::parse_document(&mut config, source)?;
+
+        let symbols = doc
+            .occurrences
+            .iter()
+            .map(|occurrence| occurrence.symbol.as_str())
+            .collect::<Vec<_>>();
+        assert!(symbols.contains(&"scip-ctags main#"), "{:?}", symbols);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,161 @@
+//! Reads back an existing index: what it holds per document, and any document annotated
+//! over its source like the snapshots are.
+
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use rustc_hash::FxHashSet as HashSet;
+use scip::types::{Document, Index};
+
+use crate::snapshot::dump_document;
+
+/// What one document of an index holds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentSummary {
+    pub relative_path: String,
+    pub language: String,
+    pub occurrences: usize,
+    /// Distinct symbols of its occurrences
+    pub symbols: usize,
+    /// Symbols with a `SymbolInformation`
+    pub symbol_information: usize,
+}
+
+impl DocumentSummary {
+    pub fn new(doc: &Document) -> Self {
+        let symbols = doc
+            .occurrences
+            .iter()
+            .filter(|occurrence| !occurrence.symbol.is_empty())
+            .map(|occurrence| occurrence.symbol.as_str())
+            .collect::<HashSet<_>>();
+
+        Self {
+            relative_path: doc.relative_path.clone(),
+            language: doc.language.clone(),
+            occurrences: doc.occurrences.len(),
+            symbols: symbols.len(),
+            symbol_information: doc.symbols.len(),
+        }
+    }
+}
+
+/// Writes the metadata of `index` and a line per document with its counts.
+pub fn write_summary(index: &Index, mut writer: impl Write) -> Result<()> {
+    let metadata = &index.metadata;
+    writeln!(
+        writer,
+        "{} {}, project root {}",
+        metadata.tool_info.name, metadata.tool_info.version, metadata.project_root
+    )?;
+    writeln!(
+        writer,
+        "{} documents, {} external symbols",
+        index.documents.len(),
+        index.external_symbols.len()
+    )?;
+    writeln!(writer)?;
+
+    let summaries = index
+        .documents
+        .iter()
+        .map(DocumentSummary::new)
+        .collect::<Vec<_>>();
+    let width = summaries
+        .iter()
+        .map(|summary| summary.relative_path.len())
+        .chain(["document".len()])
+        .max()
+        .unwrap_or_default();
+
+    writeln!(
+        writer,
+        "{:width$}  {:10}  {:>11}  {:>7}  {:>11}",
+        "document", "language", "occurrences", "symbols", "information"
+    )?;
+    for summary in &summaries {
+        writeln!(
+            writer,
+            "{:width$}  {:10}  {:>11}  {:>7}  {:>11}",
+            summary.relative_path,
+            summary.language,
+            summary.occurrences,
+            summary.symbols,
+            summary.symbol_information
+        )?;
+    }
+    writeln!(
+        writer,
+        "{:width$}  {:10}  {:>11}  {:>7}  {:>11}",
+        "total",
+        "",
+        summaries.iter().map(|s| s.occurrences).sum::<usize>(),
+        summaries.iter().map(|s| s.symbols).sum::<usize>(),
+        summaries
+            .iter()
+            .map(|s| s.symbol_information)
+            .sum::<usize>()
+    )?;
+
+    Ok(())
+}
+
+/// The directory the documents of `index` are relative to, when it's a local one.
+pub fn source_root(index: &Index) -> Option<PathBuf> {
+    index
+        .metadata
+        .project_root
+        .strip_prefix("file://")
+        .map(PathBuf::from)
+}
+
+/// `doc` annotated over its source under `root`, or `None` when the source isn't there.
+pub fn annotate_document(doc: &Document, root: &Path) -> Result<Option<String>> {
+    let path = root.join(&doc.relative_path);
+    if !path.is_file() {
+        return Ok(None);
+    }
+
+    let source =
+        fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+
+    Ok(Some(dump_document(doc, &source)))
+}
+
+#[cfg(all(test, feature = "go"))]
+mod test {
+    use super::*;
+    use crate::index::{index_directory, IndexOptions};
+
+    #[test]
+    fn test_summarizes_and_annotates_documents() -> Result<()> {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/go");
+        let index = index_directory(&root, &IndexOptions::default())?;
+
+        let mut summary = vec![];
+        write_summary(&index, &mut summary)?;
+        let summary = String::from_utf8(summary)?;
+        let lines = summary.lines().collect::<Vec<_>>();
+        assert!(lines[0].contains(&index.metadata.project_root));
+        // The header, the column names, a line per document and the total
+        assert_eq!(lines.len(), index.documents.len() + 5);
+
+        let doc = &index.documents[0];
+        assert!(DocumentSummary::new(doc).symbols > 0);
+        assert!(lines[4].starts_with(&doc.relative_path));
+
+        let source_root = source_root(&index).unwrap();
+        let source = fs::read_to_string(root.join(&doc.relative_path))?;
+        assert_eq!(
+            annotate_document(doc, &source_root)?,
+            Some(dump_document(doc, &source))
+        );
+        assert_eq!(annotate_document(doc, &root.join("missing"))?, None);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,148 @@
+//! SCIP messages as JSON, following the proto3 JSON mapping: fields are named in
+//! lowerCamelCase, enums by their name, and fields left to their default are omitted.
+//! Messages are walked through protobuf reflection, so it follows the SCIP schema as it grows.
+
+use std::io::Write;
+
+use anyhow::Result;
+use protobuf::{
+    reflect::{ReflectFieldRef, ReflectValueRef},
+    MessageDyn,
+};
+use scip::types::Index;
+use serde_json::{json, Map, Value};
+
+/// `message` as a JSON object.
+pub fn message_to_json(message: &dyn MessageDyn) -> Value {
+    let mut object = Map::new();
+    for field in message.descriptor_dyn().fields() {
+        let value = match field.get_reflect(message) {
+            ReflectFieldRef::Optional(value) => match value.value() {
+                Some(value) => value_to_json(value),
+                None => continue,
+            },
+            ReflectFieldRef::Repeated(values) if values.is_empty() => continue,
+            ReflectFieldRef::Repeated(values) => values.into_iter().map(value_to_json).collect(),
+            ReflectFieldRef::Map(entries) if entries.is_empty() => continue,
+            ReflectFieldRef::Map(entries) => Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key.to_string(), value_to_json(value)))
+                    .collect(),
+            ),
+        };
+
+        object.insert(field.json_name().to_string(), value);
+    }
+
+    Value::Object(object)
+}
+
+fn value_to_json(value: ReflectValueRef) -> Value {
+    match value {
+        ReflectValueRef::U32(value) => json!(value),
+        ReflectValueRef::I32(value) => json!(value),
+        // 64-bit integers are strings, as JavaScript numbers can't hold all of them
+        ReflectValueRef::U64(value) => json!(value.to_string()),
+        ReflectValueRef::I64(value) => json!(value.to_string()),
+        ReflectValueRef::F32(value) => json!(value),
+        ReflectValueRef::F64(value) => json!(value),
+        ReflectValueRef::Bool(value) => json!(value),
+        ReflectValueRef::String(value) => json!(value),
+        ReflectValueRef::Bytes(value) => json!(base64(value)),
+        ReflectValueRef::Enum(descriptor, number) => match descriptor.value_by_number(number) {
+            Some(value) => json!(value.name()),
+            None => json!(number),
+        },
+        ReflectValueRef::Message(message) => message_to_json(&*message),
+    }
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::new();
+    for chunk in bytes.chunks(3) {
+        let bits = chunk.iter().enumerate().fold(0u32, |bits, (idx, byte)| {
+            bits | (*byte as u32) << (16 - 8 * idx)
+        });
+        for idx in 0..4 {
+            match idx <= chunk.len() {
+                true => encoded.push(ALPHABET[(bits >> (18 - 6 * idx) & 0x3F) as usize] as char),
+                false => encoded.push('='),
+            }
+        }
+    }
+
+    encoded
+}
+
+/// Writes `index` as indented JSON.
+pub fn write_index_json(index: &Index, mut writer: impl Write) -> Result<()> {
+    serde_json::to_writer_pretty(&mut writer, &message_to_json(index))?;
+    writer.write_all(b"\n")?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use protobuf::{EnumOrUnknown, MessageField};
+    use scip::types::{Document, Metadata, Occurrence, SymbolRole, SyntaxKind, TextEncoding};
+
+    use super::*;
+
+    #[test]
+    fn test_follows_the_proto3_json_mapping() {
+        let mut index = Index::new();
+        index.metadata = MessageField::some(Metadata {
+            project_root: "file:///project".to_string(),
+            text_document_encoding: EnumOrUnknown::new(TextEncoding::UTF8),
+            ..Default::default()
+        });
+        index.documents.push(Document {
+            relative_path: "main.go".to_string(),
+            occurrences: vec![
+                Occurrence {
+                    range: vec![2, 5, 9],
+                    symbol: "local 1".to_string(),
+                    symbol_roles: SymbolRole::Definition as i32,
+                    ..Default::default()
+                },
+                Occurrence {
+                    range: vec![3, 0, 4],
+                    syntax_kind: EnumOrUnknown::new(SyntaxKind::IdentifierKeyword),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        });
+
+        assert_eq!(
+            message_to_json(&index),
+            json!({
+                "metadata": {
+                    "projectRoot": "file:///project",
+                    "textDocumentEncoding": "UTF8",
+                },
+                "documents": [{
+                    "relativePath": "main.go",
+                    "occurrences": [
+                        { "range": [2, 5, 9], "symbol": "local 1", "symbolRoles": 1 },
+                        { "range": [3, 0, 4], "syntaxKind": "IdentifierKeyword" },
+                    ],
+                }],
+            })
+        );
+    }
+
+    #[test]
+    fn test_encodes_bytes_as_base64() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    }
+}
@@ -1,15 +1,31 @@
-use scip_macros::include_scip_query;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use anyhow::{anyhow, Context, Result};
 use tree_sitter::{Language, Parser, Query};
 
+use crate::digest::digest;
+
+/// Environment variable pointing to a directory of queries, laid out like `queries/`
+/// in this repo (`<dir>/<lang>/scip-tags.scm`, ...), used instead of the embedded ones.
+/// It applies to every [`LanguageConfiguration`], including injected languages, but not
+/// to the single-query [`TagConfiguration`] and [`LocalConfiguration`].
+pub const QUERIES_ENV: &str = "SCIP_SEMANTIC_QUERIES";
+
+/// A single tags query, always the embedded one.
 pub struct TagConfiguration {
     pub language: Language,
     pub query: Query,
     pub parser: Parser,
 }
 
+#[cfg(feature = "rust")]
 pub fn rust() -> TagConfiguration {
     let language = scip_treesitter_languages::rust();
-    let query = include_scip_query!("rust", "scip-tags");
+    let query = scip_macros::include_scip_query!("rust", "scip-tags");
 
     let mut parser = Parser::new();
     parser.set_language(language).unwrap();
@@ -21,9 +37,10 @@ pub fn rust() -> TagConfiguration {
     }
 }
 
+#[cfg(feature = "go")]
 pub fn go() -> TagConfiguration {
     let language = scip_treesitter_languages::go();
-    let query = include_scip_query!("go", "scip-tags");
+    let query = scip_macros::include_scip_query!("go", "scip-tags");
 
     let mut parser = Parser::new();
     parser.set_language(language).unwrap();
@@ -35,15 +52,17 @@ pub fn go() -> TagConfiguration {
     }
 }
 
+/// A single locals query, always the embedded one.
 pub struct LocalConfiguration {
     pub language: Language,
     pub query: Query,
     pub parser: Parser,
 }
 
+#[cfg(feature = "go")]
 pub fn go_locals() -> LocalConfiguration {
     let language = scip_treesitter_languages::go();
-    let query = include_scip_query!("go", "scip-locals");
+    let query = scip_macros::include_scip_query!("go", "scip-locals");
 
     let mut parser = Parser::new();
     parser.set_language(language).unwrap();
@@ -54,3 +73,305 @@ pub fn go_locals() -> LocalConfiguration {
         query: Query::new(language, query).unwrap(),
     }
 }
+
+/// All the queries for a single language, sharing one parser so that a file
+/// only has to be parsed once to produce both global tags and locals.
+pub struct LanguageConfiguration {
+    pub name: &'static str,
+    /// Used to find inline expectations in test files, see [`crate::expectations`]
+    pub line_comment: &'static str,
+    pub language: Language,
+    pub parser: Parser,
+    pub tags: Option<Query>,
+    pub locals: Option<Query>,
+    pub highlights: Option<Query>,
+    /// Regions written in another language, see [`crate::injections`]
+    pub injections: Option<Query>,
+    /// Changes whenever the grammar or a query does, see [`crate::cache`]
+    pub fingerprint: String,
+    /// The directory the queries were overridden from, which injected languages
+    /// are loaded from too
+    pub queries: Option<PathBuf>,
+}
+
+impl LanguageConfiguration {
+    fn new(
+        name: &'static str,
+        line_comment: &'static str,
+        language: Language,
+        tags: Option<&str>,
+        locals: Option<&str>,
+        highlights: Option<&str>,
+        injections: Option<&str>,
+    ) -> Self {
+        let mut parser = Parser::new();
+        parser.set_language(language).unwrap();
+
+        let fingerprint = digest(&[
+            name.as_bytes(),
+            &language.version().to_le_bytes(),
+            tags.unwrap_or_default().as_bytes(),
+            locals.unwrap_or_default().as_bytes(),
+            highlights.unwrap_or_default().as_bytes(),
+            injections.unwrap_or_default().as_bytes(),
+        ]);
+
+        LanguageConfiguration {
+            name,
+            line_comment,
+            language,
+            parser,
+            fingerprint,
+            queries: None,
+            tags: tags.map(|query| Query::new(language, query).unwrap()),
+            locals: locals.map(|query| Query::new(language, query).unwrap()),
+            highlights: highlights.map(|query| Query::new(language, query).unwrap()),
+            injections: injections.map(|query| Query::new(language, query).unwrap()),
+        }
+    }
+
+    /// Replaces the embedded queries with the ones found in `<directory>/<name>/`.
+    /// Queries missing from the directory keep their embedded version.
+    pub fn override_queries(&mut self, directory: &Path) -> Result<()> {
+        self.queries = Some(directory.to_path_buf());
+
+        let language_directory = directory.join(self.name);
+        for (file, query) in [
+            ("scip-tags", &mut self.tags),
+            ("scip-locals", &mut self.locals),
+            ("scip-highlights", &mut self.highlights),
+            ("scip-injections", &mut self.injections),
+        ] {
+            let path = language_directory.join(format!("{file}.scm"));
+            if path.is_file() {
+                let source = fs::read_to_string(&path)
+                    .with_context(|| format!("reading {}", path.display()))?;
+                *query = Some(compile_query(self.language, &path, &source)?);
+                self.fingerprint = digest(&[
+                    self.fingerprint.as_bytes(),
+                    file.as_bytes(),
+                    source.as_bytes(),
+                ]);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Compiles the contents of the query at `path`.
+pub fn compile_query(language: Language, path: &Path, source: &str) -> Result<Query> {
+    Query::new(language, source).map_err(|err| {
+        anyhow!(
+            "{}:{}:{}: {:?} error: {}",
+            path.display(),
+            err.row + 1,
+            err.column + 1,
+            err.kind,
+            err.message
+        )
+    })
+}
+
+#[cfg(feature = "rust")]
+pub fn rust_configuration() -> LanguageConfiguration {
+    LanguageConfiguration::new(
+        "rust",
+        "//",
+        scip_treesitter_languages::rust(),
+        Some(scip_macros::include_scip_query!("rust", "scip-tags")),
+        Some(scip_macros::include_scip_query!("rust", "scip-locals")),
+        Some(scip_macros::include_scip_query!("rust", "scip-highlights")),
+        None,
+    )
+}
+
+#[cfg(feature = "go")]
+pub fn go_configuration() -> LanguageConfiguration {
+    LanguageConfiguration::new(
+        "go",
+        "//",
+        scip_treesitter_languages::go(),
+        Some(scip_macros::include_scip_query!("go", "scip-tags")),
+        Some(scip_macros::include_scip_query!("go", "scip-locals")),
+        Some(scip_macros::include_scip_query!("go", "scip-highlights")),
+        Some(scip_macros::include_scip_query!("go", "scip-injections")),
+    )
+}
+
+/// A grammar loaded at runtime, see [`crate::grammars`]. Everything is leaked, since
+/// the language has to stay valid for as long as the process may parse with it.
+#[derive(Clone, Copy)]
+pub struct DynamicLanguage {
+    pub name: &'static str,
+    pub line_comment: &'static str,
+    /// Detected like the extensions of the built-in languages, see [`crate::detect`]
+    pub extensions: &'static [&'static str],
+    pub language: Language,
+    pub tags: Option<&'static str>,
+    pub locals: Option<&'static str>,
+    pub highlights: Option<&'static str>,
+    pub injections: Option<&'static str>,
+    /// Digest of the shared library, so that rebuilding it invalidates cached documents
+    pub grammar_digest: &'static str,
+}
+
+static DYNAMIC_LANGUAGES: Mutex<Vec<DynamicLanguage>> = Mutex::new(vec![]);
+
+/// Adds a language to the registry, replacing any earlier one with the same name.
+/// Its queries must already compile, they are unwrapped like the embedded ones.
+pub fn register_language(dynamic: DynamicLanguage) {
+    let mut languages = DYNAMIC_LANGUAGES.lock().unwrap();
+    languages.retain(|language| language.name != dynamic.name);
+    languages.push(dynamic);
+}
+
+fn dynamic_language(name: &str) -> Option<DynamicLanguage> {
+    DYNAMIC_LANGUAGES
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|language| language.name == name)
+        .copied()
+}
+
+/// The registered language that claims files ending with `.<extension>`.
+pub fn dynamic_language_for_extension(extension: &str) -> Option<&'static str> {
+    DYNAMIC_LANGUAGES
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|language| language.extensions.contains(&extension))
+        .map(|language| language.name)
+}
+
+/// The languages compiled in, each behind the cargo feature of the same name.
+const BUILTIN_LANGUAGES: &[&str] = &[
+    #[cfg(feature = "go")]
+    "go",
+    #[cfg(feature = "rust")]
+    "rust",
+];
+
+/// The names of every language with a [`LanguageConfiguration`], including the
+/// ones registered at runtime.
+pub fn language_names() -> Vec<&'static str> {
+    let mut names = BUILTIN_LANGUAGES.to_vec();
+    for language in DYNAMIC_LANGUAGES.lock().unwrap().iter() {
+        if !names.contains(&language.name) {
+            names.push(language.name);
+        }
+    }
+
+    names
+}
+
+pub fn get_language_configuration(name: &str) -> Option<LanguageConfiguration> {
+    if let Some(dynamic) = dynamic_language(name) {
+        let mut config = LanguageConfiguration::new(
+            dynamic.name,
+            dynamic.line_comment,
+            dynamic.language,
+            dynamic.tags,
+            dynamic.locals,
+            dynamic.highlights,
+            dynamic.injections,
+        );
+        config.fingerprint = digest(&[
+            config.fingerprint.as_bytes(),
+            dynamic.grammar_digest.as_bytes(),
+        ]);
+
+        return Some(config);
+    }
+
+    match name {
+        #[cfg(feature = "go")]
+        "go" => Some(go_configuration()),
+        #[cfg(feature = "rust")]
+        "rust" => Some(rust_configuration()),
+        _ => None,
+    }
+}
+
+/// Same as [`get_language_configuration`], with the queries from `queries` when given.
+pub fn load_language_configuration(
+    name: &str,
+    queries: Option<&Path>,
+) -> Result<Option<LanguageConfiguration>> {
+    let Some(mut config) = get_language_configuration(name) else {
+        return Ok(None);
+    };
+
+    if let Some(queries) = queries {
+        config.override_queries(queries)?;
+    }
+
+    Ok(Some(config))
+}
+
+#[cfg(all(test, feature = "go"))]
+mod test {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn queries_directory(files: &[(&str, &str)]) -> TempDir {
+        let directory = tempfile::tempdir().unwrap();
+        for (path, contents) in files {
+            let path = directory.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        }
+
+        directory
+    }
+
+    #[test]
+    fn test_overrides_only_the_queries_present() -> Result<()> {
+        let directory = queries_directory(&[("go/scip-highlights.scm", "(comment) @comment")]);
+
+        let config = load_language_configuration("go", Some(directory.path()))?.unwrap();
+        assert_eq!(config.highlights.unwrap().pattern_count(), 1);
+        assert!(config.tags.unwrap().pattern_count() > 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reports_query_errors_with_position() {
+        let directory =
+            queries_directory(&[("go/scip-tags.scm", "(comment) @comment\n(not_a_node) @oops")]);
+
+        let err = load_language_configuration("go", Some(directory.path()))
+            .err()
+            .expect("the query does not compile")
+            .to_string();
+        assert!(
+            err.ends_with("go/scip-tags.scm:2:2: NodeType error: not_a_node"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_detects_registered_extensions() {
+        register_language(DynamicLanguage {
+            name: "registered-go",
+            line_comment: "//",
+            extensions: &["rgo"],
+            language: scip_treesitter_languages::go(),
+            tags: None,
+            locals: None,
+            highlights: Some("(comment) @comment"),
+            injections: None,
+            grammar_digest: "",
+        });
+
+        let detection = crate::detect::Detector::new()
+            .detect(Path::new("main.rgo"), "")
+            .unwrap();
+        assert_eq!(detection.language, "registered-go");
+        assert!(get_language_configuration(&detection.language).is_some());
+    }
+}
@@ -1,5 +1,25 @@
+pub mod cache;
+pub mod detect;
+pub mod digest;
+pub mod document;
+pub mod expectations;
+pub mod git;
+pub mod grammars;
+pub mod highlights;
+pub mod index;
+pub mod injections;
+pub mod inspect;
+pub mod json;
 pub mod languages;
+pub mod limits;
+pub mod lint;
 pub mod locals;
+pub mod lsif;
 pub mod matches;
+pub mod merge;
 pub mod snapshot;
+pub mod tags_file;
+pub mod timings;
 pub mod ts_scip;
+pub mod writer;
+pub mod zoekt;
@@ -0,0 +1,328 @@
+//! Safeguards against huge and generated files, which can dominate indexing time
+//! while adding little to navigation.
+//!
+//! Files that are skipped or only partly indexed are still emitted as documents, and
+//! the reason is returned next to them, for the caller to report with [`reasons_json`].
+
+use std::{fmt, str::FromStr, time::Duration};
+
+use anyhow::{bail, Result};
+use scip::types::Document;
+use serde_json::{json, Value};
+
+use crate::{
+    document::{parse_document_with, DocumentOptions, ParsedDocument},
+    languages::LanguageConfiguration,
+};
+
+/// Code of a document that produced no occurrences
+pub const SKIPPED: &str = "skipped";
+/// Code of a document that produced only part of its occurrences
+pub const LIMITED: &str = "limited";
+/// Code of a generated document of which only tags are indexed, as asked by
+/// [`GeneratedPolicy::TagsOnly`]
+pub const TAGS_ONLY: &str = "tags-only";
+
+/// Generated code markers are only looked for in the first lines of a file.
+const MARKER_LINES: usize = 10;
+
+#[derive(Debug, Clone)]
+pub struct Limits {
+    /// In bytes
+    pub max_file_size: Option<u64>,
+    pub parse_timeout: Option<Duration>,
+    /// See [`DocumentOptions::max_matches`]
+    pub max_matches: Option<usize>,
+    pub generated: GeneratedPolicy,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_file_size: Some(2 * 1024 * 1024),
+            parse_timeout: Some(Duration::from_secs(2)),
+            max_matches: Some(200_000),
+            generated: GeneratedPolicy::TagsOnly,
+        }
+    }
+}
+
+impl Limits {
+    /// No limits at all, every file is fully indexed.
+    pub fn none() -> Self {
+        Self {
+            max_file_size: None,
+            parse_timeout: None,
+            max_matches: None,
+            generated: GeneratedPolicy::Full,
+        }
+    }
+
+    /// Why a file of `size` bytes shouldn't be read at all, if it shouldn't.
+    pub fn check_size(&self, size: u64) -> Option<String> {
+        let max = self.max_file_size?;
+        (size > max).then(|| format!("file is {} bytes, over the limit of {} bytes", size, max))
+    }
+}
+
+/// The query matches the passes over a document may still go through, so that a file
+/// with a pathological number of matches stops being matched rather than being matched
+/// in full and thrown away.
+#[derive(Debug, Default)]
+pub struct MatchBudget {
+    remaining: Option<usize>,
+    exhausted: bool,
+}
+
+impl MatchBudget {
+    /// At most `max` matches, or as many as there are with `None`.
+    pub fn new(max: Option<usize>) -> Self {
+        Self {
+            remaining: max,
+            exhausted: false,
+        }
+    }
+
+    /// Ends `matches` once the budget is spent.
+    pub fn limit<'b, I: Iterator + 'b>(
+        &'b mut self,
+        matches: I,
+    ) -> impl Iterator<Item = I::Item> + 'b {
+        matches.take_while(|_| match &mut self.remaining {
+            Some(0) => {
+                self.exhausted = true;
+                false
+            }
+            Some(remaining) => {
+                *remaining -= 1;
+                true
+            }
+            None => true,
+        })
+    }
+
+    /// Whether a pass had matches left over when the budget ran out.
+    pub fn is_exhausted(&self) -> bool {
+        self.exhausted
+    }
+}
+
+/// What to do with files carrying a generated code marker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeneratedPolicy {
+    Skip,
+    TagsOnly,
+    Full,
+}
+
+impl FromStr for GeneratedPolicy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "skip" => Self::Skip,
+            "tags-only" => Self::TagsOnly,
+            "full" => Self::Full,
+            _ => bail!("unknown policy {:?}, expected skip, tags-only or full", s),
+        })
+    }
+}
+
+/// Finds a marker like Go's `// Code generated by mockgen. DO NOT EDIT.` or `@generated`
+/// in the first lines of `source`, and returns the line it's on.
+pub fn generated_marker(source: &str) -> Option<&str> {
+    source.lines().take(MARKER_LINES).find_map(|line| {
+        let is_marker = line.contains("DO NOT EDIT")
+            || line.contains("@generated")
+            || line.contains("Automatically generated by");
+
+        is_marker.then(|| line.trim())
+    })
+}
+
+/// Why a document was skipped or only partly indexed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reason {
+    /// [`SKIPPED`], [`LIMITED`] or [`TAGS_ONLY`]
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl Reason {
+    /// Whether the same contents and limits always give the same document. Only a policy
+    /// is: a parse timeout depends on how busy the machine was.
+    pub fn is_deterministic(&self) -> bool {
+        self.code == TAGS_ONLY
+    }
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.code, self.message)
+    }
+}
+
+/// `{"documents": [{"relativePath": ..., "code": ..., "message": ...}]}`, the report of the
+/// documents of an index that were skipped or only partly indexed, in index order.
+pub fn reasons_json(reasons: &[(String, Reason)]) -> Value {
+    json!({
+        "documents": reasons
+            .iter()
+            .map(|(path, reason)| {
+                json!({ "relativePath": path, "code": reason.code, "message": reason.message })
+            })
+            .collect::<Vec<_>>(),
+    })
+}
+
+/// An empty document, with the reason it was skipped.
+pub fn skipped_document(language: &str, message: String) -> ParsedDocument {
+    let mut document = Document::new();
+    document.language = language.to_string();
+
+    ParsedDocument {
+        document,
+        reason: Some(Reason {
+            code: SKIPPED,
+            message,
+        }),
+    }
+}
+
+/// The reason of a generated `source` of which only tags are indexed with `limits`, if it is.
+pub fn tags_only_reason(source: &str, limits: &Limits) -> Option<Reason> {
+    let marker =
+        generated_marker(source).filter(|_| limits.generated == GeneratedPolicy::TagsOnly)?;
+
+    Some(Reason {
+        code: TAGS_ONLY,
+        message: format!("generated file, only tags are indexed: {}", marker),
+    })
+}
+
+/// Parses `source` like [`crate::document::parse_document`], within `limits`.
+pub fn parse_document_within(
+    config: &mut LanguageConfiguration,
+    source: &str,
+    limits: &Limits,
+) -> Result<ParsedDocument> {
+    if let Some(reason) = limits.check_size(source.len() as u64) {
+        return Ok(skipped_document(config.name, reason));
+    }
+
+    let mut options = DocumentOptions {
+        tags_only: false,
+        parse_timeout: limits.parse_timeout,
+        max_matches: limits.max_matches,
+    };
+
+    if let Some(marker) = generated_marker(source) {
+        match limits.generated {
+            GeneratedPolicy::Skip => {
+                let reason = format!("generated file: {}", marker);
+                return Ok(skipped_document(config.name, reason));
+            }
+            GeneratedPolicy::TagsOnly => options.tags_only = true,
+            GeneratedPolicy::Full => {}
+        }
+    }
+
+    let mut parsed = parse_document_with(config, source, &options)?;
+    if parsed.reason.is_none() {
+        parsed.reason = tags_only_reason(source, limits);
+    }
+
+    Ok(parsed)
+}
+
+#[cfg(all(test, feature = "go"))]
+mod test {
+    use super::*;
+
+    const GENERATED: &str = "// Code generated by mockgen. DO NOT EDIT.\n\npackage mocks\n\nfunc New() {\n\tx := 1\n\t_ = x\n}\n";
+
+    #[test]
+    fn test_detects_generated_markers() {
+        assert_eq!(
+            generated_marker(GENERATED),
+            Some("// Code generated by mockgen. DO NOT EDIT.")
+        );
+        assert_eq!(
+            generated_marker("// @generated\nfn main() {}"),
+            Some("// @generated")
+        );
+        assert_eq!(generated_marker("package main\n\nfunc main() {}"), None);
+    }
+
+    #[test]
+    fn test_generated_policies() -> Result<()> {
+        let mut config = crate::languages::go_configuration();
+        let mut limits = Limits::none();
+
+        limits.generated = GeneratedPolicy::Skip;
+        let parsed = parse_document_within(&mut config, GENERATED, &limits)?;
+        assert!(parsed.document.occurrences.is_empty());
+        assert_eq!(parsed.reason.unwrap().code, SKIPPED);
+
+        limits.generated = GeneratedPolicy::TagsOnly;
+        let parsed = parse_document_within(&mut config, GENERATED, &limits)?;
+        assert_eq!(parsed.reason.unwrap().code, TAGS_ONLY);
+        let doc = parsed.document;
+        assert!(doc
+            .occurrences
+            .iter()
+            .all(|o| !o.symbol.starts_with("local")));
+        assert!(doc.occurrences.iter().any(|o| o.symbol.contains("New")));
+
+        limits.generated = GeneratedPolicy::Full;
+        let parsed = parse_document_within(&mut config, GENERATED, &limits)?;
+        assert_eq!(parsed.reason, None);
+        assert!(parsed
+            .document
+            .occurrences
+            .iter()
+            .any(|o| o.symbol.starts_with("local")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_match_budget_stops_iterating() {
+        let mut budget = MatchBudget::new(Some(3));
+        let mut pulled = 0;
+        let taken = budget.limit((0..10).inspect(|_| pulled += 1)).count();
+        assert_eq!((taken, pulled), (3, 4));
+        assert!(budget.is_exhausted());
+
+        let mut budget = MatchBudget::new(Some(3));
+        assert_eq!(budget.limit(0..3).count(), 3);
+        assert!(!budget.is_exhausted());
+    }
+
+    #[test]
+    fn test_size_and_match_limits() -> Result<()> {
+        let mut config = crate::languages::go_configuration();
+        let source = include_str!("../testdata/go/funcs.go");
+
+        let mut limits = Limits::none();
+        limits.max_file_size = Some(10);
+        let parsed = parse_document_within(&mut config, source, &limits)?;
+        assert!(parsed.document.occurrences.is_empty());
+        assert_eq!(parsed.reason.unwrap().code, SKIPPED);
+
+        let full = parse_document_within(&mut config, source, &Limits::none())?.document;
+        let mut limits = Limits::none();
+        limits.max_matches = Some(full.occurrences.len() - 1);
+        let parsed = parse_document_within(&mut config, source, &limits)?;
+        assert!(parsed.document.occurrences.len() < full.occurrences.len());
+        assert!(parsed.reason.is_some());
+
+        // The tags pass stops after two matches and keeps them
+        limits.max_matches = Some(2);
+        let parsed = parse_document_within(&mut config, source, &limits)?;
+        assert_eq!(parsed.reason.unwrap().code, LIMITED);
+        assert_eq!(parsed.document.occurrences.len(), 2);
+
+        Ok(())
+    }
+}
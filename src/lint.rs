@@ -0,0 +1,339 @@
+//! Checks query files for mistakes that would otherwise only show up at runtime,
+//! either as panics while indexing or as occurrences that silently go missing.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use ctags::TagKind;
+use tree_sitter::{CaptureQuantifier, Language, Query, QueryErrorKind};
+
+use crate::{
+    languages::get_language_configuration,
+    ts_scip::{capture_name_to_syntax_kind, descriptor_suffix},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryKind {
+    Tags,
+    Locals,
+    Highlights,
+    Injections,
+}
+
+impl QueryKind {
+    pub fn from_file_name(name: &str) -> Option<Self> {
+        match name {
+            "scip-tags.scm" => Some(Self::Tags),
+            "scip-locals.scm" => Some(Self::Locals),
+            "scip-highlights.scm" => Some(Self::Highlights),
+            "scip-injections.scm" => Some(Self::Injections),
+            _ => None,
+        }
+    }
+
+    fn understands(&self, capture: &str) -> bool {
+        // Captures starting with `_` are only there for predicates
+        if capture.starts_with('_') {
+            return true;
+        }
+
+        let structural = matches!(capture, "enclosing" | "scope");
+        match self {
+            Self::Tags => {
+                structural
+                    || descriptor_suffix(capture).is_some()
+                    || capture.parse::<TagKind>().is_ok()
+            }
+            Self::Locals => {
+                structural
+                    || capture.starts_with("definition.")
+                    || matches!(
+                        capture,
+                        "bindings"
+                            | "reference"
+                            | "reference.read"
+                            | "reference.write"
+                            | "reference.readwrite"
+                    )
+            }
+            Self::Highlights => capture_name_to_syntax_kind(capture).is_some(),
+            Self::Injections => matches!(capture, "injection.content" | "injection.language"),
+        }
+    }
+
+    fn check_property(&self, key: &str, value: Option<&str>) -> Option<String> {
+        let allowed: &[&str] = match (self, key) {
+            (Self::Locals, "scope") => &["global", "parent", "local"],
+            (Self::Locals, "access") => &["read", "write", "readwrite"],
+            (Self::Injections, "injection.language") => return None,
+            _ => return Some(format!("unknown #set! key \"{}\"", key)),
+        };
+
+        match value {
+            Some(value) if allowed.contains(&value) => None,
+            value => Some(format!(
+                "invalid #set! \"{}\" value {:?}, expected one of {}",
+                key,
+                value.unwrap_or_default(),
+                allowed.join(", ")
+            )),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct LintIssue {
+    pub path: PathBuf,
+    /// 1-based, like editors and compilers
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for LintIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: {}",
+            self.path.display(),
+            self.line,
+            self.column,
+            self.message
+        )
+    }
+}
+
+/// Lints every `<directory>/<lang>/*.scm` against the grammar of `<lang>`.
+pub fn lint_queries(directory: &Path) -> Result<Vec<LintIssue>> {
+    let mut issues = vec![];
+
+    for language_directory in sorted_entries(directory)? {
+        if !language_directory.is_dir() {
+            continue;
+        }
+
+        let name = language_directory
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default();
+        let Some(config) = get_language_configuration(name) else {
+            issues.push(LintIssue {
+                path: language_directory.clone(),
+                line: 1,
+                column: 1,
+                message: format!("no language named \"{}\", or it wasn't compiled in", name),
+            });
+            continue;
+        };
+
+        for path in sorted_entries(&language_directory)? {
+            let file_name = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or_default();
+            if !file_name.ends_with(".scm") {
+                continue;
+            }
+
+            match QueryKind::from_file_name(file_name) {
+                Some(kind) => issues.extend(lint_query(config.language, kind, &path)?),
+                None => issues.push(LintIssue {
+                    path,
+                    line: 1,
+                    column: 1,
+                    message: "not a query file scip-semantic loads".to_string(),
+                }),
+            }
+        }
+    }
+
+    Ok(issues)
+}
+
+fn sorted_entries(directory: &Path) -> Result<Vec<PathBuf>> {
+    let mut entries = fs::read_dir(directory)
+        .with_context(|| format!("reading {}", directory.display()))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    entries.sort();
+
+    Ok(entries)
+}
+
+pub fn lint_query(language: Language, kind: QueryKind, path: &Path) -> Result<Vec<LintIssue>> {
+    let source = fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+
+    let issue = |offset: usize, message: String| {
+        let before = &source[..offset];
+        let line = before.matches('\n').count() + 1;
+        let column = offset - before.rfind('\n').map_or(0, |idx| idx + 1) + 1;
+
+        LintIssue {
+            path: path.to_path_buf(),
+            line,
+            column,
+            message,
+        }
+    };
+
+    let query = match Query::new(language, &source) {
+        Ok(query) => query,
+        Err(err) => {
+            let message = match err.kind {
+                QueryErrorKind::Structure => format!("pattern can never match: {}", err.message),
+                kind => format!("{:?} error: {}", kind, err.message),
+            };
+            return Ok(vec![issue(err.offset, message)]);
+        }
+    };
+
+    let mut issues = vec![];
+    let capture_names = query.capture_names();
+    for pattern in 0..query.pattern_count() {
+        let offset = query.start_byte_for_pattern(pattern);
+
+        let captures = query
+            .capture_quantifiers(pattern)
+            .iter()
+            .enumerate()
+            .filter(|(_, quantifier)| **quantifier != CaptureQuantifier::Zero)
+            .map(|(idx, quantifier)| (capture_names[idx].as_str(), *quantifier))
+            .collect::<Vec<_>>();
+
+        for (capture, _) in &captures {
+            if !kind.understands(capture) {
+                issues.push(issue(offset, format!("unknown capture @{}", capture)));
+            }
+        }
+
+        // The pipelines handle exactly one of these per match
+        let singles: &[&str] = match kind {
+            QueryKind::Tags => &["scope", "definition"],
+            QueryKind::Locals => &["definition", "reference", "scope", "bindings"],
+            QueryKind::Highlights => &[],
+            QueryKind::Injections => &["injection.content", "injection.language"],
+        };
+        for group in singles {
+            let matching = captures
+                .iter()
+                .filter(|(capture, _)| capture.starts_with(group))
+                .collect::<Vec<_>>();
+            let repeated = matching.iter().any(|(_, quantifier)| {
+                matches!(
+                    quantifier,
+                    CaptureQuantifier::OneOrMore | CaptureQuantifier::ZeroOrMore
+                )
+            });
+
+            if matching.len() > 1 || repeated {
+                issues.push(issue(
+                    offset,
+                    format!("several {} captures in one pattern", group),
+                ));
+            }
+        }
+
+        let required = match kind {
+            QueryKind::Tags => captures
+                .iter()
+                .any(|(capture, _)| capture.starts_with("descriptor")),
+            QueryKind::Locals => captures.iter().any(|(capture, _)| {
+                ["definition", "reference", "scope"]
+                    .iter()
+                    .any(|group| capture.starts_with(group))
+            }),
+            QueryKind::Highlights => !captures.is_empty(),
+            QueryKind::Injections => captures
+                .iter()
+                .any(|(capture, _)| *capture == "injection.content"),
+        };
+        if !required {
+            issues.push(issue(
+                offset,
+                "pattern never produces an occurrence, it has no capture the pipeline uses"
+                    .to_string(),
+            ));
+        }
+
+        for property in query.property_settings(pattern) {
+            if let Some(message) = kind.check_property(&property.key, property.value.as_deref()) {
+                issues.push(issue(offset, message));
+            }
+        }
+    }
+
+    Ok(issues)
+}
+
+#[cfg(all(test, feature = "go"))]
+mod test {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "rust")]
+    fn test_repository_queries_are_clean() -> Result<()> {
+        let queries = Path::new(env!("CARGO_MANIFEST_DIR")).join("queries");
+        let issues = lint_queries(&queries)?
+            .iter()
+            .map(|issue| issue.to_string())
+            .collect::<Vec<_>>();
+
+        assert!(issues.is_empty(), "{}", issues.join("\n"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reports_broken_patterns() -> Result<()> {
+        let directory = tempfile::tempdir()?;
+        let path = directory.path().join("scip-locals.scm");
+        fs::write(
+            &path,
+            r#"(identifier) @definition.var @definition.term
+(parameter_declaration name: (identifier) @definition.term
+  (#set! "scope" "everywhere"))
+(identifier) @refrence
+(identifier) @enclosing
+((identifier) @definition.var (#set! "kind" "call"))
+(identifier) @reference.reed
+(block) @scopes
+"#,
+        )?;
+
+        let language = get_language_configuration("go").unwrap().language;
+        let issues = lint_query(language, QueryKind::Locals, &path)?
+            .into_iter()
+            .map(|issue| format!("{}:{} {}", issue.line, issue.column, issue.message))
+            .collect::<Vec<_>>();
+
+        insta::assert_snapshot!(issues.join("\n"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reports_impossible_patterns() -> Result<()> {
+        let directory = tempfile::tempdir()?;
+        let path = directory.path().join("scip-tags.scm");
+        fs::write(
+            &path,
+            "(type_identifier) @descriptor.type\n(identifier (identifier) @descriptor.type)\n",
+        )?;
+
+        let language = get_language_configuration("go").unwrap().language;
+        let issues = lint_query(language, QueryKind::Tags, &path)?;
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!((issues[0].line, issues[0].column), (2, 13));
+        assert!(
+            issues[0].message.starts_with("pattern can never match"),
+            "{}",
+            issues[0]
+        );
+
+        Ok(())
+    }
+}
@@ -1,14 +1,13 @@
-use anyhow::Result;
+use std::time::Instant;
+
+use anyhow::{bail, Result};
 use protobuf::Enum;
 use rustc_hash::FxHashMap as HashMap;
-use scip::{
-    symbol::format_symbol,
-    types::{Occurrence, Symbol},
-};
+use scip::types::{Occurrence, SymbolRole};
 use scip_treesitter::prelude::*;
-use tree_sitter::Node;
+use tree_sitter::{Node, Query};
 
-use crate::languages::LocalConfiguration;
+use crate::{languages::LocalConfiguration, limits::MatchBudget, timings::Timings};
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct ByteRange {
@@ -22,238 +21,269 @@ impl ByteRange {
     }
 }
 
+/// The scopes of a document, stored in one arena. Scopes are added outermost first, in
+/// order of their start byte, so the arena is in pre-order and every scope only points
+/// to its parent and children by index.
+#[derive(Debug)]
+pub struct ScopeTree<'a> {
+    pub scopes: Vec<Scope<'a>>,
+    pub references: Vec<Reference<'a>>,
+}
+
 #[derive(Debug)]
 pub struct Scope<'a> {
     pub scope: Node<'a>,
     pub range: ByteRange,
+    pub parent: Option<usize>,
+    /// Sorted by start byte. Siblings never overlap.
+    pub children: Vec<usize>,
     pub definitions: HashMap<&'a str, Definition<'a>>,
-    pub references: HashMap<&'a str, Vec<Reference<'a>>>,
-    pub children: Vec<Scope<'a>>,
-}
-
-impl<'a> Eq for Scope<'a> {}
-
-impl<'a> PartialEq for Scope<'a> {
-    fn eq(&self, other: &Self) -> bool {
-        self.scope.id() == other.scope.id()
-    }
-}
-
-impl<'a> PartialOrd for Scope<'a> {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.range.partial_cmp(&other.range)
-    }
-}
-
-impl<'a> Ord for Scope<'a> {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.range.cmp(&other.range)
-    }
+    /// Indexes into [`ScopeTree::references`]
+    pub references: HashMap<&'a str, Vec<usize>>,
 }
 
 impl<'a> Scope<'a> {
-    pub fn new(scope: Node<'a>) -> Self {
+    fn new(scope: Node<'a>, parent: Option<usize>) -> Self {
         Self {
             scope,
             range: ByteRange {
                 start: scope.start_byte(),
                 end: scope.end_byte(),
             },
+            parent,
+            children: vec![],
             definitions: HashMap::default(),
             references: HashMap::default(),
-            children: vec![],
         }
     }
+}
+
+impl<'a> ScopeTree<'a> {
+    const ROOT: usize = 0;
+
+    /// Nests `scopes` under `root`, in linear time once they are sorted.
+    pub fn new(root: Node<'a>, mut scopes: Vec<Node<'a>>) -> Self {
+        // Sort smallest to largest, so we can pop off the end of the list for the largest, first scope
+        scopes.sort_by_key(|node| {
+            (
+                std::cmp::Reverse(node.start_byte()),
+                node.end_byte() - node.start_byte(),
+            )
+        });
 
-    pub fn insert_scope(&mut self, scope: Scope<'a>) {
-        if let Some(child) = self
-            .children
-            .iter_mut()
-            .find(|child| child.range.contains(&scope.range))
-        {
-            child.insert_scope(scope);
-        } else {
-            self.children.push(scope);
+        let mut tree = Self {
+            scopes: vec![Scope::new(root, None)],
+            references: vec![],
+        };
+
+        // The innermost scope so far, and all of its ancestors
+        let mut stack = vec![Self::ROOT];
+        while let Some(node) = scopes.pop() {
+            let scope = Scope::new(node, None);
+            while stack.len() > 1 && !tree.scope(&stack).range.contains(&scope.range) {
+                stack.pop();
+            }
+
+            let parent = *stack.last().expect("the root is never popped");
+            let idx = tree.scopes.len();
+            tree.scopes.push(Scope {
+                parent: Some(parent),
+                ..scope
+            });
+            tree.scopes[parent].children.push(idx);
+            stack.push(idx);
         }
+
+        tree
     }
 
-    pub fn insert_definition(&mut self, definition: Definition<'a>) {
-        // TODO: Probably should assert that this the root node?
-        if definition.scope_modifier == ScopeModifier::Global {
-            self.definitions.insert(definition.identifier, definition);
-            return;
-        }
+    fn scope(&self, stack: &[usize]) -> &Scope<'a> {
+        &self.scopes[*stack.last().expect("the root is never popped")]
+    }
 
-        if let Some(child) = self
-            .children
-            .iter_mut()
-            .find(|child| child.range.contains(&definition.range))
-        {
-            child.insert_definition(definition)
-        } else {
-            self.definitions.insert(definition.identifier, definition);
-        }
+    /// The child of `parent` that starts the closest before `start`.
+    fn child_before(&self, parent: usize, start: usize) -> Option<usize> {
+        let children = &self.scopes[parent].children;
+        let idx = children.partition_point(|&child| self.scopes[child].range.start <= start);
+
+        idx.checked_sub(1).map(|idx| children[idx])
     }
 
-    pub fn insert_reference(&mut self, reference: Reference<'a>) {
-        if let Some(definition) = self.definitions.get(&reference.identifier) {
-            if definition.node.id() == reference.node.id() {
-                return;
-            }
-        }
+    pub fn insert_definition(&mut self, definition: Definition<'a>) {
+        let mut idx = Self::ROOT;
 
-        match self
-            .children
-            .binary_search_by_key(&reference.range.start, |r| r.range.start)
-        {
-            Ok(_) => {
-                // self.children[idx].insert_reference(reference);
-                todo!("I'm not sure what to do yet, think more now");
-            }
-            Err(idx) => match idx {
-                0 => self
-                    .references
-                    .entry(reference.identifier)
-                    .or_default()
-                    .push(reference),
-                idx => {
-                    if self.children[idx - 1].range.contains(&reference.range) {
-                        self.children[idx - 1].insert_reference(reference)
-                    } else {
-                        self.references
-                            .entry(reference.identifier)
-                            .or_default()
-                            .push(reference)
-                    }
-                }
+        // Placed in the innermost scope containing the definition, or its `scope`
+        let range = match definition.scope {
+            Some(scope) => ByteRange {
+                start: scope.start_byte(),
+                end: scope.end_byte(),
             },
+            None => ByteRange {
+                start: definition.range.start,
+                end: definition.range.end,
+            },
+        };
+
+        // TODO: `Parent` is handled like `Local`
+        if definition.scope_modifier != ScopeModifier::Global {
+            while let Some(child) = self.child_before(idx, range.start) {
+                if !self.scopes[child].range.contains(&range) {
+                    break;
+                }
+                idx = child;
+            }
         }
+
+        self.scopes[idx]
+            .definitions
+            .insert(definition.identifier, definition);
     }
 
     // This flattens our scope tree so that we don't have any scopes
     // remaining for when we do reference lookups that don't actually
     // contain any definitions. Those are pretty useless.
+    //
+    // Empty scopes stay in the arena, but are unreachable from the root.
     pub fn clean_empty_scopes(&mut self) {
-        self.children.iter_mut().for_each(|child| {
-            child.clean_empty_scopes();
-        });
+        let is_kept = |scope: &Scope| scope.parent.is_none() || !scope.definitions.is_empty();
 
-        let mut empty_children = vec![];
-        for (idx, child) in self.children.iter().enumerate() {
-            if child.definitions.is_empty() {
-                empty_children.push(idx);
-            }
+        for scope in &mut self.scopes {
+            scope.children.clear();
         }
 
-        let mut to_clean = vec![];
-        for idx in empty_children.into_iter().rev() {
-            to_clean.push(self.children.remove(idx));
-        }
+        // Parents come before their children in the arena, so they already moved
+        for idx in 1..self.scopes.len() {
+            let Some(mut parent) = self.scopes[idx].parent else {
+                continue;
+            };
+            while !is_kept(&self.scopes[parent]) {
+                parent = self.scopes[parent].parent.expect("the root is kept");
+            }
 
-        // Add the children to the parent scope
-        for child in to_clean {
-            self.children.extend(child.children);
+            self.scopes[idx].parent = Some(parent);
+            if is_kept(&self.scopes[idx]) {
+                self.scopes[parent].children.push(idx);
+            }
         }
-
-        self.children.sort_by_key(|s| s.range.start);
     }
 
-    pub fn into_occurrences(&mut self, hint: usize) -> Vec<Occurrence> {
-        let mut occs = Vec::with_capacity(hint);
-        self.rec_into_occurrences(&mut 0, &mut occs);
-        occs
-    }
-
-    fn rec_into_occurrences(&self, id: &mut usize, occurrences: &mut Vec<Occurrence>) {
-        // TODO: I'm a little sad about this.
-        //  We could probably make this a runtime option, where `self` has a `sorted` value
-        //  that decides whether we need to or not. But on a huge file, this made no difference.
-        let mut values = self.definitions.values().collect::<Vec<_>>();
-        values.sort_by_key(|d| d.range.start);
-
-        for definition in values {
-            *id += 1;
-
-            let symbol = format_symbol(Symbol::new_local(*id));
-            let symbol_roles = scip::types::SymbolRole::Definition.value();
-
-            occurrences.push(scip::types::Occurrence {
-                range: definition.node.to_scip_range(),
-                symbol: symbol.clone(),
-                symbol_roles,
-                // syntax_kind: todo!(),
-                ..Default::default()
-            });
-
-            if let Some(references) = self.references.get(definition.identifier) {
-                for reference in references {
-                    occurrences.push(scip::types::Occurrence {
-                        range: reference.node.to_scip_range(),
-                        symbol: symbol.clone(),
-                        ..Default::default()
-                    });
+    pub fn insert_reference(&mut self, reference: Reference<'a>) {
+        let mut idx = Self::ROOT;
+        loop {
+            let scope = &self.scopes[idx];
+            if let Some(definition) = scope.definitions.get(&reference.identifier) {
+                if definition.node.id() == reference.node.id() {
+                    return;
                 }
             }
 
-            self.children
-                .iter()
-                .for_each(|c| c.occurrences_for_children(definition, symbol.as_str(), occurrences));
+            match self.child_before(idx, reference.range.start) {
+                // A scope can start with a reference, like a Rust match arm starting with
+                // its pattern, so the reference belongs to the scope starting there.
+                Some(child)
+                    if self.scopes[child].range.start == reference.range.start
+                        || self.scopes[child].range.contains(&reference.range) =>
+                {
+                    idx = child
+                }
+                _ => break,
+            }
         }
 
-        self.children
-            .iter()
-            .for_each(|c| c.rec_into_occurrences(id, occurrences));
+        let identifier = reference.identifier;
+        self.scopes[idx]
+            .references
+            .entry(identifier)
+            .or_default()
+            .push(self.references.len());
+        self.references.push(reference);
     }
 
-    fn occurrences_for_children(
-        self: &Scope<'a>,
-        def: &Definition<'a>,
-        symbol: &str,
-        occurrences: &mut Vec<Occurrence>,
-    ) {
-        if self.definitions.contains_key(def.identifier) {
-            return;
+    /// Kept scopes in pre-order.
+    fn preorder(&self) -> Vec<usize> {
+        let mut order = Vec::with_capacity(self.scopes.len());
+        let mut stack = vec![Self::ROOT];
+        while let Some(idx) = stack.pop() {
+            order.push(idx);
+            stack.extend(self.scopes[idx].children.iter().rev());
         }
 
-        if let Some(references) = self.references.get(def.identifier) {
-            for reference in references {
-                occurrences.push(scip::types::Occurrence {
-                    range: reference.node.to_scip_range(),
-                    symbol: symbol.to_string(),
-                    ..Default::default()
-                });
+        order
+    }
+
+    /// The closest scope defining `identifier`, starting from `idx` and going up.
+    fn resolve(&self, mut idx: usize, identifier: &str) -> Option<usize> {
+        loop {
+            if self.scopes[idx].definitions.contains_key(identifier) {
+                return Some(idx);
             }
+            idx = self.scopes[idx].parent?;
         }
-
-        self.children
-            .iter()
-            .for_each(|c| c.occurrences_for_children(def, symbol, occurrences));
     }
 
-    #[allow(dead_code)]
-    fn find_scopes_with(
-        &'a self,
-        scopes: &mut Vec<&Scope<'a>>,
-        // predicate: impl Fn(&Scope<'a>) -> bool,
-    ) {
-        if self.definitions.is_empty() {
-            scopes.push(self);
+    pub fn into_occurrences(&mut self, hint: usize) -> Vec<Occurrence> {
+        let order = self.preorder();
+
+        // References by the scope and identifier of their definition, each scope adding
+        // its own before its children's.
+        let mut resolved = HashMap::<(usize, &str), Vec<usize>>::default();
+        for &idx in &order {
+            for (identifier, references) in &self.scopes[idx].references {
+                if let Some(scope) = self.resolve(idx, identifier) {
+                    resolved
+                        .entry((scope, identifier))
+                        .or_default()
+                        .extend(references);
+                }
+            }
         }
 
-        for child in &self.children {
-            child.find_scopes_with(scopes);
-        }
-    }
+        let mut occurrences = Vec::with_capacity(hint);
+        let mut id = 0;
+        for idx in order {
+            // TODO: I'm a little sad about this.
+            //  We could probably make this a runtime option, where `self` has a `sorted` value
+            //  that decides whether we need to or not. But on a huge file, this made no difference.
+            let mut values = self.scopes[idx].definitions.values().collect::<Vec<_>>();
+            values.sort_by_key(|d| d.range.start);
 
-    pub fn display_scopes(&self) {
-        self.rec_display_scopes(0);
-    }
+            for definition in values {
+                id += 1;
+
+                // `Occurrence.symbol` is a protobuf `String`, so the symbol can't be
+                // shared: every reference gets a copy and the definition the original.
+                let symbol = format!("local {}", id);
+                let symbol_roles = scip::types::SymbolRole::Definition.value();
 
-    fn rec_display_scopes(&self, depth: usize) {
-        let depth = depth + 1;
-        for child in self.children.iter() {
-            child.rec_display_scopes(depth);
+                let definition_idx = occurrences.len();
+                occurrences.push(scip::types::Occurrence {
+                    range: definition.node.to_scip_range(),
+                    symbol_roles,
+                    enclosing_range: definition
+                        .enclosing
+                        .map(|enclosing| enclosing.to_scip_range())
+                        .unwrap_or_default(),
+                    ..Default::default()
+                });
+
+                let references = resolved
+                    .get(&(idx, definition.identifier))
+                    .map(|references| references.as_slice())
+                    .unwrap_or_default();
+                for &reference in references {
+                    let reference = &self.references[reference];
+                    occurrences.push(scip::types::Occurrence {
+                        range: reference.node.to_scip_range(),
+                        symbol: symbol.clone(),
+                        symbol_roles: reference.symbol_roles,
+                        ..Default::default()
+                    });
+                }
+                occurrences[definition_idx].symbol = symbol;
+            }
         }
+
+        occurrences
     }
 }
 
@@ -272,6 +302,11 @@ pub struct Definition<'a> {
     pub node: Node<'a>,
     pub range: ByteRange,
     pub scope_modifier: ScopeModifier,
+    /// The declaration this definition is part of, used for `enclosing_range`
+    pub enclosing: Option<Node<'a>>,
+    /// The node of the scope the definition belongs to, when it isn't the innermost one
+    /// containing it, like the block of a Rust `if let`
+    pub scope: Option<Node<'a>>,
 }
 
 #[derive(Debug)]
@@ -280,6 +315,39 @@ pub struct Reference<'a> {
     pub identifier: &'a str,
     pub node: Node<'a>,
     pub range: ByteRange,
+    /// `ReadAccess` and `WriteAccess` bits, from the capture name or the `access` property
+    pub symbol_roles: i32,
+}
+
+/// Access roles of a reference, either from its capture name (`@reference.write`,
+/// `@reference.read`, `@reference.readwrite`) or from `(#set! "access" "write")`.
+fn access_roles(capture_name: &str, access_property: Option<&str>) -> Result<i32> {
+    let read = SymbolRole::ReadAccess.value();
+    let write = SymbolRole::WriteAccess.value();
+
+    let from_access = |access: &str| match access {
+        "read" => Some(read),
+        "write" => Some(write),
+        "readwrite" => Some(read | write),
+        _ => None,
+    };
+
+    let mut roles = 0;
+    if let Some(access) = capture_name.strip_prefix("reference.") {
+        roles |= from_access(access).unwrap_or_default();
+    }
+
+    if let Some(access) = access_property {
+        match from_access(access) {
+            Some(access) => roles |= access,
+            None => bail!(
+                "unknown access: {}, expected read, write or readwrite",
+                access
+            ),
+        }
+    }
+
+    Ok(roles)
 }
 
 pub fn parse_tree<'a>(
@@ -287,28 +355,79 @@ pub fn parse_tree<'a>(
     tree: &'a tree_sitter::Tree,
     source_bytes: &'a [u8],
 ) -> Result<Vec<scip::types::Occurrence>> {
+    get_locals(&config.query, tree, source_bytes)
+}
+
+/// Same as [`parse_tree`], but only needs the query, so the tree can come from
+/// a parser shared with the other passes.
+pub fn get_locals<'a>(
+    query: &Query,
+    tree: &'a tree_sitter::Tree,
+    source_bytes: &'a [u8],
+) -> Result<Vec<scip::types::Occurrence>> {
+    get_locals_timed(
+        query,
+        tree,
+        source_bytes,
+        &mut MatchBudget::default(),
+        &mut Timings::default(),
+    )
+}
+
+/// Same as [`get_locals`], stopping once `budget` is spent, and adding the time spent
+/// matching, building the scope tree and resolving references to `timings`.
+pub fn get_locals_timed<'a>(
+    query: &Query,
+    tree: &'a tree_sitter::Tree,
+    source_bytes: &'a [u8],
+    budget: &mut MatchBudget,
+    timings: &mut Timings,
+) -> Result<Vec<scip::types::Occurrence>> {
+    let matching = Instant::now();
     let mut cursor = tree_sitter::QueryCursor::new();
 
     let root_node = tree.root_node();
-    let capture_names = config.query.capture_names();
+    let capture_names = query.capture_names();
 
     let mut scopes = vec![];
     let mut definitions = vec![];
-    let mut references = vec![];
+    let mut references: Vec<Reference> = vec![];
+    let mut seen_references = HashMap::<usize, usize>::default();
+    // Nodes whose definitions belong to a scope other than the innermost one
+    let mut redirects = vec![];
 
-    for m in cursor.matches(&config.query, root_node, source_bytes) {
+    for m in budget.limit(cursor.matches(query, root_node, source_bytes)) {
         let mut node = None;
 
         let mut scope = None;
+        let mut bindings = None;
         let mut definition = None;
         let mut reference = None;
         let mut scope_modifier = None;
+        let mut enclosing = None;
+        let mut access = None;
 
         for capture in m.captures {
             let capture_name = capture_names
                 .get(capture.index as usize)
                 .expect("capture indexes should always work");
 
+            if capture_name == "enclosing" {
+                enclosing = Some(capture.node);
+                continue;
+            }
+
+            if capture_name == "bindings" {
+                bindings = Some(capture.node);
+                continue;
+            }
+
+            if capture_name.starts_with("scope") {
+                assert!(scope.is_none(), "declare only one scope per match");
+                scope = Some(capture.node);
+                continue;
+            }
+
             node = Some(capture.node);
 
             if capture_name.starts_with("definition") {
@@ -316,7 +435,7 @@ pub fn parse_tree<'a>(
                 definition = Some(capture_name);
 
                 // Handle scope modifiers
-                let properties = config.query.property_settings(m.pattern_index);
+                let properties = query.property_settings(m.pattern_index);
                 for prop in properties {
                     if &(*prop.key) == "scope" {
                         match prop.value.as_deref() {
@@ -334,17 +453,30 @@ pub fn parse_tree<'a>(
             if capture_name.starts_with("reference") {
                 assert!(reference.is_none(), "only one reference per match");
                 reference = Some(capture_name);
-            }
 
-            if capture_name.starts_with("scope") {
-                assert!(scope.is_none(), "declare only one scope per match");
-                scope = Some(capture);
+                access = query
+                    .property_settings(m.pattern_index)
+                    .iter()
+                    .find(|prop| &(*prop.key) == "access")
+                    .and_then(|prop| prop.value.as_deref());
             }
         }
 
-        let node = node.expect("there must always be at least one descriptor");
+        let Some(node) = node else {
+            let scope =
+                scope.expect("if there is no definition or reference, there must be a scope");
+            match bindings {
+                Some(bindings) => redirects.push((bindings, scope)),
+                None => scopes.push(scope),
+            }
+            continue;
+        };
 
         if let Some(group) = definition {
+            if let Some(scope) = scope {
+                redirects.push((node, scope));
+            }
+
             let identifier = node.utf8_text(source_bytes).expect("utf8_text");
             let scope_modifier = scope_modifier.unwrap_or_default();
             definitions.push(Definition {
@@ -356,9 +488,21 @@ pub fn parse_tree<'a>(
                 identifier,
                 node,
                 scope_modifier,
+                enclosing: enclosing.or_else(|| node.parent()),
+                scope: None,
             });
         } else if let Some(group) = reference {
+            let symbol_roles = access_roles(group, access)?;
+
+            // The catch-all `@reference` patterns match the same identifiers as the ones
+            // flagging accesses, so only the roles are kept from the duplicate.
+            if let Some(&idx) = seen_references.get(&node.id()) {
+                references[idx].symbol_roles |= symbol_roles;
+                continue;
+            }
+
             let identifier = node.utf8_text(source_bytes).expect("utf8_text");
+            seen_references.insert(node.id(), references.len());
             references.push(Reference {
                 range: ByteRange {
                     start: node.start_byte(),
@@ -367,47 +511,52 @@ pub fn parse_tree<'a>(
                 group,
                 identifier,
                 node,
+                symbol_roles,
             });
-        } else {
-            let scope =
-                scope.expect("if there is no definition or reference, there must be a scope");
-            scopes.push(Scope::new(scope.node));
         }
     }
 
-    let mut root = Scope::new(root_node);
-
-    // Sort smallest to largest, so we can pop off the end of the list for the largest, first scope
-    scopes.sort_by_key(|m| {
-        (
-            std::cmp::Reverse(m.range.start),
-            m.range.end - m.range.start,
-        )
-    });
+    timings.matching += matching.elapsed();
+    let building = Instant::now();
 
     let capacity = definitions.len() + references.len();
 
-    // Add all the scopes to our tree
-    while let Some(m) = scopes.pop() {
-        root.insert_scope(m);
+    // Redirected nodes are patterns and identifiers, which don't contain each other
+    // unless one is inside the other, so the last one starting before a definition is
+    // the innermost that can contain it
+    redirects.sort_by_key(|(node, _)| node.start_byte());
+    for definition in &mut definitions {
+        let idx =
+            redirects.partition_point(|(node, _)| node.start_byte() <= definition.range.start);
+        definition.scope = idx
+            .checked_sub(1)
+            .map(|idx| redirects[idx])
+            .filter(|(node, _)| node.end_byte() >= definition.range.end)
+            .map(|(_, scope)| scope);
     }
 
+    let mut tree = ScopeTree::new(root_node, scopes);
     while let Some(m) = definitions.pop() {
-        root.insert_definition(m);
+        tree.insert_definition(m);
     }
 
-    root.clean_empty_scopes();
+    tree.clean_empty_scopes();
+
+    timings.scopes += building.elapsed();
+    let resolving = Instant::now();
 
     while let Some(m) = references.pop() {
-        root.insert_reference(m);
+        tree.insert_reference(m);
     }
 
-    let occs = root.into_occurrences(capacity);
+    let occs = tree.into_occurrences(capacity);
+
+    timings.resolve += resolving.elapsed();
 
     Ok(occs)
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "go"))]
 mod test {
     use anyhow::Result;
     use scip::types::Document;
@@ -438,7 +587,7 @@ mod test {
     #[test]
     fn test_can_do_go() -> Result<()> {
         let mut config = crate::languages::go_locals();
-        let source_code = include_str!("../testdata/locals.go");
+        let source_code = include_str!("../testdata/go/locals.go");
         let doc = parse_file_for_lang(&mut config, source_code)?;
 
         let dumped = dump_document(&doc, source_code);
@@ -450,7 +599,7 @@ mod test {
     #[test]
     fn test_can_do_nested_locals() -> Result<()> {
         let mut config = crate::languages::go_locals();
-        let source_code = include_str!("../testdata/locals-nested.go");
+        let source_code = include_str!("../testdata/go/locals-nested.go");
         let doc = parse_file_for_lang(&mut config, source_code)?;
 
         let dumped = dump_document(&doc, source_code);
@@ -462,7 +611,7 @@ mod test {
     #[test]
     fn test_can_do_functions() -> Result<()> {
         let mut config = crate::languages::go_locals();
-        let source_code = include_str!("../testdata/funcs.go");
+        let source_code = include_str!("../testdata/go/funcs.go");
         let doc = parse_file_for_lang(&mut config, source_code)?;
 
         let dumped = dump_document(&doc, source_code);
@@ -470,4 +619,45 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn test_scope_tree_skips_empty_scopes() -> Result<()> {
+        let mut config = crate::languages::go_locals();
+        let source_code =
+            "package main\n\nfunc main() {\n\t{\n\t\t{\n\t\t\tx := 2\n\t\t}\n\t}\n}\n";
+        let tree = config.parser.parse(source_code, None).unwrap();
+        let root = tree.root_node();
+
+        let mut blocks = vec![];
+        let mut stack = vec![root];
+        while let Some(node) = stack.pop() {
+            if node.kind() == "block" {
+                blocks.push(node);
+            }
+            let mut cursor = node.walk();
+            stack.extend(node.named_children(&mut cursor));
+        }
+
+        let mut scopes = ScopeTree::new(root, blocks);
+        let parents = scopes.scopes.iter().map(|s| s.parent).collect::<Vec<_>>();
+        assert_eq!(parents, vec![None, Some(0), Some(1), Some(2)]);
+
+        let x = root.descendant_for_byte_range(38, 39).unwrap();
+        assert_eq!(x.utf8_text(source_code.as_bytes())?, "x");
+        scopes.insert_definition(Definition {
+            group: "definition.var",
+            identifier: "x",
+            node: x,
+            range: ByteRange { start: 38, end: 39 },
+            scope_modifier: ScopeModifier::Local,
+            enclosing: None,
+            scope: None,
+        });
+        scopes.clean_empty_scopes();
+
+        assert_eq!(scopes.scopes[3].parent, Some(0));
+        assert_eq!(scopes.preorder(), vec![0, 3]);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,446 @@
+//! Exports an index as LSIF, as JSON lines, for tooling that predates SCIP.
+//!
+//! Every symbol gets a `resultSet` that its ranges point to with `next`, and the
+//! `definitionResult` and `referenceResult` of the symbol hang off the result set.
+//! Local symbols get one result set per document, global ones a single result set
+//! for the whole index, with a moniker in the scheme of their symbol (`scip-ctags`).
+//!
+//! Columns are UTF-8 bytes in the index and UTF-16 code units in LSIF, so converting
+//! them needs the text of every document, which is read under the source root unless
+//! the index holds it.
+
+use std::{fs, io::Write, path::Path};
+
+use anyhow::{Context, Result};
+use protobuf::Enum;
+use rustc_hash::FxHashMap as HashMap;
+use scip::{
+    symbol::is_local_symbol,
+    types::{Document, Index, SymbolRole},
+};
+use serde_json::{json, Value};
+
+pub const LSIF_VERSION: &str = "0.4.3";
+
+struct Emitter<W: Write> {
+    writer: W,
+    id: u64,
+}
+
+impl<W: Write> Emitter<W> {
+    fn emit(&mut self, kind: &str, label: &str, mut element: Value) -> Result<u64> {
+        self.id += 1;
+        element["id"] = json!(self.id);
+        element["type"] = json!(kind);
+        element["label"] = json!(label);
+
+        serde_json::to_writer(&mut self.writer, &element)?;
+        self.writer.write_all(b"\n")?;
+
+        Ok(self.id)
+    }
+
+    fn vertex(&mut self, label: &str, element: Value) -> Result<u64> {
+        self.emit("vertex", label, element)
+    }
+
+    fn edge(&mut self, label: &str, out: u64, element: Value) -> Result<u64> {
+        let mut element = element;
+        element["outV"] = json!(out);
+        self.emit("edge", label, element)
+    }
+}
+
+/// The ranges of one symbol: its document, range and whether it's a definition.
+#[derive(Default)]
+struct SymbolRanges {
+    moniker: Option<(String, String)>,
+    ranges: Vec<(u64, u64, bool)>,
+}
+
+/// Writes `index` as LSIF, and returns how many vertices and edges were written.
+/// The sources of its documents are read under `source_root`.
+pub fn write_lsif(index: &Index, source_root: &Path, writer: impl Write) -> Result<u64> {
+    let mut emitter = Emitter { writer, id: 0 };
+
+    let project_root = index.metadata.project_root.trim_end_matches('/');
+    emitter.vertex(
+        "metaData",
+        json!({
+            "version": LSIF_VERSION,
+            "projectRoot": project_root,
+            "positionEncoding": "utf-16",
+            "toolInfo": {
+                "name": index.metadata.tool_info.name,
+                "version": index.metadata.tool_info.version,
+            },
+        }),
+    )?;
+
+    // Local symbols are only unique within their document
+    let mut symbols: Vec<SymbolRanges> = vec![];
+    let mut symbol_ids: HashMap<(Option<usize>, &str), usize> = HashMap::default();
+
+    for (idx, doc) in index.documents.iter().enumerate() {
+        let document = emitter.vertex(
+            "document",
+            json!({
+                "uri": format!("{}/{}", project_root, doc.relative_path),
+                "languageId": doc.language,
+            }),
+        )?;
+
+        let text = document_text(doc, source_root)?;
+        let columns = Utf16Columns::new(&text);
+
+        let mut ranges = vec![];
+        // Where each range already is, by symbol and position
+        let mut seen: HashMap<_, (usize, usize)> = HashMap::default();
+        for occurrence in &doc.occurrences {
+            // Highlights and diagnostics have no symbol to navigate to
+            if occurrence.symbol.is_empty() {
+                continue;
+            }
+
+            let local = is_local_symbol(&occurrence.symbol);
+            let key = (local.then_some(idx), occurrence.symbol.as_str());
+            let is_definition = occurrence.symbol_roles & SymbolRole::Definition.value() != 0;
+            if let Some(&(symbol, position)) = seen.get(&(key, occurrence.range.as_slice())) {
+                // The same range can be found by several passes, keep it once
+                symbols[symbol].ranges[position].2 |= is_definition;
+                continue;
+            }
+
+            let range = emitter.vertex("range", lsif_range(&occurrence.range, &columns))?;
+            ranges.push(range);
+
+            let symbol = *symbol_ids.entry(key).or_insert_with(|| {
+                symbols.push(SymbolRanges::default());
+                symbols.len() - 1
+            });
+            if !local && symbols[symbol].moniker.is_none() {
+                symbols[symbol].moniker = occurrence
+                    .symbol
+                    .split_once(' ')
+                    .map(|(scheme, identifier)| (scheme.to_string(), identifier.to_string()));
+            }
+
+            seen.insert(
+                (key, occurrence.range.as_slice()),
+                (symbol, symbols[symbol].ranges.len()),
+            );
+            symbols[symbol]
+                .ranges
+                .push((document, range, is_definition));
+        }
+
+        if !ranges.is_empty() {
+            emitter.edge("contains", document, json!({ "inVs": ranges }))?;
+        }
+    }
+
+    for symbol in &symbols {
+        let result_set = emitter.vertex("resultSet", json!({}))?;
+        for (_, range, _) in &symbol.ranges {
+            emitter.edge("next", *range, json!({ "inV": result_set }))?;
+        }
+
+        let definitions = by_document(symbol.ranges.iter().filter(|range| range.2));
+        if !definitions.is_empty() {
+            let result = emitter.vertex("definitionResult", json!({}))?;
+            emitter.edge(
+                "textDocument/definition",
+                result_set,
+                json!({ "inV": result }),
+            )?;
+            for (document, ranges) in &definitions {
+                emitter.edge(
+                    "item",
+                    result,
+                    json!({ "inVs": ranges, "document": document }),
+                )?;
+            }
+        }
+
+        let result = emitter.vertex("referenceResult", json!({}))?;
+        emitter.edge(
+            "textDocument/references",
+            result_set,
+            json!({ "inV": result }),
+        )?;
+        for (document, ranges) in &definitions {
+            emitter.edge(
+                "item",
+                result,
+                json!({ "inVs": ranges, "document": document, "property": "definitions" }),
+            )?;
+        }
+        for (document, ranges) in by_document(symbol.ranges.iter().filter(|range| !range.2)) {
+            emitter.edge(
+                "item",
+                result,
+                json!({ "inVs": ranges, "document": document, "property": "references" }),
+            )?;
+        }
+
+        if let Some((scheme, identifier)) = &symbol.moniker {
+            let moniker = emitter.vertex(
+                "moniker",
+                json!({
+                    "scheme": scheme,
+                    "identifier": identifier,
+                    "kind": "export",
+                    "unique": "project",
+                }),
+            )?;
+            emitter.edge("moniker", result_set, json!({ "inV": moniker }))?;
+        }
+    }
+
+    emitter.writer.flush()?;
+
+    Ok(emitter.id)
+}
+
+/// The text of `doc`, from the index when it holds it.
+fn document_text(doc: &Document, source_root: &Path) -> Result<String> {
+    if !doc.text.is_empty() {
+        return Ok(doc.text.clone());
+    }
+
+    let path = source_root.join(&doc.relative_path);
+    fs::read_to_string(&path).with_context(|| {
+        format!(
+            "reading {} to convert its positions to UTF-16",
+            path.display()
+        )
+    })
+}
+
+/// Converts the byte columns of a document to UTF-16 code units.
+struct Utf16Columns<'a> {
+    lines: Vec<&'a str>,
+}
+
+impl<'a> Utf16Columns<'a> {
+    fn new(text: &'a str) -> Self {
+        Self {
+            lines: text.split('\n').collect(),
+        }
+    }
+
+    fn convert(&self, line: i32, column: i32) -> i32 {
+        let Some(text) = self.lines.get(line as usize) else {
+            return column;
+        };
+
+        let mut end = (column.max(0) as usize).min(text.len());
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+
+        // Past the end of the line, the remaining columns are kept as they are
+        text[..end].encode_utf16().count() as i32 + column - end as i32
+    }
+}
+
+/// A SCIP range, `[line, start, end]` or `[start line, start, end line, end]`.
+fn lsif_range(range: &[i32], columns: &Utf16Columns) -> Value {
+    let (start_line, start, end_line, end) = match *range {
+        [line, start, end] => (line, start, line, end),
+        [start_line, start, end_line, end] => (start_line, start, end_line, end),
+        _ => (0, 0, 0, 0),
+    };
+
+    json!({
+        "start": { "line": start_line, "character": columns.convert(start_line, start) },
+        "end": { "line": end_line, "character": columns.convert(end_line, end) },
+    })
+}
+
+/// Range ids grouped by their document, in the order the documents were written.
+fn by_document<'r>(ranges: impl Iterator<Item = &'r (u64, u64, bool)>) -> Vec<(u64, Vec<u64>)> {
+    let mut grouped: Vec<(u64, Vec<u64>)> = vec![];
+    for (document, range, _) in ranges {
+        match grouped.last_mut() {
+            Some((last, ranges)) if last == document => ranges.push(*range),
+            _ => grouped.push((*document, vec![*range])),
+        }
+    }
+
+    grouped
+}
+
+#[cfg(all(test, feature = "go"))]
+mod test {
+    use std::{
+        collections::{BTreeMap, BTreeSet},
+        path::Path,
+    };
+
+    use super::*;
+    use crate::{
+        document::parse_document,
+        index::{index_directory, IndexOptions},
+    };
+
+    type Navigation = BTreeMap<String, BTreeSet<String>>;
+
+    fn position(uri: &str, range: &Value) -> String {
+        format!(
+            "{}:{}:{}-{}:{}",
+            uri,
+            range["start"]["line"],
+            range["start"]["character"],
+            range["end"]["line"],
+            range["end"]["character"]
+        )
+    }
+
+    /// A single-line SCIP range as an LSIF position, counting characters in UTF-16.
+    fn utf16_position(uri: &str, text: &str, range: &[i32]) -> String {
+        let line = text.lines().nth(range[0] as usize).unwrap();
+        let utf16 = |column: i32| -> usize {
+            line.char_indices()
+                .take_while(|(idx, _)| *idx < column as usize)
+                .map(|(_, char)| char.len_utf16())
+                .sum()
+        };
+
+        format!(
+            "{}:{}:{}-{}:{}",
+            uri,
+            range[0],
+            utf16(range[1]),
+            range[0],
+            utf16(range[2])
+        )
+    }
+
+    /// Where each range goes to with "go to definition", according to the SCIP index.
+    fn scip_definitions(index: &Index, root: &Path) -> Result<Navigation> {
+        let project_root = index.metadata.project_root.trim_end_matches('/');
+        let mut occurrences = vec![];
+        for (idx, doc) in index.documents.iter().enumerate() {
+            let uri = format!("{}/{}", project_root, doc.relative_path);
+            let text = document_text(doc, root)?;
+            for occurrence in doc.occurrences.iter().filter(|o| !o.symbol.is_empty()) {
+                let local = is_local_symbol(&occurrence.symbol).then_some(idx);
+                let is_definition = occurrence.symbol_roles & SymbolRole::Definition.value() != 0;
+                occurrences.push((
+                    (local, occurrence.symbol.clone()),
+                    utf16_position(&uri, &text, &occurrence.range),
+                    is_definition,
+                ));
+            }
+        }
+
+        let mut navigation = Navigation::new();
+        for (key, range, _) in &occurrences {
+            let definitions = occurrences
+                .iter()
+                .filter(|(other, _, is_definition)| other == key && *is_definition)
+                .map(|(_, range, _)| range.clone());
+            navigation
+                .entry(range.clone())
+                .or_default()
+                .extend(definitions);
+        }
+
+        Ok(navigation)
+    }
+
+    /// The same, following the edges of the LSIF dump.
+    fn lsif_definitions(dump: &str) -> Result<Navigation> {
+        let elements = dump
+            .lines()
+            .map(serde_json::from_str::<Value>)
+            .collect::<Result<Vec<_>, _>>()?;
+        let by_id = elements
+            .iter()
+            .map(|element| (element["id"].as_u64().unwrap(), element))
+            .collect::<HashMap<_, _>>();
+        let edges = |label: &str| {
+            elements
+                .iter()
+                .filter(|element| element["type"] == "edge" && element["label"] == label)
+                .collect::<Vec<_>>()
+        };
+
+        let mut uris = HashMap::default();
+        for edge in edges("contains") {
+            let uri = by_id[&edge["outV"].as_u64().unwrap()]["uri"]
+                .as_str()
+                .unwrap();
+            for range in edge["inVs"].as_array().unwrap() {
+                uris.insert(range.as_u64().unwrap(), uri);
+            }
+        }
+        let range_position = |id: u64| position(uris[&id], by_id[&id]);
+
+        let mut results = HashMap::default();
+        for edge in edges("textDocument/definition") {
+            results.insert(edge["outV"].as_u64(), edge["inV"].as_u64());
+        }
+        let mut items: HashMap<_, Vec<_>> = HashMap::default();
+        for edge in edges("item") {
+            let ranges = edge["inVs"].as_array().unwrap();
+            items
+                .entry(edge["outV"].as_u64())
+                .or_default()
+                .extend(ranges.iter().map(|range| range.as_u64().unwrap()));
+        }
+
+        let mut navigation = Navigation::new();
+        for edge in edges("next") {
+            let range = edge["outV"].as_u64().unwrap();
+            let definitions = results
+                .get(&edge["inV"].as_u64())
+                .and_then(|result| items.get(result))
+                .into_iter()
+                .flatten()
+                .map(|definition| range_position(*definition));
+            navigation
+                .entry(range_position(range))
+                .or_default()
+                .extend(definitions);
+        }
+
+        Ok(navigation)
+    }
+
+    #[test]
+    fn test_round_trips_navigation() -> Result<()> {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/go");
+        let mut index = index_directory(&root, &IndexOptions::default())?;
+
+        // Columns after non-ASCII text differ between UTF-8 and UTF-16
+        let text = "package main\n\nfunc main() {\n\ts := \"日本😀\"; t := s\n\t_ = t\n}\n";
+        let mut doc = parse_document(&mut crate::languages::go_configuration(), text)?;
+        doc.relative_path = "unicode.go".to_string();
+        doc.text = text.to_string();
+        index.documents.push(doc);
+
+        let mut dump = vec![];
+        write_lsif(&index, &root, &mut dump)?;
+        let dump = String::from_utf8(dump)?;
+
+        let expected = scip_definitions(&index, &root)?;
+        assert!(expected.values().any(|definitions| definitions.len() == 1));
+        assert_eq!(lsif_definitions(&dump)?, expected);
+
+        // `t` is at byte 20 and UTF-16 code unit 14
+        let t = expected
+            .keys()
+            .find(|position| position.ends_with("unicode.go:3:14-3:15"));
+        assert!(t.is_some(), "{:#?}", expected.keys());
+
+        let monikers = dump
+            .lines()
+            .filter(|line| line.contains(r#""label":"moniker""#) && line.contains("vertex"))
+            .count();
+        assert!(monikers > 0);
+
+        Ok(())
+    }
+}
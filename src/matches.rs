@@ -1,25 +1,22 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use ctags::{TagEntry, TagKind};
 use protobuf::Enum;
-use scip::types::Descriptor;
+use rustc_hash::FxHashMap as HashMap;
+use scip::types::{descriptor::Suffix, Descriptor};
 use scip_treesitter::prelude::*;
-use tree_sitter::Node;
+use tree_sitter::{Node, Query};
 
-use crate::languages::TagConfiguration;
-
-#[derive(Debug)]
-pub struct Root<'a> {
-    pub root: Node<'a>,
-    pub children: Vec<Matched<'a>>,
-}
-
-// #[derive(Debug)]
-// pub struct Namespace {}
+use crate::{
+    languages::{LanguageConfiguration, TagConfiguration},
+    limits::MatchBudget,
+};
 
 pub struct Scope<'a> {
     pub definer: Node<'a>,
     pub scope: Node<'a>,
     pub descriptors: Vec<Descriptor>,
-    pub children: Vec<Matched<'a>>,
+    /// From the `@definition.<kind>` capture, see [`tag_kind`]
+    pub kind: Option<TagKind>,
 }
 
 impl<'a> std::fmt::Debug for Scope<'a> {
@@ -28,18 +25,21 @@ impl<'a> std::fmt::Debug for Scope<'a> {
 
         write!(
             f,
-            "({}, {}, {:?}) -> {:?}",
+            "({}, {}, {:?})",
             self.scope.kind(),
             self.scope.start_position(),
             descriptors,
-            self.children
         )
     }
 }
 
 pub struct Global<'a> {
     pub node: Node<'a>,
+    /// The whole declaration this definition belongs to, used for `enclosing_range`
+    pub enclosing: Option<Node<'a>>,
     pub descriptors: Vec<Descriptor>,
+    /// From the `@definition.<kind>` capture, see [`tag_kind`]
+    pub kind: Option<TagKind>,
 }
 
 impl<'a> std::fmt::Debug for Global<'a> {
@@ -57,11 +57,7 @@ impl<'a> std::fmt::Debug for Global<'a> {
 }
 
 #[derive(Debug)]
-// TODO: Root as it's own type?
 pub enum Matched<'a> {
-    /// The root node of a file
-    Root(Root<'a>),
-
     /// Does not generate a definition, simply a place ot add new descriptors
     /// TODO: Haven't done this one for real yet
     // Namespace(Namespace),
@@ -82,126 +78,315 @@ impl<'a> ContainsNode for Matched<'a> {
 impl<'a> Matched<'a> {
     pub fn node(&self) -> &Node<'a> {
         match self {
-            Matched::Root(m) => &m.root,
             Matched::Scope(m) => &m.scope,
             Matched::Global(m) => &m.node,
         }
     }
 
-    // pub fn children(&self) -> &Vec<Matched<'a>> {
-    //     match self {
-    //         Matched::Root(m) => &m.children,
-    //         Matched::Scope(s) => &s.children,
-    //         Matched::Global(_) => todo!(),
-    //     }
-    // }
+    /// The node declaring the definition, like the `function_declaration` of a function.
+    pub fn declaration(&self) -> Option<Node<'a>> {
+        match self {
+            Matched::Scope(m) => Some(m.scope),
+            Matched::Global(m) => m.enclosing,
+        }
+    }
 
-    pub fn insert(&mut self, m: Matched<'a>) {
+    pub fn descriptors(&self) -> &[Descriptor] {
         match self {
-            Matched::Root(root) => {
-                if let Some(child) = root
-                    .children
-                    .iter_mut()
-                    .find(|child| child.contains_node(m.node()))
-                {
-                    child.insert(m);
-                } else {
-                    root.children.push(m);
-                }
-            }
-            Matched::Scope(scope) => {
-                if let Some(child) = scope
-                    .children
-                    .iter_mut()
-                    .find(|child| child.contains_node(m.node()))
-                {
-                    child.insert(m);
-                } else {
-                    scope.children.push(m);
-                }
-            }
-            Matched::Global(_) => unreachable!(),
+            Matched::Scope(m) => &m.descriptors,
+            Matched::Global(m) => &m.descriptors,
         }
     }
 
-    pub fn into_occurences(&self) -> Vec<scip::types::Occurrence> {
-        self.rec_into_occurrences(&[])
+    /// The kind the query gave this match, if any.
+    pub fn kind(&self) -> Option<TagKind> {
+        match self {
+            Matched::Scope(m) => m.kind,
+            Matched::Global(m) => m.kind,
+        }
     }
 
-    // TODO: Could we use a dequeue for this to pop on and off quickly?
-    // TODO: Could we use a way to format the symbol w/out all the preamble?
-    //  Perhaps just a "format_descriptors" function in the lib, that I didn't expose beforehand
-    fn rec_into_occurrences(&self, descriptors: &[Descriptor]) -> Vec<scip::types::Occurrence> {
+    /// Moves the descriptors out, leaving the match with none.
+    fn take_descriptors(&mut self) -> Vec<Descriptor> {
         match self {
-            Matched::Root(root) => {
-                assert!(descriptors.is_empty(), "root should not have descriptors");
-                root.children
-                    .iter()
-                    .flat_map(|c| c.rec_into_occurrences(descriptors))
-                    .collect()
-            }
-            Matched::Scope(scope) => {
-                let mut these_descriptors = descriptors.to_vec();
-                these_descriptors.extend(scope.descriptors.iter().cloned());
-
-                let symbol = scip::symbol::format_symbol(scip::types::Symbol {
-                    scheme: "scip-ctags".into(),
-                    // TODO: Package?
-                    package: None.into(),
-                    descriptors: these_descriptors,
-                    ..Default::default()
-                });
-
-                let symbol_roles = scip::types::SymbolRole::Definition.value();
-                let mut children = vec![scip::types::Occurrence {
-                    range: vec![
-                        scope.definer.start_position().row as i32,
-                        scope.definer.start_position().column as i32,
-                        scope.definer.end_position().column as i32,
-                    ],
-                    symbol,
-                    symbol_roles,
-                    // TODO:
-                    // syntax_kind: todo!(),
-                    ..Default::default()
-                }];
-
-                children.extend(scope.children.iter().flat_map(|c| {
-                    let mut descriptors = descriptors.to_vec();
-                    descriptors.extend(scope.descriptors.iter().cloned());
-                    c.rec_into_occurrences(&descriptors)
-                }));
-
-                children
+            Matched::Scope(m) => std::mem::take(&mut m.descriptors),
+            Matched::Global(m) => std::mem::take(&mut m.descriptors),
+        }
+    }
+
+    fn into_occurrence(self, symbol: String) -> scip::types::Occurrence {
+        // An implementation adds to a type defined elsewhere, so it refers to the type
+        // rather than defining it a second time.
+        let symbol_roles = match self.kind() {
+            Some(TagKind::Implementation) => 0,
+            _ => scip::types::SymbolRole::Definition.value(),
+        };
+        let (definer, enclosing_range) = match self {
+            Matched::Scope(scope) => (scope.definer, scope.scope.to_scip_range()),
+            Matched::Global(global) => (
+                global.node,
+                global
+                    .enclosing
+                    .map(|enclosing| enclosing.to_scip_range())
+                    .unwrap_or_default(),
+            ),
+        };
+
+        scip::types::Occurrence {
+            range: vec![
+                definer.start_position().row as i32,
+                definer.start_position().column as i32,
+                definer.end_position().column as i32,
+            ],
+            symbol,
+            symbol_roles,
+            enclosing_range,
+            ..Default::default()
+        }
+    }
+}
+
+fn ctags_symbol(descriptors: Vec<Descriptor>) -> String {
+    scip::symbol::format_symbol(scip::types::Symbol {
+        scheme: "scip-ctags".into(),
+        // TODO: Package?
+        package: None.into(),
+        descriptors,
+        ..Default::default()
+    })
+}
+
+/// The symbol of the innermost definition, in a single string that nested definitions
+/// extend and truncate, so every descriptor is only formatted once.
+struct SymbolStack {
+    symbol: String,
+    /// Length of the scheme and package, which every descriptor is appended to
+    prefix: usize,
+    /// Length of `symbol` before each push
+    lengths: Vec<usize>,
+}
+
+impl SymbolStack {
+    fn new() -> Self {
+        let symbol = ctags_symbol(vec![]);
+        Self {
+            prefix: symbol.len(),
+            symbol,
+            lengths: vec![],
+        }
+    }
+
+    /// Appends `descriptors`, and returns the length of the symbol without the last one,
+    /// which is the symbol of its parent.
+    fn push(&mut self, mut descriptors: Vec<Descriptor>) -> usize {
+        self.lengths.push(self.symbol.len());
+
+        // Descriptors are concatenated after the scheme and package, so they can be
+        // formatted on their own.
+        let last = descriptors.pop();
+        if !descriptors.is_empty() {
+            let formatted = ctags_symbol(descriptors);
+            self.symbol.push_str(&formatted[self.prefix..]);
+        }
+        let parent = self.symbol.len();
+        if let Some(last) = last {
+            let formatted = ctags_symbol(vec![last]);
+            self.symbol.push_str(&formatted[self.prefix..]);
+        }
+
+        parent
+    }
+
+    fn pop(&mut self) {
+        if let Some(len) = self.lengths.pop() {
+            self.symbol.truncate(len);
+        }
+    }
+
+    fn depth(&self) -> usize {
+        self.lengths.len()
+    }
+}
+
+/// Sorts matches in document order, each with the number of scope matches containing it.
+fn nest(matched: Vec<Matched>) -> Vec<(Matched, usize)> {
+    let mut matched = matched.into_iter().enumerate().collect::<Vec<_>>();
+
+    // Outer matches first. Of several matches on the same node, the last one is the outer one.
+    matched.sort_by_key(|(idx, m)| {
+        let node = m.node();
+        (
+            node.start_byte(),
+            std::cmp::Reverse(node.end_byte()),
+            std::cmp::Reverse(*idx),
+        )
+    });
+
+    let mut nested = Vec::with_capacity(matched.len());
+    let mut scopes: Vec<Node> = vec![];
+    for (_, m) in matched {
+        while let Some(scope) = scopes.last() {
+            if scope.contains_node(m.node()) {
+                break;
             }
-            Matched::Global(global) => {
-                let mut these_descriptors = descriptors.to_vec();
-                these_descriptors.extend(global.descriptors.iter().cloned());
-
-                let symbol = scip::symbol::format_symbol(scip::types::Symbol {
-                    scheme: "scip-ctags".into(),
-                    // TODO: Package?
-                    package: None.into(),
-                    descriptors: these_descriptors,
-                    ..Default::default()
-                });
-
-                let symbol_roles = scip::types::SymbolRole::Definition.value();
-                vec![scip::types::Occurrence {
-                    range: vec![
-                        global.node.start_position().row as i32,
-                        global.node.start_position().column as i32,
-                        global.node.end_position().column as i32,
-                    ],
-                    symbol,
-                    symbol_roles,
-                    // TODO:
-                    // syntax_kind: todo!(),
-                    ..Default::default()
-                }]
+            scopes.pop();
+        }
+
+        let depth = scopes.len();
+        if let Matched::Scope(scope) = &m {
+            scopes.push(scope.scope);
+        }
+        nested.push((m, depth));
+    }
+
+    nested
+}
+
+/// Turns matches into occurrences in a single pass over them in document order, keeping
+/// the symbol of the scopes containing the current match.
+fn into_occurrences(matched: Vec<Matched>) -> Vec<scip::types::Occurrence> {
+    let mut occurrences = Vec::with_capacity(matched.len());
+    let mut symbols = SymbolStack::new();
+
+    for (mut m, depth) in nest(matched) {
+        while symbols.depth() > depth {
+            symbols.pop();
+        }
+
+        symbols.push(m.take_descriptors());
+        // `Occurrence.symbol` is an owned protobuf `String`, so it can't be interned or
+        // shared: every occurrence needs its own copy, of a symbol formatted once
+        let symbol = symbols.symbol.clone();
+        if let Matched::Global(_) = m {
+            symbols.pop();
+        }
+
+        occurrences.push(m.into_occurrence(symbol));
+    }
+
+    occurrences
+}
+
+/// The kind of a tag, from its `@definition.<kind>` capture or otherwise from its
+/// descriptor. Functions nested in a type are its methods.
+fn tag_kind(m: &Matched, enclosing: Option<TagKind>) -> TagKind {
+    let in_type = matches!(
+        enclosing,
+        Some(
+            TagKind::Type
+                | TagKind::Struct
+                | TagKind::Interface
+                | TagKind::Trait
+                | TagKind::Implementation
+                | TagKind::Enum
+        )
+    );
+
+    let suffix = m
+        .descriptors()
+        .last()
+        .map(|descriptor| descriptor.suffix.enum_value_or_default());
+    match (m.kind(), suffix) {
+        (Some(TagKind::Function), _) if in_type => TagKind::Method,
+        (Some(kind), _) => kind,
+        (None, Some(Suffix::Namespace)) => TagKind::Namespace,
+        (None, Some(Suffix::Method)) if in_type => TagKind::Method,
+        (None, Some(Suffix::Method)) => TagKind::Function,
+        (None, _) => TagKind::Type,
+    }
+}
+
+/// The parameters of a function declaration, with whitespace collapsed onto one line.
+fn signature(m: &Matched, source_bytes: &[u8]) -> Option<String> {
+    let parameters = m.declaration()?.child_by_field_name("parameters")?;
+    let text = parameters.utf8_text(source_bytes).ok()?;
+
+    Some(text.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+/// Turns matches into tags in document order. The parent of a tag is the tag of the
+/// scope it's nested in, or otherwise the first tag defining all of its descriptors but
+/// the last, wherever it is in the document, like the type of a Go method.
+fn into_tag_entries(matched: Vec<Matched>, source_bytes: &[u8]) -> Vec<TagEntry> {
+    let mut entries: Vec<TagEntry> = Vec::with_capacity(matched.len());
+    // The symbol of each entry, the length of the symbol of its parent in it, and the
+    // entry of the scope it's in
+    let mut symbols = Vec::with_capacity(matched.len());
+
+    let mut stack = SymbolStack::new();
+    let mut path: Vec<Descriptor> = vec![];
+    // The length of `path` before each scope, and the entry of the scope
+    let mut scopes: Vec<(usize, usize)> = vec![];
+    for (mut m, depth) in nest(matched) {
+        if let Some(&(len, _)) = scopes.get(depth) {
+            path.truncate(len);
+            scopes.truncate(depth);
+        }
+        while stack.depth() > depth {
+            stack.pop();
+        }
+
+        let enclosing = scopes.last().map(|(_, entry)| *entry);
+        let kind = tag_kind(&m, enclosing.map(|entry| entries[entry].kind));
+        let line = m.node().start_position().row + 1;
+        let signature = signature(&m, source_bytes);
+
+        let len = path.len();
+        path.extend_from_slice(m.descriptors());
+        let parent = stack.push(m.take_descriptors());
+        symbols.push((stack.symbol.clone(), parent, enclosing));
+        entries.push(TagEntry {
+            descriptors: path.clone(),
+            kind,
+            parent: None,
+            line,
+            signature,
+        });
+
+        match m {
+            Matched::Scope(_) => scopes.push((len, entries.len() - 1)),
+            Matched::Global(_) => {
+                path.truncate(len);
+                stack.pop();
             }
         }
     }
+
+    let mut defined = HashMap::default();
+    for (idx, (symbol, _, _)) in symbols.iter().enumerate() {
+        defined.entry(symbol.as_str()).or_insert(idx);
+    }
+
+    // Parents have fewer descriptors, so they get their own parent first
+    let mut order = (0..entries.len()).collect::<Vec<_>>();
+    order.sort_by_key(|idx| entries[*idx].descriptors.len());
+    for idx in order {
+        let (symbol, parent_len, enclosing) = &symbols[idx];
+        let parent = enclosing
+            .or_else(|| defined.get(&symbol[..*parent_len]).copied())
+            .filter(|parent| *parent != idx)
+            .map(|parent| Box::new(entries[parent].clone()));
+        entries[idx].parent = parent;
+    }
+
+    entries
+}
+
+/// Parses `source` and returns its tags, see [`get_tag_entries`].
+pub fn parse_tag_entries(
+    config: &mut LanguageConfiguration,
+    source: &str,
+) -> Result<Vec<TagEntry>> {
+    let Some(query) = &config.tags else {
+        return Ok(vec![]);
+    };
+
+    let tree = config
+        .parser
+        .parse(source.as_bytes(), None)
+        .context("parsing")?;
+
+    get_tag_entries(query, &tree, source.as_bytes())
 }
 
 pub fn parse_tree<'a>(
@@ -209,17 +394,63 @@ pub fn parse_tree<'a>(
     tree: &'a tree_sitter::Tree,
     source_bytes: &'a [u8],
 ) -> Result<Vec<scip::types::Occurrence>> {
+    get_tags(&config.query, tree, source_bytes)
+}
+
+/// Runs a `scip-tags` query over an already parsed tree, so that callers
+/// which share one parser between several queries don't have to parse twice.
+pub fn get_tags<'a>(
+    query: &Query,
+    tree: &'a tree_sitter::Tree,
+    source_bytes: &'a [u8],
+) -> Result<Vec<scip::types::Occurrence>> {
+    get_tags_within(query, tree, source_bytes, &mut MatchBudget::default())
+}
+
+/// Same as [`get_tags`], stopping once `budget` is spent.
+pub fn get_tags_within<'a>(
+    query: &Query,
+    tree: &'a tree_sitter::Tree,
+    source_bytes: &'a [u8],
+    budget: &mut MatchBudget,
+) -> Result<Vec<scip::types::Occurrence>> {
+    Ok(into_occurrences(get_matches(
+        query,
+        tree,
+        source_bytes,
+        budget,
+    )?))
+}
+
+/// Same as [`get_tags`], as tags with a kind and a parent rather than occurrences.
+pub fn get_tag_entries<'a>(
+    query: &Query,
+    tree: &'a tree_sitter::Tree,
+    source_bytes: &'a [u8],
+) -> Result<Vec<TagEntry>> {
+    Ok(into_tag_entries(
+        get_matches(query, tree, source_bytes, &mut MatchBudget::default())?,
+        source_bytes,
+    ))
+}
+
+fn get_matches<'a>(
+    query: &Query,
+    tree: &'a tree_sitter::Tree,
+    source_bytes: &'a [u8],
+    budget: &mut MatchBudget,
+) -> Result<Vec<Matched<'a>>> {
     let mut cursor = tree_sitter::QueryCursor::new();
 
     let root_node = tree.root_node();
-    let capture_names = config.query.capture_names();
+    let capture_names = query.capture_names();
 
     let mut matched = vec![];
-    for m in cursor.matches(&config.query, root_node, source_bytes) {
-        println!("\n==== NEW MATCH ====");
-
+    for m in budget.limit(cursor.matches(query, root_node, source_bytes)) {
         let mut node = None;
         let mut scope = None;
+        let mut enclosing = None;
+        let mut kind = None;
         let mut descriptors = vec![];
 
         for capture in m.captures {
@@ -237,11 +468,14 @@ pub fn parse_tree<'a>(
                 scope = Some(capture);
             }
 
-            println!(
-                "{}: {}",
-                capture_name,
-                capture.node.utf8_text(source_bytes).unwrap()
-            );
+            if capture_name == "enclosing" {
+                enclosing = Some(capture.node);
+            }
+
+            if capture_name.starts_with("definition.") {
+                kind = Some(capture_name.parse::<TagKind>()?);
+                enclosing = enclosing.or(Some(capture.node));
+            }
         }
 
         let descriptors = descriptors
@@ -252,38 +486,26 @@ pub fn parse_tree<'a>(
             .collect::<Vec<_>>();
 
         let node = node.expect("there must always be at least one descriptor");
-        dbg!(node);
 
         matched.push(match scope {
             Some(scope) => Matched::Scope(Scope {
                 definer: node,
                 scope: scope.node,
                 descriptors,
-                children: vec![],
+                kind,
+            }),
+            None => Matched::Global(Global {
+                node,
+                // Without an explicit `@enclosing` or `@definition.<kind>`, the
+                // declaration is the node the name was captured from.
+                enclosing: enclosing.or_else(|| node.parent()),
+                descriptors,
+                kind,
             }),
-            None => Matched::Global(Global { node, descriptors }),
         })
     }
 
-    dbg!(&matched);
-
-    let mut root = Matched::Root(Root {
-        root: root_node,
-        children: vec![],
-    });
-
-    matched.sort_by_key(|m| {
-        let node = m.node();
-        node.end_byte() - node.start_byte()
-    });
-
-    while let Some(m) = matched.pop() {
-        root.insert(m);
-    }
-    dbg!(&root);
-
-    let tags = root.into_occurences();
-    Ok(dbg!(tags))
+    Ok(matched)
 }
 
 fn dbg_format_descriptors(descriptors: &[Descriptor]) -> Vec<String> {
@@ -293,7 +515,7 @@ fn dbg_format_descriptors(descriptors: &[Descriptor]) -> Vec<String> {
         .collect::<Vec<_>>()
 }
 
-#[cfg(test)]
+#[cfg(all(test, any(feature = "go", feature = "rust")))]
 mod test {
     use scip::types::Document;
 
@@ -320,9 +542,10 @@ mod test {
     }
 
     #[test]
+    #[cfg(feature = "rust")]
     fn test_can_parse_rust_tree() -> Result<()> {
         let mut config = crate::languages::rust();
-        let source_code = include_str!("../testdata/scopes.rs");
+        let source_code = include_str!("../testdata/rust/scopes.rs");
         let doc = parse_file_for_lang(&mut config, source_code)?;
 
         let dumped = dump_document(&doc, source_code);
@@ -332,10 +555,11 @@ mod test {
     }
 
     #[test]
+    #[cfg(feature = "go")]
     fn test_can_parse_go_tree() -> Result<()> {
         let mut config = crate::languages::go();
-        let source_code = include_str!("../testdata/example.go");
-        let doc = dbg!(parse_file_for_lang(&mut config, source_code)?);
+        let source_code = include_str!("../testdata/go/example.go");
+        let doc = parse_file_for_lang(&mut config, source_code)?;
 
         let dumped = dump_document(&doc, source_code);
         insta::assert_snapshot!(dumped);
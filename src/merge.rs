@@ -0,0 +1,376 @@
+use rustc_hash::{FxHashMap as HashMap, FxHashSet as HashSet};
+use scip::types::{Document, Index, Occurrence};
+use serde_json::{json, Value};
+
+use crate::snapshot::PackedRange;
+
+/// Local symbols coming from the search-based index are renamed with this prefix,
+/// so they can't collide with the precise indexer's locals in the same document.
+pub const HEURISTIC_LOCAL_PREFIX: &str = "heuristic_";
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct MergeSummary {
+    pub documents_added: usize,
+    pub documents_extended: usize,
+    pub occurrences_added: usize,
+    pub occurrences_skipped: usize,
+}
+
+/// Which occurrences of a merged index came from the search-based one. It's kept out of
+/// the index, where anything would show up to users, and written next to it instead.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Provenance {
+    /// Name and version of the search-based tool, as `name@version`
+    pub tool: String,
+    /// The ranges of the search-based occurrences of each document, including the
+    /// ones without a symbol, in the order of `Document.occurrences`
+    pub documents: Vec<(String, Vec<Vec<i32>>)>,
+}
+
+impl Provenance {
+    /// `{"tool": ..., "documents": [{"relativePath": ..., "ranges": [[line, start, end], ...]}]}`
+    pub fn to_json(&self) -> Value {
+        json!({
+            "tool": self.tool,
+            "documents": self
+                .documents
+                .iter()
+                .map(|(path, ranges)| json!({ "relativePath": path, "ranges": ranges }))
+                .collect::<Vec<_>>(),
+        })
+    }
+}
+
+/// Adds the search-based `heuristic` index to the `precise` one, without ever
+/// overriding precise data:
+///
+/// - documents missing from the precise index are copied over entirely
+/// - for documents present in both, a search-based occurrence is only added if no
+///   precise occurrence overlaps its range. Local symbols are all-or-nothing: if any
+///   of their occurrences overlap, none of them are added.
+/// - symbol information is only added for symbols the precise index doesn't describe
+///
+/// Locals are prefixed with [`HEURISTIC_LOCAL_PREFIX`], and the range of every added
+/// occurrence is recorded in the returned [`Provenance`]. The precise index's metadata
+/// is left as is.
+pub fn merge_index(precise: &mut Index, heuristic: Index) -> (MergeSummary, Provenance) {
+    let mut summary = MergeSummary::default();
+    let mut provenance = Provenance {
+        tool: heuristic
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.tool_info.as_ref())
+            .map(|tool| format!("{}@{}", tool.name, tool.version))
+            .unwrap_or_else(|| "unknown".to_string()),
+        documents: vec![],
+    };
+
+    let existing = precise
+        .documents
+        .iter()
+        .enumerate()
+        .map(|(idx, doc)| (doc.relative_path.clone(), idx))
+        .collect::<HashMap<_, _>>();
+
+    for mut document in heuristic.documents {
+        rename_locals(&mut document);
+
+        let relative_path = document.relative_path.clone();
+        let ranges = match existing.get(&document.relative_path) {
+            Some(&idx) => {
+                let target = &mut precise.documents[idx];
+                let ranges = merge_document(target, document, &mut summary);
+                if !ranges.is_empty() {
+                    summary.documents_extended += 1;
+                }
+                ranges
+            }
+            None => {
+                let mut added = Document {
+                    relative_path: document.relative_path.clone(),
+                    language: document.language.clone(),
+                    text: document.text.clone(),
+                    ..Default::default()
+                };
+                let ranges = merge_document(&mut added, document, &mut summary);
+                summary.documents_added += 1;
+                precise.documents.push(added);
+                ranges
+            }
+        };
+
+        if !ranges.is_empty() {
+            provenance.documents.push((relative_path, ranges));
+        }
+    }
+
+    (summary, provenance)
+}
+
+/// Adds what `heuristic` has that doesn't overlap `precise`, and returns the ranges
+/// of the added occurrences.
+fn merge_document(
+    precise: &mut Document,
+    heuristic: Document,
+    summary: &mut MergeSummary,
+) -> Vec<Vec<i32>> {
+    let coverage = Coverage::new(&precise.occurrences);
+
+    // A local is only usable if all of its occurrences make it in, otherwise we would
+    // end up with references pointing to a definition that was dropped.
+    let mut rejected_locals = HashSet::default();
+    for occ in &heuristic.occurrences {
+        if is_heuristic_local(&occ.symbol) && coverage.overlaps(&occ.range) {
+            rejected_locals.insert(occ.symbol.clone());
+        }
+    }
+
+    let mut described = precise
+        .symbols
+        .iter()
+        .map(|info| info.symbol.clone())
+        .collect::<HashSet<_>>();
+    let mut infos = heuristic
+        .symbols
+        .into_iter()
+        .map(|info| (info.symbol.clone(), info))
+        .collect::<HashMap<_, _>>();
+
+    let mut ranges = vec![];
+    for occ in heuristic.occurrences {
+        if rejected_locals.contains(&occ.symbol) || coverage.overlaps(&occ.range) {
+            summary.occurrences_skipped += 1;
+            continue;
+        }
+
+        if described.insert(occ.symbol.clone()) {
+            if let Some(info) = infos.remove(&occ.symbol) {
+                precise.symbols.push(info);
+            }
+        }
+
+        ranges.push(occ.range.clone());
+        precise.occurrences.push(occ);
+    }
+
+    if !ranges.is_empty() {
+        precise
+            .occurrences
+            .sort_by_key(|occ| PackedRange::from_vec(&occ.range));
+    }
+
+    summary.occurrences_added += ranges.len();
+    ranges
+}
+
+fn is_heuristic_local(symbol: &str) -> bool {
+    symbol
+        .strip_prefix("local ")
+        .filter(|id| id.starts_with(HEURISTIC_LOCAL_PREFIX))
+        .is_some()
+}
+
+fn rename_locals(document: &mut Document) {
+    let rename = |symbol: &mut String| {
+        if let Some(id) = symbol.strip_prefix("local ") {
+            *symbol = format!("local {HEURISTIC_LOCAL_PREFIX}{id}");
+        }
+    };
+
+    document
+        .occurrences
+        .iter_mut()
+        .for_each(|occ| rename(&mut occ.symbol));
+    document
+        .symbols
+        .iter_mut()
+        .for_each(|info| rename(&mut info.symbol));
+}
+
+/// The columns covered by precise occurrences, per line.
+struct Coverage {
+    lines: HashMap<i32, Vec<(i32, i32)>>,
+}
+
+impl Coverage {
+    fn new(occurrences: &[Occurrence]) -> Self {
+        let mut lines = HashMap::<i32, Vec<(i32, i32)>>::default();
+        for occ in occurrences {
+            let range = PackedRange::from_vec(&occ.range);
+            for (line, start, end) in line_spans(range) {
+                lines.entry(line).or_default().push((start, end));
+            }
+        }
+
+        Self { lines }
+    }
+
+    fn overlaps(&self, range: &[i32]) -> bool {
+        let range = PackedRange::from_vec(range);
+        line_spans(range).any(|(line, start, end)| {
+            self.lines
+                .get(&line)
+                .into_iter()
+                .flatten()
+                .any(|&(s, e)| (start < e && s < end) || start == s)
+        })
+    }
+}
+
+fn line_spans(range: PackedRange) -> impl Iterator<Item = (i32, i32, i32)> {
+    (range.start_line..=range.end_line).map(move |line| {
+        let start = if line == range.start_line {
+            range.start_col
+        } else {
+            0
+        };
+        let end = if line == range.end_line {
+            range.end_col
+        } else {
+            i32::MAX
+        };
+
+        (line, start, end)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use protobuf::Enum;
+    use scip::types::SymbolRole;
+
+    use super::*;
+
+    fn occurrence(range: Vec<i32>, symbol: &str, definition: bool) -> Occurrence {
+        Occurrence {
+            range,
+            symbol: symbol.to_string(),
+            symbol_roles: if definition {
+                SymbolRole::Definition.value()
+            } else {
+                0
+            },
+            ..Default::default()
+        }
+    }
+
+    fn document(path: &str, occurrences: Vec<Occurrence>) -> Document {
+        Document {
+            relative_path: path.to_string(),
+            occurrences,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_adds_missing_documents() {
+        let mut precise = Index {
+            documents: vec![document(
+                "a.go",
+                vec![occurrence(vec![0, 0, 3], "go a 1 a/", true)],
+            )],
+            ..Default::default()
+        };
+        let heuristic = Index {
+            documents: vec![document(
+                "b.go",
+                vec![
+                    occurrence(vec![1, 0, 3], "local 1", true),
+                    // A highlight, without a symbol
+                    occurrence(vec![2, 0, 6], "", false),
+                ],
+            )],
+            ..Default::default()
+        };
+
+        let (summary, provenance) = merge_index(&mut precise, heuristic);
+
+        assert_eq!(summary.documents_added, 1);
+        assert_eq!(precise.documents.len(), 2);
+        assert_eq!(
+            precise.documents[1].occurrences[0].symbol,
+            "local heuristic_1"
+        );
+        assert!(precise.documents[1].symbols.is_empty());
+        assert!(precise.metadata.is_none());
+        assert_eq!(
+            provenance,
+            Provenance {
+                tool: "unknown".to_string(),
+                documents: vec![("b.go".to_string(), vec![vec![1, 0, 3], vec![2, 0, 6]])],
+            }
+        );
+    }
+
+    #[test]
+    fn test_never_overrides_precise_ranges() {
+        let mut precise = Index {
+            documents: vec![document(
+                "a.go",
+                vec![
+                    occurrence(vec![2, 4, 8], "go a 1 a/Thing#", true),
+                    occurrence(vec![5, 1, 2], "local 1", false),
+                ],
+            )],
+            ..Default::default()
+        };
+        let heuristic = Index {
+            documents: vec![document(
+                "a.go",
+                vec![
+                    // Same range as a precise definition
+                    occurrence(vec![2, 4, 8], "scip-ctags Thing#", true),
+                    // Nothing precise here
+                    occurrence(vec![3, 0, 6], "scip-ctags Other#", true),
+                    // A local whose reference collides with a precise occurrence
+                    occurrence(vec![4, 1, 2], "local 1", true),
+                    occurrence(vec![5, 1, 2], "local 1", false),
+                    // A local that fits entirely
+                    occurrence(vec![6, 1, 2], "local 2", true),
+                    occurrence(vec![7, 1, 2], "local 2", false),
+                ],
+            )],
+            ..Default::default()
+        };
+
+        let (summary, provenance) = merge_index(&mut precise, heuristic);
+
+        assert_eq!(
+            summary,
+            MergeSummary {
+                documents_added: 0,
+                documents_extended: 1,
+                occurrences_added: 3,
+                occurrences_skipped: 3,
+            }
+        );
+
+        let symbols = precise.documents[0]
+            .occurrences
+            .iter()
+            .map(|o| o.symbol.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            symbols,
+            vec![
+                "go a 1 a/Thing#",
+                "scip-ctags Other#",
+                "local 1",
+                "local heuristic_2",
+                "local heuristic_2",
+            ]
+        );
+
+        assert_eq!(
+            provenance.documents,
+            vec![(
+                "a.go".to_string(),
+                vec![vec![3, 0, 6], vec![6, 1, 2], vec![7, 1, 2]]
+            )]
+        );
+        assert!(precise.documents[0]
+            .symbols
+            .iter()
+            .all(|info| info.documentation.is_empty()));
+    }
+}
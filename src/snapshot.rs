@@ -1,7 +1,22 @@
-use std::{collections::VecDeque, fmt::Write};
+use std::{
+    collections::VecDeque,
+    fmt::Write,
+    fs, io,
+    path::{Path, PathBuf},
+};
 
+use anyhow::Result;
+use pretty_assertions::StrComparison;
 use protobuf::Enum;
-use scip::types::{Document, SymbolRole};
+use rustc_hash::FxHashMap as HashMap;
+use scip::types::{symbol_information::Kind, Document, SymbolInformation, SymbolRole, SyntaxKind};
+use unicode_width::UnicodeWidthChar;
+use walkdir::WalkDir;
+
+use crate::{
+    document::parse_document,
+    languages::{language_names, load_language_configuration},
+};
 
 pub struct FileRange {
     pub start: usize,
@@ -17,6 +32,12 @@ pub fn dump_document_range(doc: &Document, source: &str, file_range: &Option<Fil
     occurrences.sort_by_key(|o| PackedRange::from_vec(&o.range));
     let mut occurrences = VecDeque::from(occurrences);
 
+    let mut symbols = doc
+        .symbols
+        .iter()
+        .map(|info| (info.symbol.as_str(), info))
+        .collect::<HashMap<_, _>>();
+
     let mut result = String::new();
 
     let line_iterator: Box<dyn Iterator<Item = (usize, &str)>> = match file_range {
@@ -32,7 +53,7 @@ pub fn dump_document_range(doc: &Document, source: &str, file_range: &Option<Fil
 
     for (idx, line) in line_iterator {
         result += "  ";
-        result += &line.replace('\t', " ");
+        result += line;
         result += "\n";
 
         while let Some(occ) = occurrences.pop_front() {
@@ -51,36 +72,36 @@ pub fn dump_document_range(doc: &Document, source: &str, file_range: &Option<Fil
                     break;
                 }
                 std::cmp::Ordering::Equal => {
-                    let length = (end_col - range.start_col) as usize;
-                    let multiline_suffix = if is_single_line {
-                        "".to_string()
-                    } else {
-                        format!(
-                            " {}:{}..{}:{}",
+                    let indent = caret_indent(line, range.start_col as usize);
+                    let carets = "^".repeat(display_width(
+                        line,
+                        range.start_col as usize,
+                        end_col as usize,
+                    ));
+
+                    let mut parts = format_roles(occ.symbol_roles, occ.symbol.is_empty());
+                    if !occ.symbol.is_empty() {
+                        parts.push(occ.symbol.clone());
+                    }
+                    if !is_single_line {
+                        parts.push(format!(
+                            "{}:{}..{}:{}",
                             range.start_line, range.start_col, range.end_line, range.end_col
-                        )
-                    };
-                    let symbol_suffix = if occ.symbol.is_empty() {
-                        "".to_owned()
-                    } else {
-                        format!(" {}", occ.symbol)
-                    };
-
-                    let kind = if occ.symbol_roles == SymbolRole::Definition.value() {
-                        "definition"
-                    } else {
-                        "reference"
-                    };
-
-                    // TODO: This will only work for definitions right now
-                    let _ = writeln!(
-                        result,
-                        "//{}{} {}{multiline_suffix} {}",
-                        " ".repeat(range.start_col as usize),
-                        "^".repeat(length),
-                        kind,
-                        symbol_suffix,
-                    );
+                        ));
+                    }
+                    match occ.syntax_kind.enum_value_or_default() {
+                        SyntaxKind::UnspecifiedSyntaxKind => {}
+                        syntax_kind => parts.push(format!("{:?}", syntax_kind)),
+                    }
+
+                    let _ = writeln!(result, "//{indent}{carets} {}", parts.join(" "));
+
+                    // Symbol information is printed once, under the first definition
+                    if occ.symbol_roles & SymbolRole::Definition.value() != 0 {
+                        if let Some(info) = symbols.remove(occ.symbol.as_str()) {
+                            dump_symbol_information(&mut result, &indent, info);
+                        }
+                    }
                 }
             }
         }
@@ -89,6 +110,219 @@ pub fn dump_document_range(doc: &Document, source: &str, file_range: &Option<Fil
     result
 }
 
+pub const ROLE_NAMES: &[(SymbolRole, &str)] = &[
+    (SymbolRole::Definition, "definition"),
+    (SymbolRole::Import, "import"),
+    (SymbolRole::WriteAccess, "write"),
+    (SymbolRole::ReadAccess, "read"),
+    (SymbolRole::Generated, "generated"),
+    (SymbolRole::Test, "test"),
+];
+
+/// Every role bit that is set, with a leading `reference` for anything that isn't a definition.
+pub fn format_roles(symbol_roles: i32, is_syntax_only: bool) -> Vec<String> {
+    let mut parts = vec![];
+    if symbol_roles & SymbolRole::Definition.value() == 0 {
+        parts.push(
+            if is_syntax_only {
+                "syntax"
+            } else {
+                "reference"
+            }
+            .to_string(),
+        );
+    }
+
+    let mut remaining = symbol_roles;
+    for (role, name) in ROLE_NAMES {
+        if symbol_roles & role.value() != 0 {
+            parts.push(name.to_string());
+            remaining &= !role.value();
+        }
+    }
+
+    if remaining != 0 {
+        parts.push(format!("role(0x{:x})", remaining));
+    }
+
+    parts
+}
+
+fn dump_symbol_information(result: &mut String, indent: &str, info: &SymbolInformation) {
+    match info.kind.enum_value_or_default() {
+        Kind::UnspecifiedKind => {}
+        kind => {
+            let _ = writeln!(result, "//{indent}kind {:?}", kind);
+        }
+    }
+
+    if !info.documentation.is_empty() {
+        let _ = writeln!(result, "//{indent}documentation");
+        for documentation in &info.documentation {
+            for line in documentation.lines() {
+                let _ = writeln!(result, "//{indent}> {line}");
+            }
+        }
+    }
+
+    for relationship in &info.relationships {
+        let mut kinds = vec![];
+        if relationship.is_reference {
+            kinds.push("reference");
+        }
+        if relationship.is_implementation {
+            kinds.push("implementation");
+        }
+        if relationship.is_type_definition {
+            kinds.push("type_definition");
+        }
+        if relationship.is_definition {
+            kinds.push("definition");
+        }
+
+        let _ = writeln!(
+            result,
+            "//{indent}relationship {} {}",
+            kinds.join(" "),
+            relationship.symbol
+        );
+    }
+}
+
+/// Whitespace that lines the carets up under `line[..column]`, keeping tabs as tabs
+/// and giving wide characters two columns, the same way a terminal would render them.
+fn caret_indent(line: &str, column: usize) -> String {
+    match line.get(..column) {
+        Some(prefix) => prefix
+            .chars()
+            .map(|c| match c {
+                '\t' => "\t".to_string(),
+                c => " ".repeat(c.width().unwrap_or(0)),
+            })
+            .collect(),
+        None => " ".repeat(column),
+    }
+}
+
+/// Number of terminal columns taken by `line[start..end]`, where `start` and `end` are
+/// byte offsets as reported by tree-sitter.
+fn display_width(line: &str, start: usize, end: usize) -> usize {
+    match line.get(start..end) {
+        Some(text) => text.chars().map(|c| c.width().unwrap_or(1)).sum(),
+        None => end.saturating_sub(start),
+    }
+}
+
+/// Golden outputs for `testdata/<lang>/<path>` live in `testdata/snapshots/<lang>/<path>`.
+pub const SNAPSHOT_DIRECTORY: &str = "snapshots";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum SnapshotOutcome {
+    Matched,
+    Updated,
+    Missing,
+    Mismatched { expected: String, actual: String },
+}
+
+#[derive(Debug)]
+pub struct SnapshotResult {
+    pub input: PathBuf,
+    pub expected: PathBuf,
+    pub outcome: SnapshotOutcome,
+}
+
+impl std::fmt::Display for SnapshotResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.outcome {
+            SnapshotOutcome::Matched => write!(f, "ok: {}", self.input.display()),
+            SnapshotOutcome::Updated => write!(f, "updated: {}", self.expected.display()),
+            SnapshotOutcome::Missing => write!(
+                f,
+                "missing: {} has no snapshot at {}",
+                self.input.display(),
+                self.expected.display()
+            ),
+            SnapshotOutcome::Mismatched { expected, actual } => write!(
+                f,
+                "mismatch: {}\n{}",
+                self.input.display(),
+                StrComparison::new(expected, actual)
+            ),
+        }
+    }
+}
+
+/// Every file in `testdata/<language>/`, sorted by path.
+pub fn testdata_files(testdata: &Path, language: &str) -> Result<Vec<PathBuf>> {
+    let input_root = testdata.join(language);
+    if !input_root.is_dir() {
+        return Ok(vec![]);
+    }
+
+    let mut files = vec![];
+    for entry in WalkDir::new(&input_root).sort_by_file_name() {
+        let entry = entry?;
+        if entry.file_type().is_file() {
+            files.push(entry.into_path());
+        }
+    }
+
+    Ok(files)
+}
+
+/// Runs the document pipeline over every file in `testdata/<lang>/`, for each
+/// language in the registry, and compares the dumped document to its golden file.
+///
+/// With `update`, golden files that are missing or out of date are written instead.
+/// Queries are loaded from `queries` when given, see [`load_language_configuration`].
+pub fn run_snapshots(
+    testdata: &Path,
+    queries: Option<&Path>,
+    update: bool,
+) -> Result<Vec<SnapshotResult>> {
+    let mut results = vec![];
+
+    for language in language_names() {
+        let input_root = testdata.join(language);
+        let output_root = testdata.join(SNAPSHOT_DIRECTORY).join(language);
+        let mut config = load_language_configuration(language, queries)?
+            .expect("registered languages have a configuration");
+
+        for input in testdata_files(testdata, language)? {
+            let expected = output_root.join(input.strip_prefix(&input_root)?);
+
+            let source = fs::read_to_string(&input)?;
+            let doc = parse_document(&mut config, &source)?;
+            let actual = dump_document(&doc, &source);
+
+            let outcome = match fs::read_to_string(&expected) {
+                Ok(contents) if contents == actual => SnapshotOutcome::Matched,
+                Ok(_) | Err(_) if update => {
+                    if let Some(parent) = expected.parent() {
+                        fs::create_dir_all(parent)?;
+                    }
+                    fs::write(&expected, actual)?;
+                    SnapshotOutcome::Updated
+                }
+                Ok(contents) => SnapshotOutcome::Mismatched {
+                    expected: contents,
+                    actual,
+                },
+                Err(err) if err.kind() == io::ErrorKind::NotFound => SnapshotOutcome::Missing,
+                Err(err) => return Err(err.into()),
+            };
+
+            results.push(SnapshotResult {
+                input,
+                expected,
+                outcome,
+            });
+        }
+    }
+
+    Ok(results)
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct PackedRange {
     pub start_line: i32,
@@ -122,11 +356,7 @@ impl PackedRange {
 
 impl PartialOrd for PackedRange {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        (self.start_line, self.end_line, self.start_col).partial_cmp(&(
-            other.start_line,
-            other.end_line,
-            other.start_col,
-        ))
+        Some(self.cmp(other))
     }
 }
 
@@ -139,3 +369,72 @@ impl Ord for PackedRange {
         ))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use scip::types::{Occurrence, Relationship};
+
+    use super::*;
+
+    #[test]
+    fn test_dump_roles_alignment_and_symbol_information() {
+        let source = "\tlet 名前 = 1;\n\tname += 名前;\n";
+        let role = |roles: &[SymbolRole]| roles.iter().fold(0, |acc, r| acc | r.value());
+
+        let doc = Document {
+            occurrences: vec![
+                Occurrence {
+                    range: vec![0, 5, 11],
+                    symbol: "local 1".to_string(),
+                    symbol_roles: role(&[SymbolRole::Definition, SymbolRole::WriteAccess]),
+                    ..Default::default()
+                },
+                Occurrence {
+                    range: vec![1, 1, 5],
+                    symbol: "scip-ctags name.".to_string(),
+                    symbol_roles: role(&[SymbolRole::ReadAccess, SymbolRole::WriteAccess]),
+                    ..Default::default()
+                },
+                Occurrence {
+                    range: vec![1, 9, 15],
+                    symbol: "local 1".to_string(),
+                    symbol_roles: role(&[SymbolRole::ReadAccess]),
+                    syntax_kind: SyntaxKind::IdentifierLocal.into(),
+                    ..Default::default()
+                },
+            ],
+            symbols: vec![SymbolInformation {
+                symbol: "local 1".to_string(),
+                documentation: vec!["A name\nwith two lines".to_string()],
+                kind: Kind::Variable.into(),
+                relationships: vec![Relationship {
+                    symbol: "scip-ctags name.".to_string(),
+                    is_reference: true,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        insta::assert_snapshot!(dump_document(&doc, source));
+    }
+
+    #[test]
+    fn test_testdata_snapshots() -> Result<()> {
+        let testdata = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata");
+        let failures = run_snapshots(&testdata, None, false)?
+            .into_iter()
+            .filter(|result| result.outcome != SnapshotOutcome::Matched)
+            .map(|result| result.to_string())
+            .collect::<Vec<_>>();
+
+        assert!(
+            failures.is_empty(),
+            "{}\n\nRun `cargo run --bin scip-semantic -- snapshot --update` to accept the changes",
+            failures.join("\n")
+        );
+
+        Ok(())
+    }
+}
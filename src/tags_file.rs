@@ -0,0 +1,87 @@
+//! Classic `tags` files for vim, emacs and other editors, with the tags search uses.
+
+use std::{io::Write, path::Path};
+
+use anyhow::Result;
+
+use crate::{
+    index::{for_each_source, IndexOptions},
+    languages::LanguageConfiguration,
+    matches::parse_tag_entries,
+};
+
+/// The lines of the `tags` file for one file, unsorted.
+pub fn file_tag_lines(
+    path: &str,
+    config: &mut LanguageConfiguration,
+    source: &str,
+) -> Result<Vec<String>> {
+    let lines = source.lines().collect::<Vec<_>>();
+
+    Ok(parse_tag_entries(config, source)?
+        .iter()
+        .map(|entry| {
+            let source_line = lines.get(entry.line - 1).copied().unwrap_or_default();
+            entry.tags_line(path, config.name, source_line)
+        })
+        .collect())
+}
+
+/// Writes the `tags` file of every supported file under `root`, with paths relative to
+/// `root`, and returns the number of tags.
+pub fn write_directory_tags(
+    root: &Path,
+    options: &IndexOptions,
+    writer: &mut impl Write,
+) -> Result<usize> {
+    let mut lines = vec![];
+    for_each_source(root, options, |path, config, source| {
+        lines.extend(file_tag_lines(path, config, source)?);
+        Ok(())
+    })?;
+
+    let count = lines.len();
+    ctags::write_tags_file(writer, "scip-semantic", env!("CARGO_PKG_VERSION"), lines)?;
+
+    Ok(count)
+}
+
+#[cfg(all(test, any(feature = "go", feature = "rust")))]
+mod test {
+    use super::*;
+
+    fn testdata_tags(language: &str) -> Result<String> {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("testdata")
+            .join(language);
+
+        let mut tags = vec![];
+        let count = write_directory_tags(&root, &IndexOptions::default(), &mut tags)?;
+        let tags = String::from_utf8(tags)?;
+
+        let lines = tags
+            .lines()
+            .filter(|line| !line.starts_with("!_TAG_"))
+            .collect::<Vec<_>>();
+        assert_eq!(lines.len(), count);
+        assert!(lines.windows(2).all(|pair| pair[0] <= pair[1]));
+
+        Ok(tags.replace(env!("CARGO_PKG_VERSION"), "[version]"))
+    }
+
+    #[test]
+    #[cfg(feature = "go")]
+    fn test_writes_a_sorted_tags_file() -> Result<()> {
+        insta::assert_snapshot!(testdata_tags("go")?);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "rust")]
+    fn test_writes_rust_scopes_like_ctags() -> Result<()> {
+        insta::assert_snapshot!(testdata_tags("rust")?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+//! Time spent in each phase of indexing a document, for `scip-perf`.
+
+use std::{
+    ops::AddAssign,
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Timings {
+    /// tree-sitter parsing, of the document and its injected regions
+    pub parse: Duration,
+    /// Running the queries and collecting their captures
+    pub matching: Duration,
+    /// Building the scope tree and adding definitions to it
+    pub scopes: Duration,
+    /// Resolving references, and merging the output of the passes
+    pub resolve: Duration,
+    /// Encoding the document as protobuf
+    pub serialize: Duration,
+}
+
+impl Timings {
+    pub const PHASES: [&'static str; 5] = ["parse", "matching", "scopes", "resolve", "serialize"];
+
+    /// The durations in the order of [`Timings::PHASES`].
+    pub fn phases(&self) -> [Duration; 5] {
+        [
+            self.parse,
+            self.matching,
+            self.scopes,
+            self.resolve,
+            self.serialize,
+        ]
+    }
+
+    pub fn total(&self) -> Duration {
+        self.phases().iter().sum()
+    }
+}
+
+impl AddAssign for Timings {
+    fn add_assign(&mut self, other: Self) {
+        self.parse += other.parse;
+        self.matching += other.matching;
+        self.scopes += other.scopes;
+        self.resolve += other.resolve;
+        self.serialize += other.serialize;
+    }
+}
+
+/// Runs `f`, adding the time it took to `phase`.
+pub fn timed<T>(phase: &mut Duration, f: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let result = f();
+    *phase += start.elapsed();
+
+    result
+}
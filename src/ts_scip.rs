@@ -1,15 +1,71 @@
-use scip::types::{descriptor::Suffix, Descriptor};
+use scip::types::{descriptor::Suffix, Descriptor, SyntaxKind};
 
 pub fn capture_name_to_descriptor(capture: &str, name: String) -> Descriptor {
     Descriptor {
-        suffix: match capture {
-            "descriptor.method" => Suffix::Method,
-            "descriptor.namespace" => Suffix::Namespace,
-            "descriptor.type" => Suffix::Type,
-            _ => unimplemented!("Missing {}", name),
-        }
-        .into(),
+        suffix: descriptor_suffix(capture)
+            .unwrap_or_else(|| unimplemented!("Missing {}", name))
+            .into(),
         name,
         ..Default::default()
     }
 }
+
+/// The descriptor suffix for a `scip-tags` capture, if it's one we support.
+pub fn descriptor_suffix(capture: &str) -> Option<Suffix> {
+    match capture {
+        "descriptor.method" => Some(Suffix::Method),
+        "descriptor.namespace" => Some(Suffix::Namespace),
+        "descriptor.type" => Some(Suffix::Type),
+        _ => None,
+    }
+}
+
+/// Maps a `scip-highlights` capture to a SyntaxKind.
+///
+/// Unknown captures fall back to their parent, so `@keyword.function`
+/// is highlighted as a `@keyword`.
+pub fn capture_name_to_syntax_kind(capture: &str) -> Option<SyntaxKind> {
+    let mut capture = capture;
+    loop {
+        let kind = match capture {
+            "attribute" => SyntaxKind::IdentifierAttribute,
+            "boolean" => SyntaxKind::BooleanLiteral,
+            "character" => SyntaxKind::CharacterLiteral,
+            "comment" => SyntaxKind::Comment,
+            "constant" => SyntaxKind::IdentifierConstant,
+            "constant.null" => SyntaxKind::IdentifierNull,
+            "function" => SyntaxKind::IdentifierFunction,
+            "function.definition" => SyntaxKind::IdentifierFunctionDefinition,
+            "keyword" => SyntaxKind::Keyword,
+            "macro" => SyntaxKind::IdentifierMacro,
+            "macro.definition" => SyntaxKind::IdentifierMacroDefinition,
+            "namespace" => SyntaxKind::IdentifierNamespace,
+            "number" => SyntaxKind::NumericLiteral,
+            "operator" => SyntaxKind::IdentifierOperator,
+            "punctuation.bracket" => SyntaxKind::PunctuationBracket,
+            "punctuation.delimiter" => SyntaxKind::PunctuationDelimiter,
+            "string" => SyntaxKind::StringLiteral,
+            "string.escape" => SyntaxKind::StringLiteralEscape,
+            "string.special" => SyntaxKind::StringLiteralSpecial,
+            "tag" => SyntaxKind::Tag,
+            "tag.attribute" => SyntaxKind::TagAttribute,
+            "tag.delimiter" => SyntaxKind::TagDelimiter,
+            "type" => SyntaxKind::IdentifierType,
+            "type.builtin" => SyntaxKind::IdentifierBuiltinType,
+            "variable" => SyntaxKind::Identifier,
+            "variable.builtin" => SyntaxKind::IdentifierBuiltin,
+            "variable.global" => SyntaxKind::IdentifierMutableGlobal,
+            "variable.local" => SyntaxKind::IdentifierLocal,
+            "variable.parameter" => SyntaxKind::IdentifierParameter,
+            _ => match capture.rsplit_once('.') {
+                Some((parent, _)) => {
+                    capture = parent;
+                    continue;
+                }
+                None => return None,
+            },
+        };
+
+        return Some(kind);
+    }
+}
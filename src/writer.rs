@@ -0,0 +1,122 @@
+//! Writes a SCIP index one document at a time, so that indexing a large repository
+//! doesn't need every document in memory at once.
+//!
+//! An `Index` is a protobuf message whose fields are its metadata (1), documents (2)
+//! and external symbols (3). Writing each of them as a length-delimited field, in
+//! field order, gives the same bytes as encoding the whole `Index` at once.
+
+use std::io::Write;
+
+use anyhow::{Context, Result};
+use protobuf::Message;
+use scip::types::{Document, Metadata, SymbolInformation};
+
+const METADATA_TAG: u8 = 1 << 3 | 2;
+const DOCUMENT_TAG: u8 = 2 << 3 | 2;
+const EXTERNAL_SYMBOL_TAG: u8 = 3 << 3 | 2;
+
+pub struct IndexWriter<W: Write> {
+    writer: W,
+    documents: usize,
+    writing_external_symbols: bool,
+}
+
+impl<W: Write> IndexWriter<W> {
+    /// Starts an index, writing its metadata if there is any.
+    pub fn new(mut writer: W, metadata: Option<&Metadata>) -> Result<Self> {
+        if let Some(metadata) = metadata {
+            write_field(&mut writer, METADATA_TAG, metadata).context("writing the metadata")?;
+        }
+
+        Ok(Self {
+            writer,
+            documents: 0,
+            writing_external_symbols: false,
+        })
+    }
+
+    pub fn write_document(&mut self, document: &Document) -> Result<()> {
+        assert!(
+            !self.writing_external_symbols,
+            "documents must be written before external symbols"
+        );
+
+        write_field(&mut self.writer, DOCUMENT_TAG, document)
+            .with_context(|| format!("writing {}", document.relative_path))?;
+        self.documents += 1;
+
+        Ok(())
+    }
+
+    pub fn write_external_symbol(&mut self, symbol: &SymbolInformation) -> Result<()> {
+        self.writing_external_symbols = true;
+        write_field(&mut self.writer, EXTERNAL_SYMBOL_TAG, symbol)
+            .with_context(|| format!("writing {}", symbol.symbol))
+    }
+
+    /// How many documents were written so far.
+    pub fn documents(&self) -> usize {
+        self.documents
+    }
+
+    /// Flushes the index and gives back the underlying writer.
+    pub fn finish(mut self) -> Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+fn write_field(writer: &mut impl Write, tag: u8, message: &impl Message) -> Result<()> {
+    writer.write_all(&[tag])?;
+    message.write_length_delimited_to_writer(writer)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use protobuf::MessageField;
+    use scip::types::{Index, Occurrence, ToolInfo};
+
+    use super::*;
+
+    #[test]
+    fn test_matches_a_buffered_write() -> Result<()> {
+        let mut index = Index::new();
+        index.metadata = MessageField::some(Metadata {
+            tool_info: MessageField::some(ToolInfo {
+                name: "scip-semantic".to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        });
+        for path in ["a.go", "b.go"] {
+            let mut document = Document::new();
+            document.relative_path = path.to_string();
+            document.occurrences.push(Occurrence {
+                range: vec![0, 1, 2],
+                symbol: "local 1".to_string(),
+                ..Default::default()
+            });
+            index.documents.push(document);
+        }
+        index.external_symbols.push(SymbolInformation {
+            symbol: "scip-ctags fmt/".to_string(),
+            ..Default::default()
+        });
+
+        let mut writer = IndexWriter::new(vec![], index.metadata.as_ref())?;
+        for document in &index.documents {
+            writer.write_document(document)?;
+        }
+        for symbol in &index.external_symbols {
+            writer.write_external_symbol(symbol)?;
+        }
+        let streamed = writer.finish()?;
+
+        assert_eq!(streamed, index.write_to_bytes()?);
+        assert_eq!(Index::parse_from_bytes(&streamed)?, index);
+
+        Ok(())
+    }
+}
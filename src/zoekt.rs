@@ -0,0 +1,111 @@
+//! Symbols as zoekt's `Symbol{Sym, Kind, Parent, ParentKind}`, so that its symbol search
+//! can read them from scip-semantic rather than universal-ctags. Kinds are named the way
+//! universal-ctags names them, which is what zoekt ranks symbols by.
+
+use std::path::Path;
+
+use anyhow::Result;
+use ctags::TagEntry;
+use serde_json::json;
+
+use crate::{
+    index::{for_each_source, IndexOptions},
+    languages::LanguageConfiguration,
+    matches::parse_tag_entries,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZoektSymbol {
+    pub sym: String,
+    pub kind: String,
+    pub parent: String,
+    pub parent_kind: String,
+}
+
+impl ZoektSymbol {
+    pub fn from_entry(entry: &TagEntry, language: &str) -> Self {
+        let (parent, parent_kind) = match entry.parent_name_and_kind(language) {
+            Some((name, kind)) => (name, kind.ctags_name(language)),
+            None => ("", ""),
+        };
+
+        Self {
+            sym: entry.name().to_string(),
+            kind: entry.kind.ctags_name(language).to_string(),
+            parent: parent.to_string(),
+            parent_kind: parent_kind.to_string(),
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        json!({
+            "Sym": self.sym,
+            "Kind": self.kind,
+            "Parent": self.parent,
+            "ParentKind": self.parent_kind,
+        })
+    }
+}
+
+/// The symbols of one file, in document order.
+pub fn file_symbols(config: &mut LanguageConfiguration, source: &str) -> Result<Vec<ZoektSymbol>> {
+    let entries = parse_tag_entries(config, source)?;
+
+    Ok(entries
+        .iter()
+        .map(|entry| ZoektSymbol::from_entry(entry, config.name))
+        .collect())
+}
+
+/// Calls `emit` with one JSON line per supported file under `root`, as
+/// `{"path": ..., "symbols": [{"Sym": ..., "Kind": ..., "Parent": ..., "ParentKind": ...}]}`.
+pub fn directory_symbols(
+    root: &Path,
+    options: &IndexOptions,
+    mut emit: impl FnMut(String) -> Result<()>,
+) -> Result<()> {
+    for_each_source(root, options, |path, config, source| {
+        let symbols = file_symbols(config, source)?;
+        let line = json!({
+            "path": path,
+            "symbols": symbols.iter().map(ZoektSymbol::to_json).collect::<Vec<_>>(),
+        });
+
+        emit(line.to_string())
+    })
+}
+
+#[cfg(all(test, any(feature = "go", feature = "rust")))]
+mod test {
+    use super::*;
+
+    fn testdata_symbols(language: &str) -> Result<String> {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("testdata")
+            .join(language);
+
+        let mut lines = vec![];
+        directory_symbols(&root, &IndexOptions::default(), |line| {
+            lines.push(line);
+            Ok(())
+        })?;
+
+        Ok(lines.join("\n"))
+    }
+
+    #[test]
+    #[cfg(feature = "go")]
+    fn test_go_symbols() -> Result<()> {
+        insta::assert_snapshot!(testdata_symbols("go")?);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "rust")]
+    fn test_rust_symbols() -> Result<()> {
+        insta::assert_snapshot!(testdata_symbols("rust")?);
+
+        Ok(())
+    }
+}
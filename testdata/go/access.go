@@ -0,0 +1,16 @@
+package main
+
+func main() {
+	total, count := 0, 0
+	total = 2
+	// <- reference write -> 4:2
+	total += count
+	// <- reference read write -> 4:2
+	//       ^^^^^ reference read -> 4:9
+	count++
+	// <- reference read write -> 4:9
+	count--
+	// <- reference read write
+	println(total)
+	//      ^^^^^ reference read -> 4:2
+}
@@ -0,0 +1,22 @@
+package enclosing
+
+type Point struct {
+	X int
+	Y int
+}
+
+func Distance(a Point, b Point) int {
+	dx := a.X - b.X
+	dy := a.Y - b.Y
+	square := func(v int) int {
+		return v * v
+	}
+
+	return square(dx) + square(dy)
+}
+
+func (p *Point) Shift(by int) {
+	for i := range []int{by} {
+		p.X += i
+	}
+}
@@ -0,0 +1,21 @@
+package main
+
+func Shadowed(value int) int {
+	//           ^^^^^ definition
+	if value > 0 {
+	// ^^^^^ reference -> 3:15
+		inner := value - 1
+		// <- definition
+		//       ^^^^^ reference -> 3:15
+		return inner
+		//     ^^^^^ reference -> 7:3
+	}
+
+	return value
+	//     ^^^^^ reference -> 3:15
+}
+
+func main() {
+//   ^^^^ definition scip-ctags main().
+	println(Shadowed(1))
+}
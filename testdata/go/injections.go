@@ -0,0 +1,20 @@
+package main
+
+// language=rust
+const snippet = `
+struct Point {
+    x: i32,
+}
+
+fn origin() -> Point {
+    let p = Point { x: 0 };
+    p
+}
+`
+
+func main() {
+	count := 1
+	// language=unknown
+	other := `count`
+	println(count, snippet, other)
+}
@@ -0,0 +1,14 @@
+fn accumulate(values: &[i32]) -> i32 {
+    let mut total = 0;
+    for value in values {
+        total += value;
+        // <- reference read write -> 2:13
+        //       ^^^^^ reference read -> 3:9
+    }
+
+    let mut last = 0;
+    last = total;
+    // <- reference write -> 9:13
+    //     ^^^^^ reference read -> 2:13
+    last
+}
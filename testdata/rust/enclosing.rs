@@ -0,0 +1,18 @@
+pub trait Shape {
+    fn area(&self) -> f64;
+}
+
+pub struct Square {
+    side: f64,
+}
+
+impl Shape for Square {
+    fn area(&self) -> f64 {
+        let side = self.side;
+        side * side
+    }
+}
+
+fn total(shapes: &[Square]) -> f64 {
+    shapes.iter().map(|s| s.area()).sum()
+}
@@ -0,0 +1,16 @@
+struct Point {
+//     ^^^^^ definition scip-ctags Point#
+    x: i32,
+}
+
+impl Point {
+    fn origin() -> Self {
+    // ^^^^^^ definition scip-ctags Point#origin().
+        Point { x: 0 }
+    }
+}
+
+fn distance() -> i32 {
+// ^^^^^^^^ definition scip-ctags distance().
+    Point::origin().x
+}
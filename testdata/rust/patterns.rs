@@ -0,0 +1,26 @@
+fn first(values: Option<Vec<i32>>) -> i32 {
+    if let Some(values) = values {
+        //                ^^^^^^ reference -> 1:10
+        return values[0];
+        //     ^^^^^^ reference -> 2:17
+    }
+
+    let (a, mut b) = (1, 2);
+    b += a;
+    // <- reference read write -> 8:17
+    //   ^ reference -> 8:10
+
+    while let Some(next) = Some(b) {
+        //                      ^ reference -> 8:17
+        b = next;
+        //  ^^^^ reference -> 13:20
+    }
+
+    match Some(b) {
+        Some(found @ 1..=9) => found,
+        //                     ^^^^^ reference -> 20:14
+        Some(other) => other,
+        //             ^^^^^ reference -> 22:14
+        None => 0,
+    }
+}
@@ -0,0 +1,38 @@
+  package main
+//^^^^^^^ syntax Keyword
+//        ^^^^ definition scip-ctags main/ IdentifierNamespace
+  
+  func main() {
+//^^^^ syntax Keyword
+//     ^^^^ definition scip-ctags main(). IdentifierFunctionDefinition
+  	total, count := 0, 0
+//	^^^^^ definition local 2
+//	       ^^^^^ definition local 3
+//	                ^ syntax NumericLiteral
+//	                   ^ syntax NumericLiteral
+  	total = 2
+//	^^^^^ reference write local 2
+//	        ^ syntax NumericLiteral
+  	// <- reference write -> 4:2
+//	^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+  	total += count
+//	^^^^^ reference write read local 2
+//	         ^^^^^ reference read local 3
+  	// <- reference read write -> 4:2
+//	^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+  	//       ^^^^^ reference read -> 4:9
+//	^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+  	count++
+//	^^^^^ reference write read local 3
+  	// <- reference read write -> 4:9
+//	^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+  	count--
+//	^^^^^ reference write read local 3
+  	// <- reference read write
+//	^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+  	println(total)
+//	^^^^^^^ syntax IdentifierFunction
+//	        ^^^^^ reference read local 2
+  	//      ^^^^^ reference read -> 4:2
+//	^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+  }
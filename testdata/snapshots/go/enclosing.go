@@ -0,0 +1,68 @@
+  package enclosing
+//^^^^^^^ syntax Keyword
+//        ^^^^^^^^^ definition scip-ctags enclosing/ IdentifierNamespace
+  
+  type Point struct {
+//^^^^ syntax Keyword
+//     ^^^^^ definition scip-ctags Point# IdentifierType
+//           ^^^^^^ syntax Keyword
+  	X int
+//	  ^^^ syntax IdentifierBuiltinType
+  	Y int
+//	  ^^^ syntax IdentifierBuiltinType
+  }
+  
+  func Distance(a Point, b Point) int {
+//^^^^ syntax Keyword
+//     ^^^^^^^^ definition scip-ctags Distance(). IdentifierFunctionDefinition
+//              ^ definition local 4 IdentifierParameter
+//                ^^^^^ reference scip-ctags Point# IdentifierType
+//                       ^ definition local 5 IdentifierParameter
+//                         ^^^^^ reference scip-ctags Point# IdentifierType
+//                                ^^^ syntax IdentifierBuiltinType
+  	dx := a.X - b.X
+//	^^ definition local 6
+//	      ^ reference local 4
+//	            ^ reference local 5
+  	dy := a.Y - b.Y
+//	^^ definition local 7
+//	      ^ reference local 4
+//	            ^ reference local 5
+  	square := func(v int) int {
+//	^^^^^^ definition local 8
+//	          ^^^^ syntax Keyword
+//	               ^ definition local 9 IdentifierParameter
+//	                 ^^^ syntax IdentifierBuiltinType
+//	                      ^^^ syntax IdentifierBuiltinType
+  		return v * v
+//		^^^^^^ syntax Keyword
+//		       ^ reference read local 9
+//		           ^ reference read local 9
+  	}
+  
+  	return square(dx) + square(dy)
+//	^^^^^^ syntax Keyword
+//	       ^^^^^^ reference local 8 IdentifierFunction
+//	              ^^ reference read local 6
+//	                    ^^^^^^ reference local 8 IdentifierFunction
+//	                           ^^ reference read local 7
+  }
+  
+  func (p *Point) Shift(by int) {
+//^^^^ syntax Keyword
+//      ^ definition local 10 IdentifierParameter
+//         ^^^^^ reference scip-ctags Point# IdentifierType
+//                ^^^^^ definition scip-ctags Point#Shift(). IdentifierFunctionDefinition
+//                      ^^ definition local 12 IdentifierParameter
+//                         ^^^ syntax IdentifierBuiltinType
+  	for i := range []int{by} {
+//	^^^ syntax Keyword
+//	    ^ definition local 13
+//	         ^^^^^ syntax Keyword
+//	                 ^^^ syntax IdentifierBuiltinType
+//	                     ^^ reference local 12
+  		p.X += i
+//		^ reference local 10
+//		       ^ reference read local 13
+  	}
+  }
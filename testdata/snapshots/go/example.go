@@ -0,0 +1,46 @@
+  package example
+//^^^^^^^ syntax Keyword
+//        ^^^^^^^ definition scip-ctags example/ IdentifierNamespace
+  
+  import (
+//^^^^^^ syntax Keyword
+  	f "fmt"
+//	^ definition local 2 IdentifierNamespace
+//	  ^^^^^ syntax StringLiteral
+  )
+  
+  func Something() {
+//^^^^ syntax Keyword
+//     ^^^^^^^^^ definition scip-ctags Something(). IdentifierFunctionDefinition
+  	x := true
+//	^ definition local 6
+//	     ^^^^ syntax BooleanLiteral
+  	f.Println(x)
+//	^ reference local 2
+//	  ^^^^^^^ syntax IdentifierFunction
+//	          ^ reference read local 6
+  }
+  
+  func Another() float64 { return 5 / 3 }
+//^^^^ syntax Keyword
+//     ^^^^^^^ definition scip-ctags Another(). IdentifierFunctionDefinition
+//               ^^^^^^^ syntax IdentifierBuiltinType
+//                         ^^^^^^ syntax Keyword
+//                                ^ syntax NumericLiteral
+//                                    ^ syntax NumericLiteral
+  
+  type MyThing struct{}
+//^^^^ syntax Keyword
+//     ^^^^^^^ definition scip-ctags MyThing# IdentifierType
+//             ^^^^^^ syntax Keyword
+  
+  func (m *MyThing) DoSomething()    {}
+//^^^^ syntax Keyword
+//      ^ definition local 7 IdentifierParameter
+//         ^^^^^^^ reference scip-ctags MyThing# IdentifierType
+//                  ^^^^^^^^^^^ definition scip-ctags MyThing#DoSomething(). IdentifierFunctionDefinition
+  func (m MyThing) DoSomethingElse() {}
+//^^^^ syntax Keyword
+//      ^ definition local 9 IdentifierParameter
+//        ^^^^^^^ reference scip-ctags MyThing# IdentifierType
+//                 ^^^^^^^^^^^^^^^ definition scip-ctags MyThing#DoSomethingElse(). IdentifierFunctionDefinition
@@ -0,0 +1,50 @@
+  package main
+//^^^^^^^ syntax Keyword
+//        ^^^^ definition scip-ctags main/ IdentifierNamespace
+  
+  func Shadowed(value int) int {
+//^^^^ syntax Keyword
+//     ^^^^^^^^ definition scip-ctags Shadowed(). IdentifierFunctionDefinition
+//              ^^^^^ definition local 3 IdentifierParameter
+//                    ^^^ syntax IdentifierBuiltinType
+//                         ^^^ syntax IdentifierBuiltinType
+  	//           ^^^^^ definition
+//	^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+  	if value > 0 {
+//	^^ syntax Keyword
+//	   ^^^^^ reference read local 3
+//	           ^ syntax NumericLiteral
+  	// ^^^^^ reference -> 3:15
+//	^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+  		inner := value - 1
+//		^^^^^ definition local 4
+//		         ^^^^^ reference read local 3
+//		                 ^ syntax NumericLiteral
+  		// <- definition
+//		^^^^^^^^^^^^^^^^ syntax Comment
+  		//       ^^^^^ reference -> 3:15
+//		^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+  		return inner
+//		^^^^^^ syntax Keyword
+//		       ^^^^^ reference read local 4
+  		//     ^^^^^ reference -> 7:3
+//		^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+  	}
+  
+  	return value
+//	^^^^^^ syntax Keyword
+//	       ^^^^^ reference read local 3
+  	//     ^^^^^ reference -> 3:15
+//	^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+  }
+  
+  func main() {
+//^^^^ syntax Keyword
+//     ^^^^ definition scip-ctags main(). IdentifierFunctionDefinition
+  //   ^^^^ definition scip-ctags main().
+//^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+  	println(Shadowed(1))
+//	^^^^^^^ syntax IdentifierFunction
+//	        ^^^^^^^^ reference scip-ctags Shadowed(). IdentifierFunction
+//	                 ^ syntax NumericLiteral
+  }
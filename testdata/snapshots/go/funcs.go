@@ -0,0 +1,60 @@
+  package example
+//^^^^^^^ syntax Keyword
+//        ^^^^^^^ definition scip-ctags example/ IdentifierNamespace
+  
+  import (
+//^^^^^^ syntax Keyword
+  	f "fmt"
+//	^ definition local 2 IdentifierNamespace
+//	  ^^^^^ syntax StringLiteral
+  	"github.com/sourcegraph/"
+//	^^^^^^^^^^^^^^^^^^^^^^^^^ syntax StringLiteral
+  )
+  
+  func Something() {
+//^^^^ syntax Keyword
+//     ^^^^^^^^^ definition scip-ctags Something(). IdentifierFunctionDefinition
+  	y := ", world"
+//	^ definition local 5
+//	     ^^^^^^^^^ syntax StringLiteral
+  	f.Println("hello", y)
+//	^ reference local 2
+//	  ^^^^^^^ syntax IdentifierFunction
+//	          ^^^^^^^ syntax StringLiteral
+//	                   ^ reference read local 5
+  }
+  
+  func Another() {
+//^^^^ syntax Keyword
+//     ^^^^^^^ definition scip-ctags Another(). IdentifierFunctionDefinition
+  	Something()
+//	^^^^^^^^^ reference scip-ctags Something(). IdentifierFunction
+  	if true {
+//	^^ syntax Keyword
+//	   ^^^^ syntax BooleanLiteral
+  		x := true
+//		^ definition local 6
+//		     ^^^^ syntax BooleanLiteral
+  	}
+  	if true {
+//	^^ syntax Keyword
+//	   ^^^^ syntax BooleanLiteral
+  		x := true
+//		^ definition local 7
+//		     ^^^^ syntax BooleanLiteral
+  		if true {
+//		^^ syntax Keyword
+//		   ^^^^ syntax BooleanLiteral
+  			x := true
+//			^ definition local 8
+//			     ^^^^ syntax BooleanLiteral
+  		}
+  	}
+  	if true {
+//	^^ syntax Keyword
+//	   ^^^^ syntax BooleanLiteral
+  		x := true
+//		^ definition local 9
+//		     ^^^^ syntax BooleanLiteral
+  	}
+  }
@@ -0,0 +1,48 @@
+  package main
+//^^^^^^^ syntax Keyword
+//        ^^^^ definition scip-ctags main/ IdentifierNamespace
+  
+  // language=rust
+//^^^^^^^^^^^^^^^^ syntax Comment
+  const snippet = `
+//^^^^^ syntax Keyword
+//      ^^^^^^^ definition local 2
+//                ^ syntax 3:16..12:1 StringLiteral
+  struct Point {
+//^^^^^^ syntax Keyword
+//       ^^^^^ definition scip-ctags Point# IdentifierType
+      x: i32,
+//       ^^^ syntax IdentifierBuiltinType
+  }
+  
+  fn origin() -> Point {
+//^^ syntax Keyword
+//   ^^^^^^ definition scip-ctags origin(). IdentifierFunctionDefinition
+//               ^^^^^ syntax IdentifierType
+      let p = Point { x: 0 };
+//    ^^^ syntax Keyword
+//        ^ definition local 5
+//            ^^^^^ syntax IdentifierType
+//                       ^ syntax NumericLiteral
+      p
+//    ^ reference read local 5
+  }
+  `
+  
+  func main() {
+//^^^^ syntax Keyword
+//     ^^^^ definition scip-ctags main(). IdentifierFunctionDefinition
+  	count := 1
+//	^^^^^ definition local 3
+//	         ^ syntax NumericLiteral
+  	// language=unknown
+//	^^^^^^^^^^^^^^^^^^^ syntax Comment
+  	other := `count`
+//	^^^^^ definition local 4
+//	         ^^^^^^^ syntax StringLiteral
+  	println(count, snippet, other)
+//	^^^^^^^ syntax IdentifierFunction
+//	        ^^^^^ reference read local 3
+//	               ^^^^^^^ reference read local 2
+//	                        ^^^^^ reference read local 4
+  }
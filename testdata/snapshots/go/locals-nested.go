@@ -0,0 +1,54 @@
+  package main
+//^^^^^^^ syntax Keyword
+//        ^^^^ definition scip-ctags main/ IdentifierNamespace
+  
+  func main() {
+//^^^^ syntax Keyword
+//     ^^^^ definition scip-ctags main(). IdentifierFunctionDefinition
+  	local := 5
+//	^^^^^ definition local 2
+//	         ^ syntax NumericLiteral
+  	something := func(unrelated int) int {
+//	^^^^^^^^^ definition local 3
+//	             ^^^^ syntax Keyword
+//	                  ^^^^^^^^^ definition local 4 IdentifierParameter
+//	                            ^^^ syntax IdentifierBuiltinType
+//	                                 ^^^ syntax IdentifierBuiltinType
+  		superNested := func(deeplyNested int) int {
+//		^^^^^^^^^^^ definition local 5
+//		               ^^^^ syntax Keyword
+//		                    ^^^^^^^^^^^^ definition local 7 IdentifierParameter
+//		                                 ^^^ syntax IdentifierBuiltinType
+//		                                      ^^^ syntax IdentifierBuiltinType
+  			return local + unrelated + deeplyNested
+//			^^^^^^ syntax Keyword
+//			       ^^^^^ reference read local 2
+//			               ^^^^^^^^^ reference read local 4
+//			                           ^^^^^^^^^^^^ reference read local 7
+  		}
+  
+  		overwriteName := func(local int) int {
+//		^^^^^^^^^^^^^ definition local 6
+//		                 ^^^^ syntax Keyword
+//		                      ^^^^^ definition local 8 IdentifierParameter
+//		                            ^^^ syntax IdentifierBuiltinType
+//		                                 ^^^ syntax IdentifierBuiltinType
+  			return local + unrelated
+//			^^^^^^ syntax Keyword
+//			       ^^^^^ reference read local 8
+//			               ^^^^^^^^^ reference read local 4
+  		}
+  
+  		return superNested(1) + overwriteName(1)
+//		^^^^^^ syntax Keyword
+//		       ^^^^^^^^^^^ reference local 5 IdentifierFunction
+//		                   ^ syntax NumericLiteral
+//		                        ^^^^^^^^^^^^^ reference local 6 IdentifierFunction
+//		                                      ^ syntax NumericLiteral
+  	}
+  
+  	println(local, something)
+//	^^^^^^^ syntax IdentifierFunction
+//	        ^^^^^ reference read local 2
+//	               ^^^^^^^^^ reference read local 3
+  }
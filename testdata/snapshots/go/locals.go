@@ -0,0 +1,37 @@
+  package main
+//^^^^^^^ syntax Keyword
+//        ^^^^ definition scip-ctags main/ IdentifierNamespace
+  
+  func main() {
+//^^^^ syntax Keyword
+//     ^^^^ definition scip-ctags main(). IdentifierFunctionDefinition
+  	local := true
+//	^^^^^ definition local 3
+//	         ^^^^ syntax BooleanLiteral
+  	something := func(local int) int {
+//	^^^^^^^^^ definition local 4
+//	             ^^^^ syntax Keyword
+//	                  ^^^^^ definition local 5 IdentifierParameter
+//	                        ^^^ syntax IdentifierBuiltinType
+//	                             ^^^ syntax IdentifierBuiltinType
+  		return local
+//		^^^^^^ syntax Keyword
+//		       ^^^^^ reference read local 5
+  	}
+  
+  	println(local, something)
+//	^^^^^^^ syntax IdentifierFunction
+//	        ^^^^^ reference read local 3
+//	               ^^^^^^^^^ reference read local 4
+  }
+  
+  func Another(local int) int {
+//^^^^ syntax Keyword
+//     ^^^^^^^ definition scip-ctags Another(). IdentifierFunctionDefinition
+//             ^^^^^ definition local 6 IdentifierParameter
+//                   ^^^ syntax IdentifierBuiltinType
+//                        ^^^ syntax IdentifierBuiltinType
+  	return local
+//	^^^^^^ syntax Keyword
+//	       ^^^^^ reference read local 6
+  }
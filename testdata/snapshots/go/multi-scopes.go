@@ -0,0 +1,153 @@
+  package main
+//^^^^^^^ syntax Keyword
+//        ^^^^ definition scip-ctags main/ IdentifierNamespace
+  
+  func main() {
+//^^^^ syntax Keyword
+//     ^^^^ definition scip-ctags main(). IdentifierFunctionDefinition
+  	local := 5
+//	^^^^^ definition local 6
+//	         ^ syntax NumericLiteral
+  	something := func(unrelated int) int {
+//	^^^^^^^^^ definition local 7
+//	             ^^^^ syntax Keyword
+//	                  ^^^^^^^^^ definition local 8 IdentifierParameter
+//	                            ^^^ syntax IdentifierBuiltinType
+//	                                 ^^^ syntax IdentifierBuiltinType
+  		superNested := func(deeplyNested int) int {
+//		^^^^^^^^^^^ definition local 9
+//		               ^^^^ syntax Keyword
+//		                    ^^^^^^^^^^^^ definition local 11 IdentifierParameter
+//		                                 ^^^ syntax IdentifierBuiltinType
+//		                                      ^^^ syntax IdentifierBuiltinType
+  			return local + unrelated + deeplyNested
+//			^^^^^^ syntax Keyword
+//			       ^^^^^ reference read local 6
+//			               ^^^^^^^^^ reference read local 8
+//			                           ^^^^^^^^^^^^ reference read local 11
+  		}
+  
+  		overwriteName := func(local int) int {
+//		^^^^^^^^^^^^^ definition local 10
+//		                 ^^^^ syntax Keyword
+//		                      ^^^^^ definition local 12 IdentifierParameter
+//		                            ^^^ syntax IdentifierBuiltinType
+//		                                 ^^^ syntax IdentifierBuiltinType
+  			return local + unrelated
+//			^^^^^^ syntax Keyword
+//			       ^^^^^ reference read local 12
+//			               ^^^^^^^^^ reference read local 8
+  		}
+  
+  		return superNested(1) + overwriteName(1)
+//		^^^^^^ syntax Keyword
+//		       ^^^^^^^^^^^ reference local 9 IdentifierFunction
+//		                   ^ syntax NumericLiteral
+//		                        ^^^^^^^^^^^^^ reference local 10 IdentifierFunction
+//		                                      ^ syntax NumericLiteral
+  	}
+  
+  	println(local, something)
+//	^^^^^^^ syntax IdentifierFunction
+//	        ^^^^^ reference read local 6
+//	               ^^^^^^^^^ reference read local 7
+  }
+  
+  
+  func Another() {
+//^^^^ syntax Keyword
+//     ^^^^^^^ definition scip-ctags Another(). IdentifierFunctionDefinition
+  	x := true
+//	^ definition local 13
+//	     ^^^^ syntax BooleanLiteral
+  }
+  
+  func Something() {
+//^^^^ syntax Keyword
+//     ^^^^^^^^^ definition scip-ctags Something(). IdentifierFunctionDefinition
+  	x := true
+//	^ definition local 14
+//	     ^^^^ syntax BooleanLiteral
+  	Another(x)
+//	^^^^^^^ reference scip-ctags Another(). IdentifierFunction
+//	        ^ reference read local 14
+  	Another(x)
+//	^^^^^^^ reference scip-ctags Another(). IdentifierFunction
+//	        ^ reference read local 14
+  	Another(x)
+//	^^^^^^^ reference scip-ctags Another(). IdentifierFunction
+//	        ^ reference read local 14
+  	Another(x)
+//	^^^^^^^ reference scip-ctags Another(). IdentifierFunction
+//	        ^ reference read local 14
+  	Another(x)
+//	^^^^^^^ reference scip-ctags Another(). IdentifierFunction
+//	        ^ reference read local 14
+  	Another(x)
+//	^^^^^^^ reference scip-ctags Another(). IdentifierFunction
+//	        ^ reference read local 14
+  	Another(x)
+//	^^^^^^^ reference scip-ctags Another(). IdentifierFunction
+//	        ^ reference read local 14
+  	Another(x)
+//	^^^^^^^ reference scip-ctags Another(). IdentifierFunction
+//	        ^ reference read local 14
+  	Another(x)
+//	^^^^^^^ reference scip-ctags Another(). IdentifierFunction
+//	        ^ reference read local 14
+  	Another(x)
+//	^^^^^^^ reference scip-ctags Another(). IdentifierFunction
+//	        ^ reference read local 14
+  	Another(x)
+//	^^^^^^^ reference scip-ctags Another(). IdentifierFunction
+//	        ^ reference read local 14
+  	Another(x)
+//	^^^^^^^ reference scip-ctags Another(). IdentifierFunction
+//	        ^ reference read local 14
+  	Another(x)
+//	^^^^^^^ reference scip-ctags Another(). IdentifierFunction
+//	        ^ reference read local 14
+  	Another(x)
+//	^^^^^^^ reference scip-ctags Another(). IdentifierFunction
+//	        ^ reference read local 14
+  	Another(x)
+//	^^^^^^^ reference scip-ctags Another(). IdentifierFunction
+//	        ^ reference read local 14
+  	Another(x)
+//	^^^^^^^ reference scip-ctags Another(). IdentifierFunction
+//	        ^ reference read local 14
+  }
+  
+  func Short() {}
+//^^^^ syntax Keyword
+//     ^^^^^ definition scip-ctags Short(). IdentifierFunctionDefinition
+  
+  func Final() {
+//^^^^ syntax Keyword
+//     ^^^^^ definition scip-ctags Final(). IdentifierFunctionDefinition
+  	x := true
+//	^ definition local 15
+//	     ^^^^ syntax BooleanLiteral
+  	if x {
+//	^^ syntax Keyword
+//	   ^ reference local 15
+  		x := false
+//		^ definition local 16
+//		     ^^^^^ syntax BooleanLiteral
+  		if x {
+//		^^ syntax Keyword
+//		   ^ reference local 16
+  			x := true
+//			^ definition local 17
+//			     ^^^^ syntax BooleanLiteral
+  			if x {
+//			^^ syntax Keyword
+//			   ^ reference local 17
+  				x := true
+//				^ definition local 18
+//				     ^^^^ syntax BooleanLiteral
+  			}
+  		}
+  	}
+  
+  }
@@ -0,0 +1,40 @@
+  fn accumulate(values: &[i32]) -> i32 {
+//^^ syntax Keyword
+//   ^^^^^^^^^^ definition scip-ctags accumulate(). IdentifierFunctionDefinition
+//              ^^^^^^ definition local 1 IdentifierParameter
+//                        ^^^ syntax IdentifierBuiltinType
+//                                 ^^^ syntax IdentifierBuiltinType
+      let mut total = 0;
+//    ^^^ syntax Keyword
+//        ^^^ syntax Keyword
+//            ^^^^^ definition local 2
+//                    ^ syntax NumericLiteral
+      for value in values {
+//    ^^^ syntax Keyword
+//        ^^^^^ definition local 4
+//              ^^ syntax Keyword
+//                 ^^^^^^ reference local 1
+          total += value;
+//        ^^^^^ reference write read local 2
+//                 ^^^^^ reference read local 4
+          // <- reference read write -> 2:13
+//        ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+          //       ^^^^^ reference read -> 3:9
+//        ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+      }
+  
+      let mut last = 0;
+//    ^^^ syntax Keyword
+//        ^^^ syntax Keyword
+//            ^^^^ definition local 3
+//                   ^ syntax NumericLiteral
+      last = total;
+//    ^^^^ reference write local 3
+//           ^^^^^ reference read local 2
+      // <- reference write -> 9:13
+//    ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+      //     ^^^^^ reference read -> 2:13
+//    ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+      last
+//    ^^^^ reference read local 3
+  }
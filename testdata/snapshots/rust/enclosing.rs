@@ -0,0 +1,54 @@
+  pub trait Shape {
+//^^^ syntax Keyword
+//    ^^^^^ syntax Keyword
+//          ^^^^^ definition scip-ctags Shape# IdentifierType
+      fn area(&self) -> f64;
+//    ^^ syntax Keyword
+//       ^^^^ definition scip-ctags Shape#area(). IdentifierFunctionDefinition
+//             ^^^^ syntax IdentifierBuiltin
+//                      ^^^ syntax IdentifierBuiltinType
+  }
+  
+  pub struct Square {
+//^^^ syntax Keyword
+//    ^^^^^^ syntax Keyword
+//           ^^^^^^ definition scip-ctags Square# IdentifierType
+      side: f64,
+//          ^^^ syntax IdentifierBuiltinType
+  }
+  
+  impl Shape for Square {
+//^^^^ syntax Keyword
+//     ^^^^^ syntax IdentifierType
+//           ^^^ syntax Keyword
+//               ^^^^^^ reference scip-ctags Square# IdentifierType
+      fn area(&self) -> f64 {
+//    ^^ syntax Keyword
+//       ^^^^ definition scip-ctags Square#area(). IdentifierFunctionDefinition
+//             ^^^^ syntax IdentifierBuiltin
+//                      ^^^ syntax IdentifierBuiltinType
+          let side = self.side;
+//        ^^^ syntax Keyword
+//            ^^^^ definition local 1
+//                   ^^^^ syntax IdentifierBuiltin
+          side * side
+//        ^^^^ reference read local 1
+//               ^^^^ reference read local 1
+      }
+  }
+  
+  fn total(shapes: &[Square]) -> f64 {
+//^^ syntax Keyword
+//   ^^^^^ definition scip-ctags total(). IdentifierFunctionDefinition
+//         ^^^^^^ definition local 2 IdentifierParameter
+//                   ^^^^^^ syntax IdentifierType
+//                               ^^^ syntax IdentifierBuiltinType
+      shapes.iter().map(|s| s.area()).sum()
+//    ^^^^^^ reference local 2
+//           ^^^^ syntax IdentifierFunction
+//                  ^^^ syntax IdentifierFunction
+//                       ^ definition local 3
+//                          ^ reference local 3
+//                            ^^^^ syntax IdentifierFunction
+//                                    ^^^ syntax IdentifierFunction
+  }
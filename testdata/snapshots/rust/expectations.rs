@@ -0,0 +1,33 @@
+  struct Point {
+//^^^^^^ syntax Keyword
+//       ^^^^^ definition scip-ctags Point# IdentifierType
+  //     ^^^^^ definition scip-ctags Point#
+//^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+      x: i32,
+//       ^^^ syntax IdentifierBuiltinType
+  }
+  
+  impl Point {
+//^^^^ syntax Keyword
+//     ^^^^^ reference scip-ctags Point# IdentifierType
+      fn origin() -> Self {
+//    ^^ syntax Keyword
+//       ^^^^^^ definition scip-ctags Point#origin(). IdentifierFunctionDefinition
+//                   ^^^^ syntax IdentifierType
+      // ^^^^^^ definition scip-ctags Point#origin().
+//    ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+          Point { x: 0 }
+//        ^^^^^ syntax IdentifierType
+//                   ^ syntax NumericLiteral
+      }
+  }
+  
+  fn distance() -> i32 {
+//^^ syntax Keyword
+//   ^^^^^^^^ definition scip-ctags distance(). IdentifierFunctionDefinition
+//                 ^^^ syntax IdentifierBuiltinType
+  // ^^^^^^^^ definition scip-ctags distance().
+//^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+      Point::origin().x
+//           ^^^^^^ syntax IdentifierFunction
+  }
@@ -0,0 +1,74 @@
+  fn first(values: Option<Vec<i32>>) -> i32 {
+//^^ syntax Keyword
+//   ^^^^^ definition scip-ctags first(). IdentifierFunctionDefinition
+//         ^^^^^^ definition local 1 IdentifierParameter
+//                 ^^^^^^ syntax IdentifierType
+//                        ^^^ syntax IdentifierType
+//                            ^^^ syntax IdentifierBuiltinType
+//                                      ^^^ syntax IdentifierBuiltinType
+      if let Some(values) = values {
+//    ^^ syntax Keyword
+//       ^^^ syntax Keyword
+//                ^^^^^^ reference local 1
+//                ^^^^^^ definition local 4
+//                          ^^^^^^ reference local 1
+          //                ^^^^^^ reference -> 1:10
+//        ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+          return values[0];
+//        ^^^^^^ syntax Keyword
+//               ^^^^^^ reference local 4
+//                      ^ syntax NumericLiteral
+          //     ^^^^^^ reference -> 2:17
+//        ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+      }
+  
+      let (a, mut b) = (1, 2);
+//    ^^^ syntax Keyword
+//         ^ definition local 2
+//            ^^^ syntax Keyword
+//                ^ definition local 3
+//                      ^ syntax NumericLiteral
+//                         ^ syntax NumericLiteral
+      b += a;
+//    ^ reference write read local 3
+//         ^ reference read local 2
+      // <- reference read write -> 8:17
+//    ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+      //   ^ reference -> 8:10
+//    ^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+  
+      while let Some(next) = Some(b) {
+//    ^^^^^ syntax Keyword
+//          ^^^ syntax Keyword
+//                   ^^^^ definition local 5
+//                           ^^^^ syntax IdentifierFunction
+//                                ^ reference read local 3
+          //                      ^ reference -> 8:17
+//        ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+          b = next;
+//        ^ reference write local 3
+//            ^^^^ reference read local 5
+          //  ^^^^ reference -> 13:20
+//        ^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+      }
+  
+      match Some(b) {
+//    ^^^^^ syntax Keyword
+//          ^^^^ syntax IdentifierFunction
+//               ^ reference read local 3
+          Some(found @ 1..=9) => found,
+//             ^^^^^ definition local 6
+//                     ^ syntax NumericLiteral
+//                         ^ syntax NumericLiteral
+//                               ^^^^^ reference local 6
+          //                     ^^^^^ reference -> 20:14
+//        ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+          Some(other) => other,
+//             ^^^^^ definition local 7
+//                       ^^^^^ reference local 7
+          //             ^^^^^ reference -> 22:14
+//        ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+          None => 0,
+//                ^ syntax NumericLiteral
+      }
+  }
@@ -0,0 +1,47 @@
+  pub trait Tag {
+//^^^ syntax Keyword
+//    ^^^^^ syntax Keyword
+//          ^^^ definition scip-ctags Tag# IdentifierType
+      // This is a pretty big thing
+//    ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+      // And some more things here
+//    ^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+      fn name(&self) -> &str;
+//    ^^ syntax Keyword
+//       ^^^^ definition scip-ctags Tag#name(). IdentifierFunctionDefinition
+//             ^^^^ syntax IdentifierBuiltin
+//                       ^^^ syntax IdentifierBuiltinType
+  }
+  
+  mod namespace {
+//^^^ syntax Keyword
+//    ^^^^^^^^^ definition scip-ctags namespace/ IdentifierNamespace
+      mod nested {
+//    ^^^ syntax Keyword
+//        ^^^^^^ definition scip-ctags namespace/nested/ IdentifierNamespace
+          mod even_more_nested {
+//        ^^^ syntax Keyword
+//            ^^^^^^^^^^^^^^^^ definition scip-ctags namespace/nested/even_more_nested/ IdentifierNamespace
+              pub struct CoolStruct {}
+//            ^^^ syntax Keyword
+//                ^^^^^^ syntax Keyword
+//                       ^^^^^^^^^^ definition scip-ctags namespace/nested/even_more_nested/CoolStruct# IdentifierType
+  
+              impl Tag for CoolStruct {
+//            ^^^^ syntax Keyword
+//                 ^^^ syntax IdentifierType
+//                     ^^^ syntax Keyword
+//                         ^^^^^^^^^^ reference scip-ctags namespace/nested/even_more_nested/CoolStruct# IdentifierType
+                  fn name(&self) -> &str {}
+//                ^^ syntax Keyword
+//                   ^^^^ definition scip-ctags namespace/nested/even_more_nested/CoolStruct#name(). IdentifierFunctionDefinition
+//                         ^^^^ syntax IdentifierBuiltin
+//                                   ^^^ syntax IdentifierBuiltinType
+              }
+          }
+      }
+  }
+  
+  fn something() {}
+//^^ syntax Keyword
+//   ^^^^^^^^^ definition scip-ctags something(). IdentifierFunctionDefinition
@@ -0,0 +1,29 @@
+  mod foo {
+//^^^ syntax Keyword
+//    ^^^ definition scip-ctags foo/ IdentifierNamespace
+      mod namespace {
+//    ^^^ syntax Keyword
+//        ^^^^^^^^^ definition scip-ctags foo/namespace/ IdentifierNamespace
+          pub trait Tag {
+//        ^^^ syntax Keyword
+//            ^^^^^ syntax Keyword
+//                  ^^^ definition scip-ctags foo/namespace/Tag# IdentifierType
+              fn name(&self) -> &str;
+//            ^^ syntax Keyword
+//               ^^^^ definition scip-ctags foo/namespace/Tag#name(). IdentifierFunctionDefinition
+//                     ^^^^ syntax IdentifierBuiltin
+//                               ^^^ syntax IdentifierBuiltinType
+          }
+      }
+  }
+  
+  pub trait Other {
+//^^^ syntax Keyword
+//    ^^^^^ syntax Keyword
+//          ^^^^^ definition scip-ctags Other# IdentifierType
+      fn name(&self) -> &str;
+//    ^^ syntax Keyword
+//       ^^^^ definition scip-ctags Other#name(). IdentifierFunctionDefinition
+//             ^^^^ syntax IdentifierBuiltin
+//                       ^^^ syntax IdentifierBuiltinType
+  }
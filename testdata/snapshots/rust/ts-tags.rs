@@ -0,0 +1,1928 @@
+  use memchr::memchr;
+//^^^ syntax Keyword
+  use regex::Regex;
+//^^^ syntax Keyword
+  use std::collections::HashMap;
+//^^^ syntax Keyword
+  use std::ffi::{CStr, CString};
+//^^^ syntax Keyword
+  use std::ops::Range;
+//^^^ syntax Keyword
+  use std::os::raw::c_char;
+//^^^ syntax Keyword
+  use std::sync::atomic::{AtomicUsize, Ordering};
+//^^^ syntax Keyword
+  use std::{char, mem, str};
+//^^^ syntax Keyword
+  use thiserror::Error;
+//^^^ syntax Keyword
+  use tree_sitter::{
+//^^^ syntax Keyword
+      Language, LossyUtf8, Parser, Point, Query, QueryCursor, QueryError, QueryPredicateArg, Tree,
+  };
+  
+  const MAX_LINE_LEN: usize = 180;
+//^^^^^ syntax Keyword
+//                    ^^^^^ syntax IdentifierBuiltinType
+//                            ^^^ syntax NumericLiteral
+  const CANCELLATION_CHECK_INTERVAL: usize = 100;
+//^^^^^ syntax Keyword
+//                                   ^^^^^ syntax IdentifierBuiltinType
+//                                           ^^^ syntax NumericLiteral
+  
+  /// Contains the data needed to compute tags for code written in a
+//^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+  /// particular language.
+//^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+  #[derive(Debug)]
+//^^^^^^^^^^^^^^^^ syntax IdentifierAttribute
+  pub struct TagsConfiguration {
+//^^^ syntax Keyword
+//    ^^^^^^ syntax Keyword
+//           ^^^^^^^^^^^^^^^^^ definition scip-ctags TagsConfiguration# IdentifierType
+      pub language: Language,
+//    ^^^ syntax Keyword
+//                  ^^^^^^^^ syntax IdentifierType
+      pub query: Query,
+//    ^^^ syntax Keyword
+//               ^^^^^ syntax IdentifierType
+      syntax_type_names: Vec<Box<[u8]>>,
+//                       ^^^ syntax IdentifierType
+//                           ^^^ syntax IdentifierType
+//                                ^^ syntax IdentifierBuiltinType
+      c_syntax_type_names: Vec<*const u8>,
+//                         ^^^ syntax IdentifierType
+//                              ^^^^^ syntax Keyword
+//                                    ^^ syntax IdentifierBuiltinType
+      capture_map: HashMap<u32, NamedCapture>,
+//                 ^^^^^^^ syntax IdentifierType
+//                         ^^^ syntax IdentifierBuiltinType
+//                              ^^^^^^^^^^^^ syntax IdentifierType
+      doc_capture_index: Option<u32>,
+//                       ^^^^^^ syntax IdentifierType
+//                              ^^^ syntax IdentifierBuiltinType
+      name_capture_index: Option<u32>,
+//                        ^^^^^^ syntax IdentifierType
+//                               ^^^ syntax IdentifierBuiltinType
+      ignore_capture_index: Option<u32>,
+//                          ^^^^^^ syntax IdentifierType
+//                                 ^^^ syntax IdentifierBuiltinType
+      local_scope_capture_index: Option<u32>,
+//                               ^^^^^^ syntax IdentifierType
+//                                      ^^^ syntax IdentifierBuiltinType
+      local_definition_capture_index: Option<u32>,
+//                                    ^^^^^^ syntax IdentifierType
+//                                           ^^^ syntax IdentifierBuiltinType
+      tags_pattern_index: usize,
+//                        ^^^^^ syntax IdentifierBuiltinType
+      pattern_info: Vec<PatternInfo>,
+//                  ^^^ syntax IdentifierType
+//                      ^^^^^^^^^^^ syntax IdentifierType
+  }
+  
+  #[derive(Debug)]
+//^^^^^^^^^^^^^^^^ syntax IdentifierAttribute
+  pub struct NamedCapture {
+//^^^ syntax Keyword
+//    ^^^^^^ syntax Keyword
+//           ^^^^^^^^^^^^ definition scip-ctags NamedCapture# IdentifierType
+      pub syntax_type_id: u32,
+//    ^^^ syntax Keyword
+//                        ^^^ syntax IdentifierBuiltinType
+      pub is_definition: bool,
+//    ^^^ syntax Keyword
+//                       ^^^^ syntax IdentifierBuiltinType
+  }
+  
+  pub struct TagsContext {
+//^^^ syntax Keyword
+//    ^^^^^^ syntax Keyword
+//           ^^^^^^^^^^^ definition scip-ctags TagsContext# IdentifierType
+      parser: Parser,
+//            ^^^^^^ syntax IdentifierType
+      cursor: QueryCursor,
+//            ^^^^^^^^^^^ syntax IdentifierType
+  }
+  
+  #[derive(Debug, Clone)]
+//^^^^^^^^^^^^^^^^^^^^^^^ syntax IdentifierAttribute
+  pub struct Tag {
+//^^^ syntax Keyword
+//    ^^^^^^ syntax Keyword
+//           ^^^ definition scip-ctags Tag# IdentifierType
+      pub range: Range<usize>,
+//    ^^^ syntax Keyword
+//               ^^^^^ syntax IdentifierType
+//                     ^^^^^ syntax IdentifierBuiltinType
+      pub name_range: Range<usize>,
+//    ^^^ syntax Keyword
+//                    ^^^^^ syntax IdentifierType
+//                          ^^^^^ syntax IdentifierBuiltinType
+      pub line_range: Range<usize>,
+//    ^^^ syntax Keyword
+//                    ^^^^^ syntax IdentifierType
+//                          ^^^^^ syntax IdentifierBuiltinType
+      pub span: Range<Point>,
+//    ^^^ syntax Keyword
+//              ^^^^^ syntax IdentifierType
+//                    ^^^^^ syntax IdentifierType
+      pub utf16_column_range: Range<usize>,
+//    ^^^ syntax Keyword
+//                            ^^^^^ syntax IdentifierType
+//                                  ^^^^^ syntax IdentifierBuiltinType
+      pub docs: Option<String>,
+//    ^^^ syntax Keyword
+//              ^^^^^^ syntax IdentifierType
+//                     ^^^^^^ syntax IdentifierType
+      pub is_definition: bool,
+//    ^^^ syntax Keyword
+//                       ^^^^ syntax IdentifierBuiltinType
+      pub syntax_type_id: u32,
+//    ^^^ syntax Keyword
+//                        ^^^ syntax IdentifierBuiltinType
+  }
+  
+  #[derive(Debug, Error, PartialEq)]
+//^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax IdentifierAttribute
+  pub enum Error {
+//^^^ syntax Keyword
+//    ^^^^ syntax Keyword
+//         ^^^^^ syntax IdentifierType
+      #[error(transparent)]
+//    ^^^^^^^^^^^^^^^^^^^^^ syntax IdentifierAttribute
+      Query(#[from] QueryError),
+//          ^^^^^^^ syntax IdentifierAttribute
+//                  ^^^^^^^^^^ syntax IdentifierType
+      #[error(transparent)]
+//    ^^^^^^^^^^^^^^^^^^^^^ syntax IdentifierAttribute
+      Regex(#[from] regex::Error),
+//          ^^^^^^^ syntax IdentifierAttribute
+//                         ^^^^^ syntax IdentifierType
+      #[error("Cancelled")]
+//    ^^^^^^^^^^^^^^^^^^^^^ syntax IdentifierAttribute
+//            ^^^^^^^^^^^ syntax StringLiteral
+      Cancelled,
+      #[error("Invalid language")]
+//    ^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax IdentifierAttribute
+//            ^^^^^^^^^^^^^^^^^^ syntax StringLiteral
+      InvalidLanguage,
+      #[error("Invalid capture @{0}. Expected one of: @definition.*, @reference.*, @doc, @name, @local.(scope|definition|reference).")]
+//    ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax IdentifierAttribute
+//            ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax StringLiteral
+      InvalidCapture(String),
+//                   ^^^^^^ syntax IdentifierType
+  }
+  
+  #[derive(Debug, Default)]
+//^^^^^^^^^^^^^^^^^^^^^^^^^ syntax IdentifierAttribute
+  struct PatternInfo {
+//^^^^^^ syntax Keyword
+//       ^^^^^^^^^^^ definition scip-ctags PatternInfo# IdentifierType
+      docs_adjacent_capture: Option<u32>,
+//                           ^^^^^^ syntax IdentifierType
+//                                  ^^^ syntax IdentifierBuiltinType
+      local_scope_inherits: bool,
+//                          ^^^^ syntax IdentifierBuiltinType
+      name_must_be_non_local: bool,
+//                            ^^^^ syntax IdentifierBuiltinType
+      doc_strip_regex: Option<Regex>,
+//                     ^^^^^^ syntax IdentifierType
+//                            ^^^^^ syntax IdentifierType
+  }
+  
+  #[derive(Debug)]
+//^^^^^^^^^^^^^^^^ syntax IdentifierAttribute
+  struct LocalDef<'a> {
+//^^^^^^ syntax Keyword
+//       ^^^^^^^^ definition scip-ctags LocalDef# IdentifierType
+      name: &'a [u8],
+//               ^^ syntax IdentifierBuiltinType
+  }
+  
+  #[derive(Debug)]
+//^^^^^^^^^^^^^^^^ syntax IdentifierAttribute
+  struct LocalScope<'a> {
+//^^^^^^ syntax Keyword
+//       ^^^^^^^^^^ definition scip-ctags LocalScope# IdentifierType
+      inherits: bool,
+//              ^^^^ syntax IdentifierBuiltinType
+      range: Range<usize>,
+//           ^^^^^ syntax IdentifierType
+//                 ^^^^^ syntax IdentifierBuiltinType
+      local_defs: Vec<LocalDef<'a>>,
+//                ^^^ syntax IdentifierType
+//                    ^^^^^^^^ syntax IdentifierType
+  }
+  
+  struct TagsIter<'a, I>
+//^^^^^^ syntax Keyword
+//       ^^^^^^^^ definition scip-ctags TagsIter# IdentifierType
+//                    ^ syntax IdentifierType
+  where
+//^^^^^ syntax Keyword
+      I: Iterator<Item = tree_sitter::QueryMatch<'a, 'a>>,
+//    ^ syntax IdentifierType
+//       ^^^^^^^^ syntax IdentifierType
+//                ^^^^ syntax IdentifierType
+//                                    ^^^^^^^^^^ syntax IdentifierType
+  {
+      matches: I,
+//             ^ syntax IdentifierType
+      _tree: Tree,
+//           ^^^^ syntax IdentifierType
+      source: &'a [u8],
+//                 ^^ syntax IdentifierBuiltinType
+      prev_line_info: Option<LineInfo>,
+//                    ^^^^^^ syntax IdentifierType
+//                           ^^^^^^^^ syntax IdentifierType
+      config: &'a TagsConfiguration,
+//                ^^^^^^^^^^^^^^^^^ syntax IdentifierType
+      cancellation_flag: Option<&'a AtomicUsize>,
+//                       ^^^^^^ syntax IdentifierType
+//                                  ^^^^^^^^^^^ syntax IdentifierType
+      iter_count: usize,
+//                ^^^^^ syntax IdentifierBuiltinType
+      tag_queue: Vec<(Tag, usize)>,
+//               ^^^ syntax IdentifierType
+//                    ^^^ syntax IdentifierType
+//                         ^^^^^ syntax IdentifierBuiltinType
+      scopes: Vec<LocalScope<'a>>,
+//            ^^^ syntax IdentifierType
+//                ^^^^^^^^^^ syntax IdentifierType
+  }
+  
+  struct LineInfo {
+//^^^^^^ syntax Keyword
+//       ^^^^^^^^ definition scip-ctags LineInfo# IdentifierType
+      utf8_position: Point,
+//                   ^^^^^ syntax IdentifierType
+      utf8_byte: usize,
+//               ^^^^^ syntax IdentifierBuiltinType
+      utf16_column: usize,
+//                  ^^^^^ syntax IdentifierBuiltinType
+      line_range: Range<usize>,
+//                ^^^^^ syntax IdentifierType
+//                      ^^^^^ syntax IdentifierBuiltinType
+  }
+  
+  impl TagsConfiguration {
+//^^^^ syntax Keyword
+//     ^^^^^^^^^^^^^^^^^ reference scip-ctags TagsConfiguration# IdentifierType
+      pub fn new(language: Language, tags_query: &str, locals_query: &str) -> Result<Self, Error> {
+//    ^^^ syntax Keyword
+//        ^^ syntax Keyword
+//           ^^^ definition scip-ctags TagsConfiguration#new(). IdentifierFunctionDefinition
+//               ^^^^^^^^ definition local 1 IdentifierParameter
+//                         ^^^^^^^^ syntax IdentifierType
+//                                   ^^^^^^^^^^ definition local 2 IdentifierParameter
+//                                                ^^^ syntax IdentifierBuiltinType
+//                                                     ^^^^^^^^^^^^ definition local 3 IdentifierParameter
+//                                                                    ^^^ syntax IdentifierBuiltinType
+//                                                                            ^^^^^^ syntax IdentifierType
+//                                                                                   ^^^^ syntax IdentifierType
+//                                                                                         ^^^^^ syntax IdentifierType
+          let query = Query::new(language, &format!("{}{}", locals_query, tags_query))?;
+//        ^^^ syntax Keyword
+//            ^^^^^ definition local 4
+//                           ^^^ syntax IdentifierFunction
+//                               ^^^^^^^^ reference read local 1
+//                                          ^^^^^^ syntax IdentifierMacro
+//                                                  ^^^^^^ syntax StringLiteral
+//                                                          ^^^^^^^^^^^^ reference local 3
+//                                                                        ^^^^^^^^^^ reference local 2
+  
+          let tags_query_offset = locals_query.len();
+//        ^^^ syntax Keyword
+//            ^^^^^^^^^^^^^^^^^ definition local 5
+//                                ^^^^^^^^^^^^ reference local 3
+//                                             ^^^ syntax IdentifierFunction
+          let mut tags_pattern_index = 0;
+//        ^^^ syntax Keyword
+//            ^^^ syntax Keyword
+//                ^^^^^^^^^^^^^^^^^^ definition local 6
+//                                     ^ syntax NumericLiteral
+          for i in 0..(query.pattern_count()) {
+//        ^^^ syntax Keyword
+//            ^ definition local 16
+//              ^^ syntax Keyword
+//                 ^ syntax NumericLiteral
+//                     ^^^^^ reference local 4
+//                           ^^^^^^^^^^^^^ syntax IdentifierFunction
+              let pattern_offset = query.start_byte_for_pattern(i);
+//            ^^^ syntax Keyword
+//                ^^^^^^^^^^^^^^ definition local 17
+//                                 ^^^^^ reference local 4
+//                                       ^^^^^^^^^^^^^^^^^^^^^^ syntax IdentifierFunction
+//                                                              ^ reference read local 16
+              if pattern_offset < tags_query_offset {
+//            ^^ syntax Keyword
+//               ^^^^^^^^^^^^^^ reference read local 17
+//                                ^^^^^^^^^^^^^^^^^ reference read local 5
+                  tags_pattern_index += 1;
+//                ^^^^^^^^^^^^^^^^^^ reference write read local 6
+//                                      ^ syntax NumericLiteral
+              }
+          }
+  
+          let mut capture_map = HashMap::new();
+//        ^^^ syntax Keyword
+//            ^^^ syntax Keyword
+//                ^^^^^^^^^^^ definition local 7
+//                                       ^^^ syntax IdentifierFunction
+          let mut syntax_type_names = Vec::new();
+//        ^^^ syntax Keyword
+//            ^^^ syntax Keyword
+//                ^^^^^^^^^^^^^^^^^ definition local 8
+//                                         ^^^ syntax IdentifierFunction
+          let mut doc_capture_index = None;
+//        ^^^ syntax Keyword
+//            ^^^ syntax Keyword
+//                ^^^^^^^^^^^^^^^^^ definition local 9
+          let mut name_capture_index = None;
+//        ^^^ syntax Keyword
+//            ^^^ syntax Keyword
+//                ^^^^^^^^^^^^^^^^^^ definition local 10
+          let mut ignore_capture_index = None;
+//        ^^^ syntax Keyword
+//            ^^^ syntax Keyword
+//                ^^^^^^^^^^^^^^^^^^^^ definition local 11
+          let mut local_scope_capture_index = None;
+//        ^^^ syntax Keyword
+//            ^^^ syntax Keyword
+//                ^^^^^^^^^^^^^^^^^^^^^^^^^ definition local 12
+          let mut local_definition_capture_index = None;
+//        ^^^ syntax Keyword
+//            ^^^ syntax Keyword
+//                ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ definition local 13
+          for (i, name) in query.capture_names().iter().enumerate() {
+//        ^^^ syntax Keyword
+//             ^ definition local 18
+//                ^^^^ definition local 19
+//                      ^^ syntax Keyword
+//                         ^^^^^ reference local 4
+//                               ^^^^^^^^^^^^^ syntax IdentifierFunction
+//                                               ^^^^ syntax IdentifierFunction
+//                                                      ^^^^^^^^^ syntax IdentifierFunction
+              match name.as_str() {
+//            ^^^^^ syntax Keyword
+//                  ^^^^ reference local 19
+//                       ^^^^^^ syntax IdentifierFunction
+                  "" => continue,
+//                ^^ syntax StringLiteral
+//                      ^^^^^^^^ syntax Keyword
+                  "name" => name_capture_index = Some(i as u32),
+//                ^^^^^^ syntax StringLiteral
+//                          ^^^^^^^^^^^^^^^^^^ reference write local 10
+//                                               ^^^^ syntax IdentifierFunction
+//                                                    ^ reference local 18
+//                                                      ^^ syntax Keyword
+//                                                         ^^^ syntax IdentifierBuiltinType
+                  "ignore" => ignore_capture_index = Some(i as u32),
+//                ^^^^^^^^ syntax StringLiteral
+//                            ^^^^^^^^^^^^^^^^^^^^ reference write local 11
+//                                                   ^^^^ syntax IdentifierFunction
+//                                                        ^ reference local 18
+//                                                          ^^ syntax Keyword
+//                                                             ^^^ syntax IdentifierBuiltinType
+                  "doc" => doc_capture_index = Some(i as u32),
+//                ^^^^^ syntax StringLiteral
+//                         ^^^^^^^^^^^^^^^^^ reference write local 9
+//                                             ^^^^ syntax IdentifierFunction
+//                                                  ^ reference local 18
+//                                                    ^^ syntax Keyword
+//                                                       ^^^ syntax IdentifierBuiltinType
+                  "local.scope" => local_scope_capture_index = Some(i as u32),
+//                ^^^^^^^^^^^^^ syntax StringLiteral
+//                                 ^^^^^^^^^^^^^^^^^^^^^^^^^ reference write local 12
+//                                                             ^^^^ syntax IdentifierFunction
+//                                                                  ^ reference local 18
+//                                                                    ^^ syntax Keyword
+//                                                                       ^^^ syntax IdentifierBuiltinType
+                  "local.definition" => local_definition_capture_index = Some(i as u32),
+//                ^^^^^^^^^^^^^^^^^^ syntax StringLiteral
+//                                      ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ reference write local 13
+//                                                                       ^^^^ syntax IdentifierFunction
+//                                                                            ^ reference local 18
+//                                                                              ^^ syntax Keyword
+//                                                                                 ^^^ syntax IdentifierBuiltinType
+                  "local.reference" => continue,
+//                ^^^^^^^^^^^^^^^^^ syntax StringLiteral
+//                                     ^^^^^^^^ syntax Keyword
+                  _ => {
+                      let mut is_definition = false;
+//                    ^^^ syntax Keyword
+//                        ^^^ syntax Keyword
+//                            ^^^^^^^^^^^^^ definition local 20
+//                                            ^^^^^ syntax BooleanLiteral
+  
+                      let kind = if name.starts_with("definition.") {
+//                    ^^^ syntax Keyword
+//                        ^^^^ definition local 21
+//                               ^^ syntax Keyword
+//                                  ^^^^ reference local 19
+//                                       ^^^^^^^^^^^ syntax IdentifierFunction
+//                                                   ^^^^^^^^^^^^^ syntax StringLiteral
+                          is_definition = true;
+//                        ^^^^^^^^^^^^^ reference write local 20
+//                                        ^^^^ syntax BooleanLiteral
+                          name.trim_start_matches("definition.")
+//                        ^^^^ reference local 19
+//                             ^^^^^^^^^^^^^^^^^^ syntax IdentifierFunction
+//                                                ^^^^^^^^^^^^^ syntax StringLiteral
+                      } else if name.starts_with("reference.") {
+//                      ^^^^ syntax Keyword
+//                           ^^ syntax Keyword
+//                              ^^^^ reference local 19
+//                                   ^^^^^^^^^^^ syntax IdentifierFunction
+//                                               ^^^^^^^^^^^^ syntax StringLiteral
+                          name.trim_start_matches("reference.")
+//                        ^^^^ reference local 19
+//                             ^^^^^^^^^^^^^^^^^^ syntax IdentifierFunction
+//                                                ^^^^^^^^^^^^ syntax StringLiteral
+                      } else {
+//                      ^^^^ syntax Keyword
+                          return Err(Error::InvalidCapture(name.to_string()));
+//                        ^^^^^^ syntax Keyword
+//                               ^^^ syntax IdentifierFunction
+//                                          ^^^^^^^^^^^^^^ syntax IdentifierFunction
+//                                                         ^^^^ reference local 19
+//                                                              ^^^^^^^^^ syntax IdentifierFunction
+                      };
+  
+                      if let Ok(cstr) = CString::new(kind) {
+//                    ^^ syntax Keyword
+//                       ^^^ syntax Keyword
+//                              ^^^^ definition local 22
+//                                               ^^^ syntax IdentifierFunction
+//                                                   ^^^^ reference read local 21
+                          let c_kind = cstr.to_bytes_with_nul().to_vec().into_boxed_slice();
+//                        ^^^ syntax Keyword
+//                            ^^^^^^ definition local 23
+//                                     ^^^^ reference local 22
+//                                          ^^^^^^^^^^^^^^^^^ syntax IdentifierFunction
+//                                                              ^^^^^^ syntax IdentifierFunction
+//                                                                       ^^^^^^^^^^^^^^^^ syntax IdentifierFunction
+                          let syntax_type_id = syntax_type_names
+//                        ^^^ syntax Keyword
+//                            ^^^^^^^^^^^^^^ definition local 24
+//                                             ^^^^^^^^^^^^^^^^^ reference local 8
+                              .iter()
+//                             ^^^^ syntax IdentifierFunction
+                              .position(|n| n == &c_kind)
+//                             ^^^^^^^^ syntax IdentifierFunction
+//                                       ^ definition local 25
+//                                          ^ reference read local 25
+//                                                ^^^^^^ reference local 23
+                              .unwrap_or_else(|| {
+//                             ^^^^^^^^^^^^^^ syntax IdentifierFunction
+                                  syntax_type_names.push(c_kind);
+//                                ^^^^^^^^^^^^^^^^^ reference local 8
+//                                                  ^^^^ syntax IdentifierFunction
+//                                                       ^^^^^^ reference read local 23
+                                  syntax_type_names.len() - 1
+//                                ^^^^^^^^^^^^^^^^^ reference local 8
+//                                                  ^^^ syntax IdentifierFunction
+//                                                          ^ syntax NumericLiteral
+                              }) as u32;
+//                               ^^ syntax Keyword
+//                                  ^^^ syntax IdentifierBuiltinType
+                          capture_map.insert(
+//                        ^^^^^^^^^^^ reference local 7
+//                                    ^^^^^^ syntax IdentifierFunction
+                              i as u32,
+//                            ^ reference local 18
+//                              ^^ syntax Keyword
+//                                 ^^^ syntax IdentifierBuiltinType
+                              NamedCapture {
+//                            ^^^^^^^^^^^^ syntax IdentifierType
+                                  syntax_type_id,
+//                                ^^^^^^^^^^^^^^ reference local 24
+                                  is_definition,
+//                                ^^^^^^^^^^^^^ reference local 20
+                              },
+                          );
+                      }
+                  }
+              }
+          }
+  
+          let c_syntax_type_names = syntax_type_names.iter().map(|s| s.as_ptr()).collect();
+//        ^^^ syntax Keyword
+//            ^^^^^^^^^^^^^^^^^^^ definition local 14
+//                                  ^^^^^^^^^^^^^^^^^ reference local 8
+//                                                    ^^^^ syntax IdentifierFunction
+//                                                           ^^^ syntax IdentifierFunction
+//                                                                ^ definition local 26
+//                                                                   ^ reference local 26
+//                                                                     ^^^^^^ syntax IdentifierFunction
+//                                                                               ^^^^^^^ syntax IdentifierFunction
+  
+          let pattern_info = (0..query.pattern_count())
+//        ^^^ syntax Keyword
+//            ^^^^^^^^^^^^ definition local 15
+//                            ^ syntax NumericLiteral
+//                               ^^^^^ reference local 4
+//                                     ^^^^^^^^^^^^^ syntax IdentifierFunction
+              .map(|pattern_index| {
+//             ^^^ syntax IdentifierFunction
+//                  ^^^^^^^^^^^^^ definition local 27
+                  let mut info = PatternInfo::default();
+//                ^^^ syntax Keyword
+//                    ^^^ syntax Keyword
+//                        ^^^^ definition local 28
+//                                            ^^^^^^^ syntax IdentifierFunction
+                  for (property, is_positive) in query.property_predicates(pattern_index) {
+//                ^^^ syntax Keyword
+//                     ^^^^^^^^ definition local 29
+//                               ^^^^^^^^^^^ definition local 30
+//                                            ^^ syntax Keyword
+//                                               ^^^^^ reference local 4
+//                                                     ^^^^^^^^^^^^^^^^^^^ syntax IdentifierFunction
+//                                                                         ^^^^^^^^^^^^^ reference read local 27
+                      if !is_positive && property.key.as_ref() == "local" {
+//                    ^^ syntax Keyword
+//                        ^^^^^^^^^^^ reference local 30
+//                                       ^^^^^^^^ reference local 29
+//                                                    ^^^^^^ syntax IdentifierFunction
+//                                                                ^^^^^^^ syntax StringLiteral
+                          info.name_must_be_non_local = true;
+//                        ^^^^ reference local 28
+//                                                      ^^^^ syntax BooleanLiteral
+                      }
+                  }
+                  info.local_scope_inherits = true;
+//                ^^^^ reference local 28
+//                                            ^^^^ syntax BooleanLiteral
+                  for property in query.property_settings(pattern_index) {
+//                ^^^ syntax Keyword
+//                    ^^^^^^^^ definition local 31
+//                             ^^ syntax Keyword
+//                                ^^^^^ reference local 4
+//                                      ^^^^^^^^^^^^^^^^^ syntax IdentifierFunction
+//                                                        ^^^^^^^^^^^^^ reference read local 27
+                      if property.key.as_ref() == "local.scope-inherits"
+//                    ^^ syntax Keyword
+//                       ^^^^^^^^ reference local 31
+//                                    ^^^^^^ syntax IdentifierFunction
+//                                                ^^^^^^^^^^^^^^^^^^^^^^ syntax StringLiteral
+                          && property
+//                           ^^^^^^^^ reference local 31
+                              .value
+                              .as_ref()
+//                             ^^^^^^ syntax IdentifierFunction
+                              .map_or(false, |v| v.as_ref() == "false")
+//                             ^^^^^^ syntax IdentifierFunction
+//                                    ^^^^^ syntax BooleanLiteral
+//                                            ^ definition local 32
+//                                               ^ reference local 32
+//                                                 ^^^^^^ syntax IdentifierFunction
+//                                                             ^^^^^^^ syntax StringLiteral
+                      {
+                          info.local_scope_inherits = false;
+//                        ^^^^ reference local 28
+//                                                    ^^^^^ syntax BooleanLiteral
+                      }
+                  }
+                  if let Some(doc_capture_index) = doc_capture_index {
+//                ^^ syntax Keyword
+//                   ^^^ syntax Keyword
+//                            ^^^^^^^^^^^^^^^^^ reference local 9
+//                            ^^^^^^^^^^^^^^^^^ definition local 33
+//                                                 ^^^^^^^^^^^^^^^^^ reference local 9
+                      for predicate in query.general_predicates(pattern_index) {
+//                    ^^^ syntax Keyword
+//                        ^^^^^^^^^ definition local 34
+//                                  ^^ syntax Keyword
+//                                     ^^^^^ reference local 4
+//                                           ^^^^^^^^^^^^^^^^^^ syntax IdentifierFunction
+//                                                              ^^^^^^^^^^^^^ reference read local 27
+                          if predicate.args.get(0)
+//                        ^^ syntax Keyword
+//                           ^^^^^^^^^ reference local 34
+//                                          ^^^ syntax IdentifierFunction
+//                                              ^ syntax NumericLiteral
+                              == Some(&QueryPredicateArg::Capture(doc_capture_index))
+//                               ^^^^ syntax IdentifierFunction
+//                                                        ^^^^^^^ syntax IdentifierFunction
+//                                                                ^^^^^^^^^^^^^^^^^ reference read local 33
+                          {
+                              match (predicate.operator.as_ref(), predicate.args.get(1)) {
+//                            ^^^^^ syntax Keyword
+//                                   ^^^^^^^^^ reference local 34
+//                                                      ^^^^^^ syntax IdentifierFunction
+//                                                                ^^^^^^^^^ reference local 34
+//                                                                               ^^^ syntax IdentifierFunction
+//                                                                                   ^ syntax NumericLiteral
+                                  ("select-adjacent!", Some(QueryPredicateArg::Capture(index))) => {
+//                                 ^^^^^^^^^^^^^^^^^^ syntax StringLiteral
+//                                                                                     ^^^^^ definition local 35
+                                      info.docs_adjacent_capture = Some(*index);
+//                                    ^^^^ reference local 28
+//                                                                 ^^^^ syntax IdentifierFunction
+//                                                                       ^^^^^ reference local 35
+                                  }
+                                  ("strip!", Some(QueryPredicateArg::String(pattern))) => {
+//                                 ^^^^^^^^ syntax StringLiteral
+//                                                                          ^^^^^^^ definition local 36
+                                      let regex = Regex::new(pattern.as_ref())?;
+//                                    ^^^ syntax Keyword
+//                                        ^^^^^ definition local 37
+//                                                       ^^^ syntax IdentifierFunction
+//                                                           ^^^^^^^ reference local 36
+//                                                                   ^^^^^^ syntax IdentifierFunction
+                                      info.doc_strip_regex = Some(regex);
+//                                    ^^^^ reference local 28
+//                                                           ^^^^ syntax IdentifierFunction
+//                                                                ^^^^^ reference read local 37
+                                  }
+                                  _ => {}
+                              }
+                          }
+                      }
+                  }
+                  return Ok(info);
+//                ^^^^^^ syntax Keyword
+//                       ^^ syntax IdentifierFunction
+//                          ^^^^ reference read local 28
+              })
+              .collect::<Result<Vec<_>, Error>>()?;
+//                       ^^^^^^ syntax IdentifierType
+//                              ^^^ syntax IdentifierType
+//                                  ^ syntax IdentifierType
+//                                      ^^^^^ syntax IdentifierType
+  
+          Ok(TagsConfiguration {
+//        ^^ syntax IdentifierFunction
+//           ^^^^^^^^^^^^^^^^^ syntax IdentifierType
+              language,
+//            ^^^^^^^^ reference local 1
+              query,
+//            ^^^^^ reference local 4
+              syntax_type_names,
+//            ^^^^^^^^^^^^^^^^^ reference local 8
+              c_syntax_type_names,
+//            ^^^^^^^^^^^^^^^^^^^ reference local 14
+              capture_map,
+//            ^^^^^^^^^^^ reference local 7
+              doc_capture_index,
+//            ^^^^^^^^^^^^^^^^^ reference local 9
+              name_capture_index,
+//            ^^^^^^^^^^^^^^^^^^ reference local 10
+              ignore_capture_index,
+//            ^^^^^^^^^^^^^^^^^^^^ reference local 11
+              tags_pattern_index,
+//            ^^^^^^^^^^^^^^^^^^ reference local 6
+              local_scope_capture_index,
+//            ^^^^^^^^^^^^^^^^^^^^^^^^^ reference local 12
+              local_definition_capture_index,
+//            ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ reference local 13
+              pattern_info,
+//            ^^^^^^^^^^^^ reference local 15
+          })
+      }
+  
+      pub fn syntax_type_name(&self, id: u32) -> &str {
+//    ^^^ syntax Keyword
+//        ^^ syntax Keyword
+//           ^^^^^^^^^^^^^^^^ definition scip-ctags TagsConfiguration#syntax_type_name(). IdentifierFunctionDefinition
+//                             ^^^^ syntax IdentifierBuiltin
+//                                   ^^ definition local 38 IdentifierParameter
+//                                       ^^^ syntax IdentifierBuiltinType
+//                                                ^^^ syntax IdentifierBuiltinType
+          unsafe {
+//        ^^^^^^ syntax Keyword
+              let cstr =
+//            ^^^ syntax Keyword
+//                ^^^^ definition local 39
+                  CStr::from_ptr(self.syntax_type_names[id as usize].as_ptr() as *const c_char)
+//                      ^^^^^^^^ syntax IdentifierFunction
+//                               ^^^^ syntax IdentifierBuiltin
+//                                                      ^^ reference local 38
+//                                                         ^^ syntax Keyword
+//                                                            ^^^^^ syntax IdentifierBuiltinType
+//                                                                   ^^^^^^ syntax IdentifierFunction
+//                                                                            ^^ syntax Keyword
+//                                                                                ^^^^^ syntax Keyword
+//                                                                                      ^^^^^^ syntax IdentifierType
+                      .to_bytes();
+//                     ^^^^^^^^ syntax IdentifierFunction
+              str::from_utf8(cstr).expect("syntax type name was not valid utf-8")
+//                 ^^^^^^^^^ syntax IdentifierFunction
+//                           ^^^^ reference read local 39
+//                                 ^^^^^^ syntax IdentifierFunction
+//                                        ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax StringLiteral
+          }
+      }
+  }
+  
+  impl TagsContext {
+//^^^^ syntax Keyword
+//     ^^^^^^^^^^^ reference scip-ctags TagsContext# IdentifierType
+      pub fn new() -> Self {
+//    ^^^ syntax Keyword
+//        ^^ syntax Keyword
+//           ^^^ definition scip-ctags TagsContext#new(). IdentifierFunctionDefinition
+//                    ^^^^ syntax IdentifierType
+          TagsContext {
+//        ^^^^^^^^^^^ syntax IdentifierType
+              parser: Parser::new(),
+//                            ^^^ syntax IdentifierFunction
+              cursor: QueryCursor::new(),
+//                                 ^^^ syntax IdentifierFunction
+          }
+      }
+  
+      pub fn parser(&mut self) -> &mut Parser {
+//    ^^^ syntax Keyword
+//        ^^ syntax Keyword
+//           ^^^^^^ definition scip-ctags TagsContext#parser(). IdentifierFunctionDefinition
+//                   ^^^ syntax Keyword
+//                       ^^^^ syntax IdentifierBuiltin
+//                                 ^^^ syntax Keyword
+//                                     ^^^^^^ syntax IdentifierType
+          &mut self.parser
+//         ^^^ syntax Keyword
+//             ^^^^ syntax IdentifierBuiltin
+      }
+  
+      pub fn generate_tags<'a>(
+//    ^^^ syntax Keyword
+//        ^^ syntax Keyword
+//           ^^^^^^^^^^^^^ definition scip-ctags TagsContext#generate_tags(). IdentifierFunctionDefinition
+          &'a mut self,
+//            ^^^ syntax Keyword
+//                ^^^^ syntax IdentifierBuiltin
+          config: &'a TagsConfiguration,
+//        ^^^^^^ definition local 40 IdentifierParameter
+//                    ^^^^^^^^^^^^^^^^^ syntax IdentifierType
+          source: &'a [u8],
+//        ^^^^^^ definition local 41 IdentifierParameter
+//                     ^^ syntax IdentifierBuiltinType
+          cancellation_flag: Option<&'a AtomicUsize>,
+//        ^^^^^^^^^^^^^^^^^ definition local 42 IdentifierParameter
+//                           ^^^^^^ syntax IdentifierType
+//                                      ^^^^^^^^^^^ syntax IdentifierType
+      ) -> Result<(impl Iterator<Item = Result<Tag, Error>> + 'a, bool), Error> {
+//         ^^^^^^ syntax IdentifierType
+//                 ^^^^ syntax Keyword
+//                      ^^^^^^^^ syntax IdentifierType
+//                               ^^^^ syntax IdentifierType
+//                                      ^^^^^^ syntax IdentifierType
+//                                             ^^^ syntax IdentifierType
+//                                                  ^^^^^ syntax IdentifierType
+//                                                                ^^^^ syntax IdentifierBuiltinType
+//                                                                       ^^^^^ syntax IdentifierType
+          self.parser
+//        ^^^^ syntax IdentifierBuiltin
+              .set_language(config.language)
+//             ^^^^^^^^^^^^ syntax IdentifierFunction
+//                          ^^^^^^ reference local 40
+              .map_err(|_| Error::InvalidLanguage)?;
+//             ^^^^^^^ syntax IdentifierFunction
+          self.parser.reset();
+//        ^^^^ syntax IdentifierBuiltin
+//                    ^^^^^ syntax IdentifierFunction
+          unsafe { self.parser.set_cancellation_flag(cancellation_flag) };
+//        ^^^^^^ syntax Keyword
+//                 ^^^^ syntax IdentifierBuiltin
+//                             ^^^^^^^^^^^^^^^^^^^^^ syntax IdentifierFunction
+//                                                   ^^^^^^^^^^^^^^^^^ reference read local 42
+          let tree = self.parser.parse(source, None).ok_or(Error::Cancelled)?;
+//        ^^^ syntax Keyword
+//            ^^^^ definition local 43
+//                   ^^^^ syntax IdentifierBuiltin
+//                               ^^^^^ syntax IdentifierFunction
+//                                     ^^^^^^ reference read local 41
+//                                                   ^^^^^ syntax IdentifierFunction
+  
+          // The `matches` iterator borrows the `Tree`, which prevents it from being moved.
+//        ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+          // But the tree is really just a pointer, so it's actually ok to move it.
+//        ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+          let tree_ref = unsafe { mem::transmute::<_, &'static Tree>(&tree) };
+//        ^^^ syntax Keyword
+//            ^^^^^^^^ definition local 44
+//                       ^^^^^^ syntax Keyword
+//                                                 ^ syntax IdentifierType
+//                                                             ^^^^ syntax IdentifierType
+//                                                                    ^^^^ reference local 43
+          let matches = self
+//        ^^^ syntax Keyword
+//            ^^^^^^^ definition local 45
+//                      ^^^^ syntax IdentifierBuiltin
+              .cursor
+              .matches(&config.query, tree_ref.root_node(), source);
+//             ^^^^^^^ syntax IdentifierFunction
+//                      ^^^^^^ reference local 40
+//                                    ^^^^^^^^ reference local 44
+//                                             ^^^^^^^^^ syntax IdentifierFunction
+//                                                          ^^^^^^ reference read local 41
+          Ok((
+//        ^^ syntax IdentifierFunction
+              TagsIter {
+//            ^^^^^^^^ syntax IdentifierType
+                  _tree: tree,
+//                       ^^^^ reference local 43
+                  matches,
+//                ^^^^^^^ reference local 45
+                  source,
+//                ^^^^^^ reference local 41
+                  config,
+//                ^^^^^^ reference local 40
+                  cancellation_flag,
+//                ^^^^^^^^^^^^^^^^^ reference local 42
+                  prev_line_info: None,
+                  tag_queue: Vec::new(),
+//                                ^^^ syntax IdentifierFunction
+                  iter_count: 0,
+//                            ^ syntax NumericLiteral
+                  scopes: vec![LocalScope {
+//                        ^^^ syntax IdentifierMacro
+                      range: 0..source.len(),
+//                           ^ syntax NumericLiteral
+//                              ^^^^^^ reference local 41
+                      inherits: false,
+//                              ^^^^^ syntax BooleanLiteral
+                      local_defs: Vec::new(),
+                  }],
+              },
+              tree_ref.root_node().has_error(),
+//            ^^^^^^^^ reference local 44
+//                     ^^^^^^^^^ syntax IdentifierFunction
+//                                 ^^^^^^^^^ syntax IdentifierFunction
+          ))
+      }
+  }
+  
+  impl<'a, I> Iterator for TagsIter<'a, I>
+//^^^^ syntax Keyword
+//         ^ syntax IdentifierType
+//            ^^^^^^^^ syntax IdentifierType
+//                     ^^^ syntax Keyword
+//                         ^^^^^^^^ reference scip-ctags TagsIter# IdentifierType
+//                                      ^ syntax IdentifierType
+  where
+//^^^^^ syntax Keyword
+      I: Iterator<Item = tree_sitter::QueryMatch<'a, 'a>>,
+//    ^ syntax IdentifierType
+//       ^^^^^^^^ syntax IdentifierType
+//                ^^^^ syntax IdentifierType
+//                                    ^^^^^^^^^^ syntax IdentifierType
+  {
+      type Item = Result<Tag, Error>;
+//    ^^^^ syntax Keyword
+//         ^^^^ syntax IdentifierType
+//                ^^^^^^ syntax IdentifierType
+//                       ^^^ syntax IdentifierType
+//                            ^^^^^ syntax IdentifierType
+  
+      fn next(&mut self) -> Option<Self::Item> {
+//    ^^ syntax Keyword
+//       ^^^^ definition scip-ctags TagsIter#next(). IdentifierFunctionDefinition
+//             ^^^ syntax Keyword
+//                 ^^^^ syntax IdentifierBuiltin
+//                          ^^^^^^ syntax IdentifierType
+//                                       ^^^^ syntax IdentifierType
+          loop {
+//        ^^^^ syntax Keyword
+              // Periodically check for cancellation, returning `Cancelled` error if the
+//            ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+              // cancellation flag was flipped.
+//            ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+              if let Some(cancellation_flag) = self.cancellation_flag {
+//            ^^ syntax Keyword
+//               ^^^ syntax Keyword
+//                        ^^^^^^^^^^^^^^^^^ definition local 46
+//                                             ^^^^ syntax IdentifierBuiltin
+                  self.iter_count += 1;
+//                ^^^^ syntax IdentifierBuiltin
+//                                   ^ syntax NumericLiteral
+                  if self.iter_count >= CANCELLATION_CHECK_INTERVAL {
+//                ^^ syntax Keyword
+//                   ^^^^ syntax IdentifierBuiltin
+                      self.iter_count = 0;
+//                    ^^^^ syntax IdentifierBuiltin
+//                                      ^ syntax NumericLiteral
+                      if cancellation_flag.load(Ordering::Relaxed) != 0 {
+//                    ^^ syntax Keyword
+//                       ^^^^^^^^^^^^^^^^^ reference local 46
+//                                         ^^^^ syntax IdentifierFunction
+//                                                                    ^ syntax NumericLiteral
+                          return Some(Err(Error::Cancelled));
+//                        ^^^^^^ syntax Keyword
+//                               ^^^^ syntax IdentifierFunction
+//                                    ^^^ syntax IdentifierFunction
+                      }
+                  }
+              }
+  
+              // If there is a queued tag for an earlier node in the syntax tree, then pop
+//            ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+              // it off of the queue and return it.
+//            ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+              if let Some(last_entry) = self.tag_queue.last() {
+//            ^^ syntax Keyword
+//               ^^^ syntax Keyword
+//                        ^^^^^^^^^^ definition local 47
+//                                      ^^^^ syntax IdentifierBuiltin
+//                                                     ^^^^ syntax IdentifierFunction
+                  if self.tag_queue.len() > 1
+//                ^^ syntax Keyword
+//                   ^^^^ syntax IdentifierBuiltin
+//                                  ^^^ syntax IdentifierFunction
+//                                          ^ syntax NumericLiteral
+                      && self.tag_queue[0].0.name_range.end < last_entry.0.name_range.start
+//                       ^^^^ syntax IdentifierBuiltin
+//                                      ^ syntax NumericLiteral
+//                                         ^ syntax NumericLiteral
+//                                                            ^^^^^^^^^^ reference local 47
+//                                                                       ^ syntax NumericLiteral
+                  {
+                      let tag = self.tag_queue.remove(0).0;
+//                    ^^^ syntax Keyword
+//                        ^^^ definition local 48
+//                              ^^^^ syntax IdentifierBuiltin
+//                                             ^^^^^^ syntax IdentifierFunction
+//                                                    ^ syntax NumericLiteral
+//                                                       ^ syntax NumericLiteral
+                      if tag.is_ignored() {
+//                    ^^ syntax Keyword
+//                       ^^^ reference local 48
+//                           ^^^^^^^^^^ syntax IdentifierFunction
+                          continue;
+//                        ^^^^^^^^ syntax Keyword
+                      } else {
+//                      ^^^^ syntax Keyword
+                          return Some(Ok(tag));
+//                        ^^^^^^ syntax Keyword
+//                               ^^^^ syntax IdentifierFunction
+//                                    ^^ syntax IdentifierFunction
+//                                       ^^^ reference read local 48
+                      }
+                  }
+              }
+  
+              // If there is another match, then compute its tag and add it to the
+//            ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+              // tag queue.
+//            ^^^^^^^^^^^^^ syntax Comment
+              if let Some(mat) = self.matches.next() {
+//            ^^ syntax Keyword
+//               ^^^ syntax Keyword
+//                        ^^^ definition local 49
+//                               ^^^^ syntax IdentifierBuiltin
+//                                            ^^^^ syntax IdentifierFunction
+                  let pattern_info = &self.config.pattern_info[mat.pattern_index];
+//                ^^^ syntax Keyword
+//                    ^^^^^^^^^^^^ definition local 50
+//                                    ^^^^ syntax IdentifierBuiltin
+//                                                             ^^^ reference local 49
+  
+                  if mat.pattern_index < self.config.tags_pattern_index {
+//                ^^ syntax Keyword
+//                   ^^^ reference local 49
+//                                       ^^^^ syntax IdentifierBuiltin
+                      for capture in mat.captures {
+//                    ^^^ syntax Keyword
+//                        ^^^^^^^ definition local 58
+//                                ^^ syntax Keyword
+//                                   ^^^ reference local 49
+                          let index = Some(capture.index);
+//                        ^^^ syntax Keyword
+//                            ^^^^^ definition local 59
+//                                    ^^^^ syntax IdentifierFunction
+//                                         ^^^^^^^ reference local 58
+                          let range = capture.node.byte_range();
+//                        ^^^ syntax Keyword
+//                            ^^^^^ definition local 60
+//                                    ^^^^^^^ reference local 58
+//                                                 ^^^^^^^^^^ syntax IdentifierFunction
+                          if index == self.config.local_scope_capture_index {
+//                        ^^ syntax Keyword
+//                           ^^^^^ reference read local 59
+//                                    ^^^^ syntax IdentifierBuiltin
+                              self.scopes.push(LocalScope {
+//                            ^^^^ syntax IdentifierBuiltin
+//                                        ^^^^ syntax IdentifierFunction
+//                                             ^^^^^^^^^^ syntax IdentifierType
+                                  range,
+//                                ^^^^^ reference local 60
+                                  inherits: pattern_info.local_scope_inherits,
+//                                          ^^^^^^^^^^^^ reference local 50
+                                  local_defs: Vec::new(),
+//                                                 ^^^ syntax IdentifierFunction
+                              });
+                          } else if index == self.config.local_definition_capture_index {
+//                          ^^^^ syntax Keyword
+//                               ^^ syntax Keyword
+//                                  ^^^^^ reference read local 59
+//                                           ^^^^ syntax IdentifierBuiltin
+                              if let Some(scope) = self.scopes.iter_mut().rev().find(|scope| {
+//                            ^^ syntax Keyword
+//                               ^^^ syntax Keyword
+//                                        ^^^^^ definition local 62
+//                                                 ^^^^ syntax IdentifierBuiltin
+//                                                             ^^^^^^^^ syntax IdentifierFunction
+//                                                                        ^^^ syntax IdentifierFunction
+//                                                                              ^^^^ syntax IdentifierFunction
+//                                                                                    ^^^^^ definition local 61
+                                  scope.range.start <= range.start && scope.range.end >= range.end
+//                                ^^^^^ reference local 61
+//                                                     ^^^^^ reference local 60
+//                                                                    ^^^^^ reference local 61
+//                                                                                       ^^^^^ reference local 60
+                              }) {
+                                  scope.local_defs.push(LocalDef {
+//                                ^^^^^ reference local 62
+//                                                 ^^^^ syntax IdentifierFunction
+//                                                      ^^^^^^^^ syntax IdentifierType
+                                      name: &self.source[range.clone()],
+//                                           ^^^^ syntax IdentifierBuiltin
+//                                                       ^^^^^ reference local 60
+//                                                             ^^^^^ syntax IdentifierFunction
+                                  });
+                              }
+                          }
+                      }
+                      continue;
+//                    ^^^^^^^^ syntax Keyword
+                  }
+  
+                  let mut name_node = None;
+//                ^^^ syntax Keyword
+//                    ^^^ syntax Keyword
+//                        ^^^^^^^^^ definition local 51
+                  let mut doc_nodes = Vec::new();
+//                ^^^ syntax Keyword
+//                    ^^^ syntax Keyword
+//                        ^^^^^^^^^ definition local 52
+//                                         ^^^ syntax IdentifierFunction
+                  let mut tag_node = None;
+//                ^^^ syntax Keyword
+//                    ^^^ syntax Keyword
+//                        ^^^^^^^^ definition local 53
+                  let mut syntax_type_id = 0;
+//                ^^^ syntax Keyword
+//                    ^^^ syntax Keyword
+//                        ^^^^^^^^^^^^^^ definition local 54
+//                                         ^ syntax NumericLiteral
+                  let mut is_definition = false;
+//                ^^^ syntax Keyword
+//                    ^^^ syntax Keyword
+//                        ^^^^^^^^^^^^^ definition local 55
+//                                        ^^^^^ syntax BooleanLiteral
+                  let mut docs_adjacent_node = None;
+//                ^^^ syntax Keyword
+//                    ^^^ syntax Keyword
+//                        ^^^^^^^^^^^^^^^^^^ definition local 56
+                  let mut is_ignored = false;
+//                ^^^ syntax Keyword
+//                    ^^^ syntax Keyword
+//                        ^^^^^^^^^^ definition local 57
+//                                     ^^^^^ syntax BooleanLiteral
+  
+                  for capture in mat.captures {
+//                ^^^ syntax Keyword
+//                    ^^^^^^^ definition local 63
+//                            ^^ syntax Keyword
+//                               ^^^ reference local 49
+                      let index = Some(capture.index);
+//                    ^^^ syntax Keyword
+//                        ^^^^^ definition local 64
+//                                ^^^^ syntax IdentifierFunction
+//                                     ^^^^^^^ reference local 63
+  
+                      if index == self.config.ignore_capture_index {
+//                    ^^ syntax Keyword
+//                       ^^^^^ reference read local 64
+//                                ^^^^ syntax IdentifierBuiltin
+                          is_ignored = true;
+//                        ^^^^^^^^^^ reference write local 57
+//                                     ^^^^ syntax BooleanLiteral
+                          name_node = Some(capture.node);
+//                        ^^^^^^^^^ reference write local 51
+//                                    ^^^^ syntax IdentifierFunction
+//                                         ^^^^^^^ reference local 63
+                      }
+  
+                      if index == self.config.pattern_info[mat.pattern_index].docs_adjacent_capture {
+//                    ^^ syntax Keyword
+//                       ^^^^^ reference read local 64
+//                                ^^^^ syntax IdentifierBuiltin
+//                                                         ^^^ reference local 49
+                          docs_adjacent_node = Some(capture.node);
+//                        ^^^^^^^^^^^^^^^^^^ reference write local 56
+//                                             ^^^^ syntax IdentifierFunction
+//                                                  ^^^^^^^ reference local 63
+                      }
+  
+                      if index == self.config.name_capture_index {
+//                    ^^ syntax Keyword
+//                       ^^^^^ reference read local 64
+//                                ^^^^ syntax IdentifierBuiltin
+                          name_node = Some(capture.node);
+//                        ^^^^^^^^^ reference write local 51
+//                                    ^^^^ syntax IdentifierFunction
+//                                         ^^^^^^^ reference local 63
+                      } else if index == self.config.doc_capture_index {
+//                      ^^^^ syntax Keyword
+//                           ^^ syntax Keyword
+//                              ^^^^^ reference read local 64
+//                                       ^^^^ syntax IdentifierBuiltin
+                          doc_nodes.push(capture.node);
+//                        ^^^^^^^^^ reference local 52
+//                                  ^^^^ syntax IdentifierFunction
+//                                       ^^^^^^^ reference local 63
+                      }
+  
+                      if let Some(named_capture) = self.config.capture_map.get(&capture.index) {
+//                    ^^ syntax Keyword
+//                       ^^^ syntax Keyword
+//                                ^^^^^^^^^^^^^ definition local 65
+//                                                 ^^^^ syntax IdentifierBuiltin
+//                                                                         ^^^ syntax IdentifierFunction
+//                                                                              ^^^^^^^ reference local 63
+                          tag_node = Some(capture.node);
+//                        ^^^^^^^^ reference write local 53
+//                                   ^^^^ syntax IdentifierFunction
+//                                        ^^^^^^^ reference local 63
+                          syntax_type_id = named_capture.syntax_type_id;
+//                        ^^^^^^^^^^^^^^ reference write local 54
+//                                         ^^^^^^^^^^^^^ reference local 65
+                          is_definition = named_capture.is_definition;
+//                        ^^^^^^^^^^^^^ reference write local 55
+//                                        ^^^^^^^^^^^^^ reference local 65
+                      }
+                  }
+  
+                  if let Some(name_node) = name_node {
+//                ^^ syntax Keyword
+//                   ^^^ syntax Keyword
+//                            ^^^^^^^^^ reference local 51
+//                            ^^^^^^^^^ definition local 66
+//                                         ^^^^^^^^^ reference local 51
+                      let name_range = name_node.byte_range();
+//                    ^^^ syntax Keyword
+//                        ^^^^^^^^^^ definition local 67
+//                                     ^^^^^^^^^ reference local 66
+//                                               ^^^^^^^^^^ syntax IdentifierFunction
+  
+                      let tag;
+//                    ^^^ syntax Keyword
+//                        ^^^ definition local 68
+                      if let Some(tag_node) = tag_node {
+//                    ^^ syntax Keyword
+//                       ^^^ syntax Keyword
+//                                ^^^^^^^^ reference local 53
+//                                ^^^^^^^^ definition local 69
+//                                            ^^^^^^^^ reference local 53
+                          if name_node.has_error() {
+//                        ^^ syntax Keyword
+//                           ^^^^^^^^^ reference local 66
+//                                     ^^^^^^^^^ syntax IdentifierFunction
+                              continue;
+//                            ^^^^^^^^ syntax Keyword
+                          }
+  
+                          if pattern_info.name_must_be_non_local {
+//                        ^^ syntax Keyword
+//                           ^^^^^^^^^^^^ reference local 50
+                              let mut is_local = false;
+//                            ^^^ syntax Keyword
+//                                ^^^ syntax Keyword
+//                                    ^^^^^^^^ definition local 82
+//                                               ^^^^^ syntax BooleanLiteral
+                              for scope in self.scopes.iter().rev() {
+//                            ^^^ syntax Keyword
+//                                ^^^^^ definition local 83
+//                                      ^^ syntax Keyword
+//                                         ^^^^ syntax IdentifierBuiltin
+//                                                     ^^^^ syntax IdentifierFunction
+//                                                            ^^^ syntax IdentifierFunction
+                                  if scope.range.start <= name_range.start
+//                                ^^ syntax Keyword
+//                                   ^^^^^ reference local 83
+//                                                        ^^^^^^^^^^ reference local 67
+                                      && scope.range.end >= name_range.end
+//                                       ^^^^^ reference local 83
+//                                                          ^^^^^^^^^^ reference local 67
+                                  {
+                                      if scope
+//                                    ^^ syntax Keyword
+//                                       ^^^^^ reference local 83
+                                          .local_defs
+                                          .iter()
+//                                         ^^^^ syntax IdentifierFunction
+                                          .any(|d| d.name == &self.source[name_range.clone()])
+//                                         ^^^ syntax IdentifierFunction
+//                                              ^ definition local 84
+//                                                 ^ reference local 84
+//                                                            ^^^^ syntax IdentifierBuiltin
+//                                                                        ^^^^^^^^^^ reference local 67
+//                                                                                   ^^^^^ syntax IdentifierFunction
+                                      {
+                                          is_local = true;
+//                                        ^^^^^^^^ reference write local 82
+//                                                   ^^^^ syntax BooleanLiteral
+                                          break;
+//                                        ^^^^^ syntax Keyword
+                                      }
+                                      if !scope.inherits {
+//                                    ^^ syntax Keyword
+//                                        ^^^^^ reference local 83
+                                          break;
+//                                        ^^^^^ syntax Keyword
+                                      }
+                                  }
+                              }
+                              if is_local {
+//                            ^^ syntax Keyword
+//                               ^^^^^^^^ reference local 82
+                                  continue;
+//                                ^^^^^^^^ syntax Keyword
+                              }
+                          }
+  
+                          // If needed, filter the doc nodes based on their ranges, selecting
+//                        ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+                          // only the slice that are adjacent to some specified node.
+//                        ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+                          let mut docs_start_index = 0;
+//                        ^^^ syntax Keyword
+//                            ^^^ syntax Keyword
+//                                ^^^^^^^^^^^^^^^^ definition local 70
+//                                                   ^ syntax NumericLiteral
+                          if let (Some(docs_adjacent_node), false) =
+//                        ^^ syntax Keyword
+//                           ^^^ syntax Keyword
+//                                     ^^^^^^^^^^^^^^^^^^ reference local 56
+//                                     ^^^^^^^^^^^^^^^^^^ definition local 85
+//                                                          ^^^^^ syntax BooleanLiteral
+                              (docs_adjacent_node, doc_nodes.is_empty())
+//                             ^^^^^^^^^^^^^^^^^^ reference local 56
+//                                                 ^^^^^^^^^ reference local 52
+//                                                           ^^^^^^^^ syntax IdentifierFunction
+                          {
+                              docs_start_index = doc_nodes.len();
+//                            ^^^^^^^^^^^^^^^^ reference write local 70
+//                                               ^^^^^^^^^ reference local 52
+//                                                         ^^^ syntax IdentifierFunction
+                              let mut start_row = docs_adjacent_node.start_position().row;
+//                            ^^^ syntax Keyword
+//                                ^^^ syntax Keyword
+//                                    ^^^^^^^^^ definition local 86
+//                                                ^^^^^^^^^^^^^^^^^^ reference local 85
+//                                                                   ^^^^^^^^^^^^^^ syntax IdentifierFunction
+                              while docs_start_index > 0 {
+//                            ^^^^^ syntax Keyword
+//                                  ^^^^^^^^^^^^^^^^ reference read local 70
+//                                                     ^ syntax NumericLiteral
+                                  let doc_node = &doc_nodes[docs_start_index - 1];
+//                                ^^^ syntax Keyword
+//                                    ^^^^^^^^ definition local 87
+//                                                ^^^^^^^^^ reference local 52
+//                                                          ^^^^^^^^^^^^^^^^ reference read local 70
+//                                                                             ^ syntax NumericLiteral
+                                  let prev_doc_end_row = doc_node.end_position().row;
+//                                ^^^ syntax Keyword
+//                                    ^^^^^^^^^^^^^^^^ definition local 88
+//                                                       ^^^^^^^^ reference local 87
+//                                                                ^^^^^^^^^^^^ syntax IdentifierFunction
+                                  if prev_doc_end_row + 1 >= start_row {
+//                                ^^ syntax Keyword
+//                                   ^^^^^^^^^^^^^^^^ reference read local 88
+//                                                      ^ syntax NumericLiteral
+//                                                           ^^^^^^^^^ reference read local 86
+                                      docs_start_index -= 1;
+//                                    ^^^^^^^^^^^^^^^^ reference write read local 70
+//                                                        ^ syntax NumericLiteral
+                                      start_row = doc_node.start_position().row;
+//                                    ^^^^^^^^^ reference write local 86
+//                                                ^^^^^^^^ reference local 87
+//                                                         ^^^^^^^^^^^^^^ syntax IdentifierFunction
+                                  } else {
+//                                  ^^^^ syntax Keyword
+                                      break;
+//                                    ^^^^^ syntax Keyword
+                                  }
+                              }
+                          }
+  
+                          // Generate a doc string from all of the doc nodes, applying any strip regexes.
+//                        ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+                          let mut docs = None;
+//                        ^^^ syntax Keyword
+//                            ^^^ syntax Keyword
+//                                ^^^^ definition local 71
+                          for doc_node in &doc_nodes[docs_start_index..] {
+//                        ^^^ syntax Keyword
+//                            ^^^^^^^^ definition local 89
+//                                     ^^ syntax Keyword
+//                                         ^^^^^^^^^ reference local 52
+//                                                   ^^^^^^^^^^^^^^^^ reference local 70
+                              if let Ok(content) = str::from_utf8(&self.source[doc_node.byte_range()])
+//                            ^^ syntax Keyword
+//                               ^^^ syntax Keyword
+//                                      ^^^^^^^ definition local 90
+//                                                      ^^^^^^^^^ syntax IdentifierFunction
+//                                                                 ^^^^ syntax IdentifierBuiltin
+//                                                                             ^^^^^^^^ reference local 89
+//                                                                                      ^^^^^^^^^^ syntax IdentifierFunction
+                              {
+                                  let content = if let Some(regex) = &pattern_info.doc_strip_regex {
+//                                ^^^ syntax Keyword
+//                                    ^^^^^^^ reference local 90
+//                                              ^^ syntax Keyword
+//                                                 ^^^ syntax Keyword
+//                                                          ^^^^^ definition local 91
+//                                                                    ^^^^^^^^^^^^ reference local 50
+                                      regex.replace_all(content, "").to_string()
+//                                    ^^^^^ reference local 91
+//                                          ^^^^^^^^^^^ syntax IdentifierFunction
+//                                                      ^^^^^^^ reference read local 90
+//                                                               ^^ syntax StringLiteral
+//                                                                   ^^^^^^^^^ syntax IdentifierFunction
+                                  } else {
+//                                  ^^^^ syntax Keyword
+                                      content.to_string()
+//                                    ^^^^^^^ reference local 90
+//                                            ^^^^^^^^^ syntax IdentifierFunction
+                                  };
+                                  match &mut docs {
+//                                ^^^^^ syntax Keyword
+//                                       ^^^ syntax Keyword
+//                                           ^^^^ reference local 71
+                                      None => docs = Some(content),
+//                                            ^^^^ reference write local 71
+//                                                   ^^^^ syntax IdentifierFunction
+//                                                        ^^^^^^^ reference read local 90
+                                      Some(d) => {
+//                                         ^ definition local 92
+                                          d.push('\n');
+//                                        ^ reference local 92
+//                                          ^^^^ syntax IdentifierFunction
+//                                               ^^^^ syntax CharacterLiteral
+                                          d.push_str(&content);
+//                                        ^ reference local 92
+//                                          ^^^^^^^^ syntax IdentifierFunction
+//                                                    ^^^^^^^ reference local 90
+                                      }
+                                  }
+                              }
+                          }
+  
+                          let rng = tag_node.byte_range();
+//                        ^^^ syntax Keyword
+//                            ^^^ definition local 72
+//                                  ^^^^^^^^ reference local 69
+//                                           ^^^^^^^^^^ syntax IdentifierFunction
+                          let range = rng.start.min(name_range.start)..rng.end.max(name_range.end);
+//                        ^^^ syntax Keyword
+//                            ^^^^^ definition local 73
+//                                    ^^^ reference local 72
+//                                              ^^^ syntax IdentifierFunction
+//                                                  ^^^^^^^^^^ reference local 67
+//                                                                     ^^^ reference local 72
+//                                                                             ^^^ syntax IdentifierFunction
+//                                                                                 ^^^^^^^^^^ reference local 67
+                          let span = name_node.start_position()..name_node.end_position();
+//                        ^^^ syntax Keyword
+//                            ^^^^ definition local 74
+//                                   ^^^^^^^^^ reference local 66
+//                                             ^^^^^^^^^^^^^^ syntax IdentifierFunction
+//                                                               ^^^^^^^^^ reference local 66
+//                                                                         ^^^^^^^^^^^^ syntax IdentifierFunction
+  
+                          // Compute tag properties that depend on the text of the containing line. If the
+//                        ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+                          // previous tag occurred on the same line, then reuse results from the previous tag.
+//                        ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+                          let line_range;
+//                        ^^^ syntax Keyword
+//                            ^^^^^^^^^^ definition local 75
+                          let mut prev_utf16_column = 0;
+//                        ^^^ syntax Keyword
+//                            ^^^ syntax Keyword
+//                                ^^^^^^^^^^^^^^^^^ definition local 76
+//                                                    ^ syntax NumericLiteral
+                          let mut prev_utf8_byte = name_range.start - span.start.column;
+//                        ^^^ syntax Keyword
+//                            ^^^ syntax Keyword
+//                                ^^^^^^^^^^^^^^ definition local 77
+//                                                 ^^^^^^^^^^ reference local 67
+//                                                                    ^^^^ reference local 74
+                          let line_info = self.prev_line_info.as_ref().and_then(|info| {
+//                        ^^^ syntax Keyword
+//                            ^^^^^^^^^ definition local 78
+//                                        ^^^^ syntax IdentifierBuiltin
+//                                                            ^^^^^^ syntax IdentifierFunction
+//                                                                     ^^^^^^^^ syntax IdentifierFunction
+//                                                                               ^^^^ definition local 93
+                              if info.utf8_position.row == span.start.row {
+//                            ^^ syntax Keyword
+//                               ^^^^ reference local 93
+//                                                         ^^^^ reference local 74
+                                  Some(info)
+//                                ^^^^ syntax IdentifierFunction
+//                                     ^^^^ reference read local 93
+                              } else {
+//                              ^^^^ syntax Keyword
+                                  None
+                              }
+                          });
+                          if let Some(line_info) = line_info {
+//                        ^^ syntax Keyword
+//                           ^^^ syntax Keyword
+//                                    ^^^^^^^^^ reference local 78
+//                                    ^^^^^^^^^ definition local 94
+//                                                 ^^^^^^^^^ reference local 78
+                              line_range = line_info.line_range.clone();
+//                            ^^^^^^^^^^ reference write local 75
+//                                         ^^^^^^^^^ reference local 94
+//                                                              ^^^^^ syntax IdentifierFunction
+                              if line_info.utf8_position.column <= span.start.column {
+//                            ^^ syntax Keyword
+//                               ^^^^^^^^^ reference local 94
+//                                                                 ^^^^ reference local 74
+                                  prev_utf8_byte = line_info.utf8_byte;
+//                                ^^^^^^^^^^^^^^ reference write local 77
+//                                                 ^^^^^^^^^ reference local 94
+                                  prev_utf16_column = line_info.utf16_column;
+//                                ^^^^^^^^^^^^^^^^^ reference write local 76
+//                                                    ^^^^^^^^^ reference local 94
+                              }
+                          } else {
+//                          ^^^^ syntax Keyword
+                              line_range = self::line_range(
+//                            ^^^^^^^^^^ reference write local 75
+//                                         ^^^^ syntax IdentifierBuiltin
+//                                               ^^^^^^^^^^ reference local 75 IdentifierFunction
+                                  self.source,
+//                                ^^^^ syntax IdentifierBuiltin
+                                  name_range.start,
+//                                ^^^^^^^^^^ reference local 67
+                                  span.start,
+//                                ^^^^ reference local 74
+                                  MAX_LINE_LEN,
+                              );
+                          }
+  
+                          let utf16_start_column = prev_utf16_column
+//                        ^^^ syntax Keyword
+//                            ^^^^^^^^^^^^^^^^^^ definition local 79
+//                                                 ^^^^^^^^^^^^^^^^^ reference read local 76
+                              + utf16_len(&self.source[prev_utf8_byte..name_range.start]);
+//                              ^^^^^^^^^ syntax IdentifierFunction
+//                                         ^^^^ syntax IdentifierBuiltin
+//                                                     ^^^^^^^^^^^^^^ reference local 77
+//                                                                     ^^^^^^^^^^ reference local 67
+                          let utf16_end_column =
+//                        ^^^ syntax Keyword
+//                            ^^^^^^^^^^^^^^^^ definition local 80
+                              utf16_start_column + utf16_len(&self.source[name_range.clone()]);
+//                            ^^^^^^^^^^^^^^^^^^ reference read local 79
+//                                                 ^^^^^^^^^ syntax IdentifierFunction
+//                                                            ^^^^ syntax IdentifierBuiltin
+//                                                                        ^^^^^^^^^^ reference local 67
+//                                                                                   ^^^^^ syntax IdentifierFunction
+                          let utf16_column_range = utf16_start_column..utf16_end_column;
+//                        ^^^ syntax Keyword
+//                            ^^^^^^^^^^^^^^^^^^ definition local 81
+//                                                 ^^^^^^^^^^^^^^^^^^ reference local 79
+//                                                                     ^^^^^^^^^^^^^^^^ reference local 80
+  
+                          self.prev_line_info = Some(LineInfo {
+//                        ^^^^ syntax IdentifierBuiltin
+//                                              ^^^^ syntax IdentifierFunction
+//                                                   ^^^^^^^^ syntax IdentifierType
+                              utf8_position: span.end,
+//                                           ^^^^ reference local 74
+                              utf8_byte: name_range.end,
+//                                       ^^^^^^^^^^ reference local 67
+                              utf16_column: utf16_end_column,
+//                                          ^^^^^^^^^^^^^^^^ reference local 80
+                              line_range: line_range.clone(),
+//                                        ^^^^^^^^^^ reference local 75
+//                                                   ^^^^^ syntax IdentifierFunction
+                          });
+                          tag = Tag {
+//                        ^^^ reference write local 68
+//                              ^^^ syntax IdentifierType
+                              line_range,
+//                            ^^^^^^^^^^ reference local 75
+                              span,
+//                            ^^^^ reference local 74
+                              utf16_column_range,
+//                            ^^^^^^^^^^^^^^^^^^ reference local 81
+                              range,
+//                            ^^^^^ reference local 73
+                              name_range,
+//                            ^^^^^^^^^^ reference local 67
+                              docs,
+//                            ^^^^ reference local 71
+                              is_definition,
+//                            ^^^^^^^^^^^^^ reference local 55
+                              syntax_type_id,
+//                            ^^^^^^^^^^^^^^ reference local 54
+                          };
+                      } else if is_ignored {
+//                      ^^^^ syntax Keyword
+//                           ^^ syntax Keyword
+//                              ^^^^^^^^^^ reference local 57
+                          tag = Tag::ignored(name_range);
+//                        ^^^ reference write local 68
+//                                   ^^^^^^^ syntax IdentifierFunction
+//                                           ^^^^^^^^^^ reference read local 67
+                      } else {
+//                      ^^^^ syntax Keyword
+                          continue;
+//                        ^^^^^^^^ syntax Keyword
+                      }
+  
+                      // Only create one tag per node. The tag queue is sorted by node position
+//                    ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+                      // to allow for fast lookup.
+//                    ^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+                      match self.tag_queue.binary_search_by_key(
+//                    ^^^^^ syntax Keyword
+//                          ^^^^ syntax IdentifierBuiltin
+//                                         ^^^^^^^^^^^^^^^^^^^^ syntax IdentifierFunction
+                          &(tag.name_range.end, tag.name_range.start),
+//                          ^^^ reference local 68
+//                                              ^^^ reference local 68
+                          |(tag, _)| (tag.name_range.end, tag.name_range.start),
+//                          ^^^ definition local 95
+//                                    ^^^ reference local 95
+//                                                        ^^^ reference local 95
+                      ) {
+                          Ok(i) => {
+//                           ^ definition local 96
+                              let (existing_tag, pattern_index) = &mut self.tag_queue[i];
+//                            ^^^ syntax Keyword
+//                                 ^^^^^^^^^^^^ definition local 97
+//                                               ^^^^^^^^^^^^^ definition local 98
+//                                                                 ^^^ syntax Keyword
+//                                                                     ^^^^ syntax IdentifierBuiltin
+//                                                                                    ^ reference local 96
+                              if *pattern_index > mat.pattern_index {
+//                            ^^ syntax Keyword
+//                                ^^^^^^^^^^^^^ reference local 98
+//                                                ^^^ reference local 49
+                                  *pattern_index = mat.pattern_index;
+//                                 ^^^^^^^^^^^^^ reference local 98
+//                                                 ^^^ reference local 49
+                                  *existing_tag = tag;
+//                                 ^^^^^^^^^^^^ reference local 97
+//                                                ^^^ reference read local 68
+                              }
+                          }
+                          Err(i) => self.tag_queue.insert(i, (tag, mat.pattern_index)),
+//                            ^ definition local 99
+//                                  ^^^^ syntax IdentifierBuiltin
+//                                                 ^^^^^^ syntax IdentifierFunction
+//                                                        ^ reference read local 99
+//                                                            ^^^ reference local 68
+//                                                                 ^^^ reference local 49
+                      }
+                  }
+              }
+              // If there are no more matches, then drain the queue.
+//            ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+              else if !self.tag_queue.is_empty() {
+//            ^^^^ syntax Keyword
+//                 ^^ syntax Keyword
+//                     ^^^^ syntax IdentifierBuiltin
+//                                    ^^^^^^^^ syntax IdentifierFunction
+                  return Some(Ok(self.tag_queue.remove(0).0));
+//                ^^^^^^ syntax Keyword
+//                       ^^^^ syntax IdentifierFunction
+//                            ^^ syntax IdentifierFunction
+//                               ^^^^ syntax IdentifierBuiltin
+//                                              ^^^^^^ syntax IdentifierFunction
+//                                                     ^ syntax NumericLiteral
+//                                                        ^ syntax NumericLiteral
+              } else {
+//              ^^^^ syntax Keyword
+                  return None;
+//                ^^^^^^ syntax Keyword
+              }
+          }
+      }
+  }
+  
+  impl Tag {
+//^^^^ syntax Keyword
+//     ^^^ reference scip-ctags Tag# IdentifierType
+      fn ignored(name_range: Range<usize>) -> Self {
+//    ^^ syntax Keyword
+//       ^^^^^^^ definition scip-ctags Tag#ignored(). IdentifierFunctionDefinition
+//               ^^^^^^^^^^ definition local 100 IdentifierParameter
+//                           ^^^^^ syntax IdentifierType
+//                                 ^^^^^ syntax IdentifierBuiltinType
+//                                            ^^^^ syntax IdentifierType
+          Tag {
+//        ^^^ syntax IdentifierType
+              name_range,
+//            ^^^^^^^^^^ reference local 100
+              line_range: 0..0,
+//                        ^ syntax NumericLiteral
+//                           ^ syntax NumericLiteral
+              span: Point::new(0, 0)..Point::new(0, 0),
+//                         ^^^ syntax IdentifierFunction
+//                             ^ syntax NumericLiteral
+//                                ^ syntax NumericLiteral
+//                                           ^^^ syntax IdentifierFunction
+//                                               ^ syntax NumericLiteral
+//                                                  ^ syntax NumericLiteral
+              utf16_column_range: 0..0,
+//                                ^ syntax NumericLiteral
+//                                   ^ syntax NumericLiteral
+              range: usize::MAX..usize::MAX,
+              docs: None,
+              is_definition: false,
+//                           ^^^^^ syntax BooleanLiteral
+              syntax_type_id: 0,
+//                            ^ syntax NumericLiteral
+          }
+      }
+  
+      fn is_ignored(&self) -> bool {
+//    ^^ syntax Keyword
+//       ^^^^^^^^^^ definition scip-ctags Tag#is_ignored(). IdentifierFunctionDefinition
+//                   ^^^^ syntax IdentifierBuiltin
+//                            ^^^^ syntax IdentifierBuiltinType
+          self.range.start == usize::MAX
+//        ^^^^ syntax IdentifierBuiltin
+      }
+  }
+  
+  fn line_range(
+//^^ syntax Keyword
+//   ^^^^^^^^^^ definition scip-ctags line_range(). IdentifierFunctionDefinition
+      text: &[u8],
+//    ^^^^ definition local 101 IdentifierParameter
+//            ^^ syntax IdentifierBuiltinType
+      start_byte: usize,
+//    ^^^^^^^^^^ definition local 102 IdentifierParameter
+//                ^^^^^ syntax IdentifierBuiltinType
+      start_point: Point,
+//    ^^^^^^^^^^^ definition local 103 IdentifierParameter
+//                 ^^^^^ syntax IdentifierType
+      max_line_len: usize,
+//    ^^^^^^^^^^^^ definition local 104 IdentifierParameter
+//                  ^^^^^ syntax IdentifierBuiltinType
+  ) -> Range<usize> {
+//     ^^^^^ syntax IdentifierType
+//           ^^^^^ syntax IdentifierBuiltinType
+      // Trim leading whitespace
+//    ^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+      let mut line_start_byte = start_byte - start_point.column;
+//    ^^^ syntax Keyword
+//        ^^^ syntax Keyword
+//            ^^^^^^^^^^^^^^^ definition local 105
+//                              ^^^^^^^^^^ reference read local 102
+//                                           ^^^^^^^^^^^ reference local 103
+      while line_start_byte < text.len() && text[line_start_byte].is_ascii_whitespace() {
+//    ^^^^^ syntax Keyword
+//          ^^^^^^^^^^^^^^^ reference read local 105
+//                            ^^^^ reference local 101
+//                                 ^^^ syntax IdentifierFunction
+//                                          ^^^^ reference local 101
+//                                               ^^^^^^^^^^^^^^^ reference local 105
+//                                                                ^^^^^^^^^^^^^^^^^^^ syntax IdentifierFunction
+          line_start_byte += 1;
+//        ^^^^^^^^^^^^^^^ reference write read local 105
+//                           ^ syntax NumericLiteral
+      }
+  
+      let max_line_len = max_line_len.min(text.len() - line_start_byte);
+//    ^^^ syntax Keyword
+//        ^^^^^^^^^^^^ definition local 106
+//                       ^^^^^^^^^^^^ reference local 106
+//                                    ^^^ syntax IdentifierFunction
+//                                        ^^^^ reference local 101
+//                                             ^^^ syntax IdentifierFunction
+//                                                     ^^^^^^^^^^^^^^^ reference read local 105
+      let text_after_line_start = &text[line_start_byte..(line_start_byte + max_line_len)];
+//    ^^^ syntax Keyword
+//        ^^^^^^^^^^^^^^^^^^^^^ definition local 107
+//                                 ^^^^ reference local 101
+//                                      ^^^^^^^^^^^^^^^ reference local 105
+//                                                        ^^^^^^^^^^^^^^^ reference read local 105
+//                                                                          ^^^^^^^^^^^^ reference read local 106
+      let line_len = if let Some(len) = memchr(b'\n', text_after_line_start) {
+//    ^^^ syntax Keyword
+//        ^^^^^^^^ definition local 108
+//                   ^^ syntax Keyword
+//                      ^^^ syntax Keyword
+//                               ^^^ definition local 110
+//                                      ^^^^^^ syntax IdentifierFunction
+//                                             ^^^^^ syntax CharacterLiteral
+//                                                    ^^^^^^^^^^^^^^^^^^^^^ reference read local 107
+          len
+//        ^^^ reference read local 110
+      } else if let Err(e) = str::from_utf8(text_after_line_start) {
+//      ^^^^ syntax Keyword
+//           ^^ syntax Keyword
+//              ^^^ syntax Keyword
+//                      ^ definition local 111
+//                                ^^^^^^^^^ syntax IdentifierFunction
+//                                          ^^^^^^^^^^^^^^^^^^^^^ reference read local 107
+          e.valid_up_to()
+//        ^ reference local 111
+//          ^^^^^^^^^^^ syntax IdentifierFunction
+      } else {
+//      ^^^^ syntax Keyword
+          max_line_len
+//        ^^^^^^^^^^^^ reference read local 106
+      };
+  
+      // Trim trailing whitespace
+//    ^^^^^^^^^^^^^^^^^^^^^^^^^^^ syntax Comment
+      let mut line_end_byte = line_start_byte + line_len;
+//    ^^^ syntax Keyword
+//        ^^^ syntax Keyword
+//            ^^^^^^^^^^^^^ definition local 109
+//                            ^^^^^^^^^^^^^^^ reference read local 105
+//                                              ^^^^^^^^ reference read local 108
+      while line_end_byte > line_start_byte && text[line_end_byte - 1].is_ascii_whitespace() {
+//    ^^^^^ syntax Keyword
+//          ^^^^^^^^^^^^^ reference read local 109
+//                          ^^^^^^^^^^^^^^^ reference read local 105
+//                                             ^^^^ reference local 101
+//                                                  ^^^^^^^^^^^^^ reference read local 109
+//                                                                  ^ syntax NumericLiteral
+//                                                                     ^^^^^^^^^^^^^^^^^^^ syntax IdentifierFunction
+          line_end_byte -= 1;
+//        ^^^^^^^^^^^^^ reference write read local 109
+//                         ^ syntax NumericLiteral
+      }
+  
+      line_start_byte..line_end_byte
+//    ^^^^^^^^^^^^^^^ reference local 105
+//                     ^^^^^^^^^^^^^ reference local 109
+  }
+  
+  fn utf16_len(bytes: &[u8]) -> usize {
+//^^ syntax Keyword
+//   ^^^^^^^^^ definition scip-ctags utf16_len(). IdentifierFunctionDefinition
+//             ^^^^^ definition local 112 IdentifierParameter
+//                      ^^ syntax IdentifierBuiltinType
+//                              ^^^^^ syntax IdentifierBuiltinType
+      LossyUtf8::new(bytes)
+//               ^^^ syntax IdentifierFunction
+//                   ^^^^^ reference read local 112
+          .flat_map(|chunk| chunk.chars().map(char::len_utf16))
+//         ^^^^^^^^ syntax IdentifierFunction
+//                   ^^^^^ definition local 113
+//                          ^^^^^ reference local 113
+//                                ^^^^^ syntax IdentifierFunction
+//                                        ^^^ syntax IdentifierFunction
+          .sum()
+//         ^^^ syntax IdentifierFunction
+  }
+  
+  #[cfg(test)]
+//^^^^^^^^^^^^ syntax IdentifierAttribute
+  mod tests {
+//^^^ syntax Keyword
+//    ^^^^^ definition scip-ctags tests/ IdentifierNamespace
+      use super::*;
+//    ^^^ syntax Keyword
+//        ^^^^^ syntax Keyword
+  
+      #[test]
+//    ^^^^^^^ syntax IdentifierAttribute
+      fn test_get_line() {
+//    ^^ syntax Keyword
+//       ^^^^^^^^^^^^^ definition scip-ctags tests/test_get_line(). IdentifierFunctionDefinition
+          let text = "abc\ndefg❤hij\nklmno".as_bytes();
+//        ^^^ syntax Keyword
+//            ^^^^ definition local 114
+//                   ^^^^^^^^^^^^^^^^^^^^^^ syntax StringLiteral
+//                       ^^ syntax StringLiteralEscape
+//                                 ^^ syntax StringLiteralEscape
+//                                          ^^^^^^^^ syntax IdentifierFunction
+          assert_eq!(line_range(text, 5, Point::new(1, 1), 30), 4..14);
+//        ^^^^^^^^^ syntax IdentifierMacro
+//                              ^^^^ reference local 114
+//                                    ^ syntax NumericLiteral
+//                                                  ^ syntax NumericLiteral
+//                                                     ^ syntax NumericLiteral
+//                                                         ^^ syntax NumericLiteral
+//                                                              ^ syntax NumericLiteral
+//                                                                 ^^ syntax NumericLiteral
+          assert_eq!(line_range(text, 5, Point::new(1, 1), 6), 4..8);
+//        ^^^^^^^^^ syntax IdentifierMacro
+//                              ^^^^ reference local 114
+//                                    ^ syntax NumericLiteral
+//                                                  ^ syntax NumericLiteral
+//                                                     ^ syntax NumericLiteral
+//                                                         ^ syntax NumericLiteral
+//                                                             ^ syntax NumericLiteral
+//                                                                ^ syntax NumericLiteral
+          assert_eq!(line_range(text, 17, Point::new(2, 2), 30), 15..20);
+//        ^^^^^^^^^ syntax IdentifierMacro
+//                              ^^^^ reference local 114
+//                                    ^^ syntax NumericLiteral
+//                                                   ^ syntax NumericLiteral
+//                                                      ^ syntax NumericLiteral
+//                                                          ^^ syntax NumericLiteral
+//                                                               ^^ syntax NumericLiteral
+//                                                                   ^^ syntax NumericLiteral
+          assert_eq!(line_range(text, 17, Point::new(2, 2), 4), 15..19);
+//        ^^^^^^^^^ syntax IdentifierMacro
+//                              ^^^^ reference local 114
+//                                    ^^ syntax NumericLiteral
+//                                                   ^ syntax NumericLiteral
+//                                                      ^ syntax NumericLiteral
+//                                                          ^ syntax NumericLiteral
+//                                                              ^^ syntax NumericLiteral
+//                                                                  ^^ syntax NumericLiteral
+      }
+  
+      #[test]
+//    ^^^^^^^ syntax IdentifierAttribute
+      fn test_get_line_trims() {
+//    ^^ syntax Keyword
+//       ^^^^^^^^^^^^^^^^^^^ definition scip-ctags tests/test_get_line_trims(). IdentifierFunctionDefinition
+          let text = b"   foo\nbar\n";
+//        ^^^ syntax Keyword
+//            ^^^^ definition local 115
+//                   ^^^^^^^^^^^^^^^^ syntax StringLiteral
+//                           ^^ syntax StringLiteralEscape
+//                                ^^ syntax StringLiteralEscape
+          assert_eq!(line_range(text, 0, Point::new(0, 0), 10), 3..6);
+//        ^^^^^^^^^ syntax IdentifierMacro
+//                              ^^^^ reference local 115
+//                                    ^ syntax NumericLiteral
+//                                                  ^ syntax NumericLiteral
+//                                                     ^ syntax NumericLiteral
+//                                                         ^^ syntax NumericLiteral
+//                                                              ^ syntax NumericLiteral
+//                                                                 ^ syntax NumericLiteral
+  
+          let text = b"\t func foo \nbar\n";
+//        ^^^ syntax Keyword
+//            ^^^^ reference local 115
+//                   ^^^^^^^^^^^^^^^^^^^^^^ syntax StringLiteral
+//                     ^^ syntax StringLiteralEscape
+//                                 ^^ syntax StringLiteralEscape
+//                                      ^^ syntax StringLiteralEscape
+          assert_eq!(line_range(text, 0, Point::new(0, 0), 10), 2..10);
+//        ^^^^^^^^^ syntax IdentifierMacro
+//                              ^^^^ reference local 115
+//                                    ^ syntax NumericLiteral
+//                                                  ^ syntax NumericLiteral
+//                                                     ^ syntax NumericLiteral
+//                                                         ^^ syntax NumericLiteral
+//                                                              ^ syntax NumericLiteral
+//                                                                 ^^ syntax NumericLiteral
+  
+          let r = line_range(text, 0, Point::new(0, 0), 14);
+//        ^^^ syntax Keyword
+//            ^ definition local 116
+//                ^^^^^^^^^^ syntax IdentifierFunction
+//                           ^^^^ reference read local 115
+//                                 ^ syntax NumericLiteral
+//                                           ^^^ syntax IdentifierFunction
+//                                               ^ syntax NumericLiteral
+//                                                  ^ syntax NumericLiteral
+//                                                      ^^ syntax NumericLiteral
+          assert_eq!(r, 2..10);
+//        ^^^^^^^^^ syntax IdentifierMacro
+//                   ^ reference local 116
+//                      ^ syntax NumericLiteral
+//                         ^^ syntax NumericLiteral
+          assert_eq!(str::from_utf8(&text[r]).unwrap_or(""), "func foo");
+//        ^^^^^^^^^ syntax IdentifierMacro
+//                   ^^^ syntax IdentifierBuiltinType
+//                                   ^^^^ reference local 115
+//                                        ^ reference local 116
+//                                                      ^^ syntax StringLiteral
+//                                                           ^^^^^^^^^^ syntax StringLiteral
+  
+          let r = line_range(text, 12, Point::new(1, 0), 14);
+//        ^^^ syntax Keyword
+//            ^ reference local 116
+//                ^^^^^^^^^^ syntax IdentifierFunction
+//                           ^^^^ reference read local 115
+//                                 ^^ syntax NumericLiteral
+//                                            ^^^ syntax IdentifierFunction
+//                                                ^ syntax NumericLiteral
+//                                                   ^ syntax NumericLiteral
+//                                                       ^^ syntax NumericLiteral
+          assert_eq!(r, 12..15);
+//        ^^^^^^^^^ syntax IdentifierMacro
+//                   ^ reference local 116
+//                      ^^ syntax NumericLiteral
+//                          ^^ syntax NumericLiteral
+          assert_eq!(str::from_utf8(&text[r]).unwrap_or(""), "bar");
+//        ^^^^^^^^^ syntax IdentifierMacro
+//                   ^^^ syntax IdentifierBuiltinType
+//                                   ^^^^ reference local 115
+//                                        ^ reference local 116
+//                                                      ^^ syntax StringLiteral
+//                                                           ^^^^^ syntax StringLiteral
+      }
+  }